@@ -1,4 +1,6 @@
 import { declareCommand } from '@/api/command'
+import { DatabaseError } from '@/api/commands/database'
+import { AppError, isAppError } from '@/api/error'
 import { ColorScheme } from '@/contexts/Theme'
 
 export type Environment = Readonly<{
@@ -49,6 +51,37 @@ export function deviceSpec (env: Environment) {
   }
 }
 
+// See: tauri/src/database/migrations/mod.rs
+
+export const NamedMigrationError = 'MigrationError' as const
+export type NamedMigrationError = typeof NamedMigrationError
+
+export enum MigrationErrorKind {
+  ChecksumMismatch = 'ChecksumMismatch',
+  Unrecorded = 'Unrecorded',
+  PartiallyApplied = 'PartiallyApplied',
+}
+
+export type MigrationError = AppError<NamedMigrationError,
+  | {
+    kind: MigrationErrorKind.ChecksumMismatch
+    version: number
+    name: string
+    expected: string
+    actual: string
+  }
+  | {
+    kind: MigrationErrorKind.Unrecorded | MigrationErrorKind.PartiallyApplied
+    version: number
+    name: string
+  }
+>
+
+export function isMigrationError (error: unknown): error is MigrationError {
+  return isAppError(error)
+    && error.name === NamedMigrationError
+}
+
 export interface PickFileArgs extends Record<string, unknown> {
   title?: string | null
   directory?: string | null
@@ -71,6 +104,10 @@ const AppCommands = {
   environment:
     declareCommand<undefined, Environment>('environment', true),
 
+  /** The database migrations failed on startup, the database is kept as is. */
+  databaseMigrationError:
+    declareCommand<undefined, MigrationError | DatabaseError | null>('database_migration_error'),
+
   changeColorScheme:
     declareCommand<{ value: ColorScheme | null }>('change_color_scheme'),
 
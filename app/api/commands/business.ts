@@ -75,6 +75,7 @@ export enum GachaUrlRequestErrorKind {
   VisitTooFrequently = 'VisitTooFrequently',
   UnexpectedResponse = 'UnexpectedResponse',
   ReachedMaxAttempts = 'ReachedMaxAttempts',
+  Cancelled = 'Cancelled',
 }
 
 export type GachaUrlRequestError = AppError<NamedGachaUrlRequestError,
@@ -99,7 +100,9 @@ export type GachaUrlRequestError = AppError<NamedGachaUrlRequestError,
     message: string
   }
   | {
-    kind: GachaUrlRequestErrorKind.ReachedMaxAttempts
+    kind:
+      | GachaUrlRequestErrorKind.ReachedMaxAttempts
+      | GachaUrlRequestErrorKind.Cancelled
   }
 >
 
@@ -112,11 +115,50 @@ export function isGachaUrlRequestError (error: unknown): error is GachaUrlReques
 
 // #region: Validate Uid
 
+export const NamedUidError = 'UidError' as const
+export type NamedUidError = typeof NamedUidError
+
+export enum UidErrorKind {
+  Zero = 'Zero',
+  DigitCount = 'DigitCount',
+  UnknownRegionDigit = 'UnknownRegionDigit',
+}
+
+export interface UidCandidate {
+  gameBiz: string
+  region: string
+  server: 'official' | 'oversea'
+}
+
+export type UidErrorDetails = (
+  | {
+    kind: UidErrorKind.Zero
+  }
+  | {
+    kind: UidErrorKind.DigitCount
+    digits: number
+  }
+  | {
+    kind: UidErrorKind.UnknownRegionDigit
+    digit: number
+  }
+) & {
+  value: Account['uid']
+  candidates: UidCandidate[]
+}
+
+export type UidError = AppError<NamedUidError, UidErrorDetails>
+
+export function isUidError (error: unknown): error is UidError {
+  return isAppError(error)
+    && error.name === NamedUidError
+}
+
 export type ValidateUidArgs
   = Pick<Account, 'business' | 'uid'>
 
 export type ValidateUid
-  = Command<ValidateUidArgs, 'official' | 'oversea' | null>
+  = Command<ValidateUidArgs, 'official' | 'oversea'>
 
 // #endregion
 
@@ -138,6 +180,7 @@ export type LocateDataFolderError = AppError<NamedLocateDataFolderError,
     kind: LocateDataFolderErrorKind.InvalidUid
     business: AccountBusiness
     value: Account['uid']
+    cause: UidErrorDetails
   }
   | {
     kind: LocateDataFolderErrorKind.UnityLogNotFound
@@ -203,6 +246,7 @@ export type GachaUrlError = AppError<NamedGachaUrlError,
     kind: GachaUrlErrorKind.InvalidUid
     business: AccountBusiness
     value: Account['uid']
+    cause: UidErrorDetails
   }
   | {
     kind: GachaUrlErrorKind.EmptyData
@@ -280,6 +324,8 @@ export type NamedGachaFetcherError = typeof NamedGachaFetcherError
 export enum GachaFetcherErrorKind {
   InvalidUid = 'InvalidUid',
   MetadataEntry = 'MetadataEntry',
+  AmbiguousMetadataEntry = 'AmbiguousMetadataEntry',
+  Cancelled = 'Cancelled',
 }
 
 export type GachaFetcherError = AppError<NamedGachaFetcherError,
@@ -287,6 +333,7 @@ export type GachaFetcherError = AppError<NamedGachaFetcherError,
     kind: GachaFetcherErrorKind.InvalidUid
     business: AccountBusiness
     value: Account['uid']
+    cause: UidErrorDetails
   }
   | {
     kind: GachaFetcherErrorKind.MetadataEntry
@@ -294,6 +341,17 @@ export type GachaFetcherError = AppError<NamedGachaFetcherError,
     lang: GachaRecord<AccountBusiness>['lang']
     itemName: GachaRecord<AccountBusiness>['itemName']
   }
+  | {
+    kind: GachaFetcherErrorKind.AmbiguousMetadataEntry
+    business: AccountBusiness
+    lang: GachaRecord<AccountBusiness>['lang']
+    itemName: GachaRecord<AccountBusiness>['itemName']
+    candidates: GachaRecord<AccountBusiness>['itemId'][]
+  }
+  | {
+    kind: GachaFetcherErrorKind.Cancelled
+    changes: number
+  }
 >
 
 export function isGachaFetcherError (error: unknown): error is GachaFetcherError {
@@ -312,6 +370,41 @@ export enum SaveOnConflict {
   Update = 'Update',
 }
 
+export enum SaveOnCancelled {
+  Discard = 'Discard',
+  Save = 'Save',
+}
+
+export type SaveReportKey<T extends AccountBusiness>
+  = Pick<GachaRecord<T>, 'business' | 'uid' | 'id' | 'gachaType'>
+
+export interface SaveReportFieldDiff {
+  field: keyof GachaRecord<AccountBusiness>
+  old: unknown
+  new: unknown
+}
+
+export interface SaveReportDiff<T extends AccountBusiness> extends SaveReportKey<T> {
+  fields: SaveReportFieldDiff[]
+}
+
+export enum SaveReportRejectReason {
+  Duplicate = 'Duplicate',
+}
+
+export interface SaveReportRejected<T extends AccountBusiness> extends SaveReportKey<T> {
+  reason: SaveReportRejectReason
+}
+
+export interface SaveReport<T extends AccountBusiness> {
+  inserted: SaveReportKey<T>[]
+  updated: SaveReportDiff<T>[] // SaveOnConflict.Update
+  unchanged: SaveReportKey<T>[]
+  conflicting: SaveReportDiff<T>[] // SaveOnConflict.Nothing, the existing are kept
+  rejected: SaveReportRejected<T>[]
+  deleted: SaveReportKey<T>[] // SaveToDatabase.FullUpdate
+}
+
 export interface FetchRecordsArgs<T extends AccountBusiness> {
   business: T
   uid: Account['uid']
@@ -320,10 +413,21 @@ export interface FetchRecordsArgs<T extends AccountBusiness> {
   eventChannel: Channel<FetchRecordsEvent>
   saveToDatabase?: SaveToDatabase | null
   saveOnConflict?: SaveOnConflict | null
+  saveOnCancelled?: SaveOnCancelled | null
+  alternateLangs?: string[] | null // Re-fetch with these langs to fill the localized item names
 }
 
 export type FetchRecords
-  = <T extends AccountBusiness> (args: FetchRecordsArgs<T>) => Promise<number>
+  = <T extends AccountBusiness> (args: FetchRecordsArgs<T>) => Promise<SaveReport<T>>
+
+export interface FetchRecordsAbortArgs extends Record<string, unknown> {
+  business: AccountBusiness
+  uid: Account['uid']
+}
+
+// Returns `false` if there is no in-flight fetch of the account.
+export type FetchRecordsAbort
+  = Command<FetchRecordsAbortArgs, boolean>
 
 export enum FetchRecordsEventKind {
   Sleeping = 'Sleeping',
@@ -332,6 +436,8 @@ export enum FetchRecordsEventKind {
   Data = 'Data',
   Completed = 'Completed',
   Finished = 'Finished',
+  Cancelled = 'Cancelled',
+  Alternate = 'Alternate',
 }
 
 export type FetchRecordsEvent
@@ -341,6 +447,8 @@ export type FetchRecordsEvent
     | { [FetchRecordsEventKind.Data]: number }
     | { [FetchRecordsEventKind.Completed]: PrettizedCategory | null }
     | FetchRecordsEventKind.Finished
+    | FetchRecordsEventKind.Cancelled
+    | { [FetchRecordsEventKind.Alternate]: string } // Lang, the progress events are muted
 
 // #endregion
 
@@ -413,6 +521,111 @@ export type LegacyMigration
 
 // #endregion
 
+// #region: Database backups
+
+export const NamedDatabaseBackupError = 'DatabaseBackupError' as const
+export type NamedDatabaseBackupError = typeof NamedDatabaseBackupError
+
+export enum DatabaseBackupErrorKind {
+  Io = 'Io',
+  NotFound = 'NotFound',
+  Invalid = 'Invalid',
+  UnsupportedVersion = 'UnsupportedVersion',
+}
+
+export type DatabaseBackupError = AppError<NamedDatabaseBackupError,
+  | {
+    kind: DatabaseBackupErrorKind.Io
+    cause: string
+  }
+  | {
+    kind: DatabaseBackupErrorKind.NotFound | DatabaseBackupErrorKind.Invalid
+    filename: string
+  }
+  | {
+    kind: DatabaseBackupErrorKind.UnsupportedVersion
+    version: number
+    expected: number
+  }
+>
+
+export function isDatabaseBackupError (error: unknown): error is DatabaseBackupError {
+  return isAppError(error)
+    && error.name === NamedDatabaseBackupError
+}
+
+export enum DatabaseBackupReason {
+  Migration = 'Migration',
+  Import = 'Import',
+  FullUpdate = 'FullUpdate',
+  Restore = 'Restore', // The database before restoring a backup
+  Repair = 'Repair', // The database before repairing
+}
+
+export interface DatabaseBackup {
+  filename: string
+  path: string
+  reason: DatabaseBackupReason
+  createdAt: string
+  size: number
+  version: number // USER_VERSION
+}
+
+export type ListBackups
+  = Command<undefined, DatabaseBackup[]>
+
+export interface RestoreBackupArgs extends Record<string, unknown> {
+  filename: DatabaseBackup['filename']
+}
+
+// Staged, restored on the next startup. Relaunch the app after it.
+export type RestoreBackup
+  = Command<RestoreBackupArgs, DatabaseBackup>
+
+// See: tauri/src/database/integrity.rs
+
+export interface UndecodableRow {
+  table: 'HG_ACCOUNTS' | 'HG_GACHA_RECORDS'
+  rowid: number
+  identity: Record<string, unknown> // Primary key of the row as is
+  reason: string
+}
+
+export interface OrphanedRecords {
+  business: AccountBusiness
+  uid: number
+  records: number
+}
+
+export interface IntegrityReport {
+  corruptions: string[] // PRAGMA integrity_check, empty if ok
+  undecodable: UndecodableRow[]
+  orphaned: OrphanedRecords[]
+}
+
+export type CheckDatabase
+  = Command<undefined, IntegrityReport>
+
+export interface IntegrityRepairOptions {
+  quarantine?: boolean // Move the undecodable rows to the quarantine
+  recreateAccounts?: boolean // Of the orphaned records, with an empty data folder
+}
+
+export interface IntegrityRepairReport {
+  quarantined: number
+  recreatedAccounts: Account[]
+  report: IntegrityReport // After repaired
+}
+
+export interface RepairDatabaseArgs extends Record<string, unknown> {
+  options: IntegrityRepairOptions
+}
+
+export type RepairDatabase
+  = Command<RepairDatabaseArgs, IntegrityRepairReport>
+
+// #endregion
+
 // #region: Converters
 
 export enum UigfVersion {
@@ -758,13 +971,39 @@ export interface ImportRecordsArgs extends Record<string, unknown> {
 }
 
 export type ImportRecords
-  = Command<ImportRecordsArgs, number>
+  = Command<ImportRecordsArgs, SaveReport<AccountBusiness>>
+
+export interface RebaseRecordsTimeArgs extends Record<string, unknown> {
+  business: AccountBusiness
+  uid: number
+  offset: number // Whole hours
+}
+
+export type RebaseRecordsTime
+  = Command<RebaseRecordsTimeArgs, number>
+
+export interface SearchRecordsArgs extends Record<string, unknown> {
+  keyword: string // Item name in any lang, each word as an infix
+}
+
+// The matched records of an account
+export interface SearchRecordsResult<T extends AccountBusiness> {
+  business: T
+  uid: number
+  records: GachaRecord<T>[]
+}
+
+export type SearchRecords
+  = Command<SearchRecordsArgs, SearchRecordsResult<AccountBusiness>[]>
 
 // #endregion
 
 // #region: Commands
 
 const BusinessCommands = {
+  /**
+   * @throws `UidError`
+   */
   validateUid:
     declareCommand('business_validate_uid') as ValidateUid,
 
@@ -810,6 +1049,9 @@ const BusinessCommands = {
   fetchRecords:
     declareCommand('business_fetch_records') as FetchRecords,
 
+  fetchRecordsAbort:
+    declareCommand('business_fetch_records_abort') as FetchRecordsAbort,
+
   /**
    * @throws `DatabaseError`
    * @throws `LegacyMigrationError`
@@ -817,6 +1059,25 @@ const BusinessCommands = {
   legacyMigration:
     declareCommand('business_legacy_migration') as LegacyMigration,
 
+  /** @throws `DatabaseBackupError` */
+  listBackups:
+    declareCommand('business_list_backups') as ListBackups,
+
+  /**
+   * @throws `DatabaseError`
+   * @throws `DatabaseBackupError`
+   */
+  restoreBackup:
+    declareCommand('business_restore_backup') as RestoreBackup,
+
+  /** @throws `DatabaseError` */
+  checkDatabase:
+    declareCommand('business_check_database') as CheckDatabase,
+
+  /** @throws `DatabaseError` */
+  repairDatabase:
+    declareCommand('business_repair_database') as RepairDatabase,
+
   /**
    * @throws `DatabaseError`
    * @throws `UigfError`
@@ -832,6 +1093,17 @@ const BusinessCommands = {
    */
   importRecords:
     declareCommand('business_import_records') as ImportRecords,
+
+  /**
+   * @throws `DatabaseError`
+   * @throws `GachaRecordRebaseError`
+   */
+  rebaseRecordsTime:
+    declareCommand('business_rebase_records_time') as RebaseRecordsTime,
+
+  /** @throws `DatabaseError` */
+  searchRecords:
+    declareCommand('business_search_records') as SearchRecords,
 } as const
 
 Object.freeze(BusinessCommands)
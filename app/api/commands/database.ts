@@ -1,7 +1,9 @@
 import { AppError, isAppError } from '@/api/error'
 import { AccountCommands } from '@/api/schemas/Account'
+import { AuditLogCommands } from '@/api/schemas/AuditLog'
 import { GachaRecordCommands } from '@/api/schemas/GachaRecord'
 import { KeyValuePairCommands } from '@/api/schemas/KeyValuePair'
+import { RecycleBinCommands } from '@/api/schemas/RecycleBin'
 
 export const NamedDatabaseError = 'DatabaseError' as const
 export type NamedDatabaseError = typeof NamedDatabaseError
@@ -29,8 +31,10 @@ export function isDatabaseError (error: unknown): error is DatabaseError {
 
 const DatabaseCommands = {
   account: AccountCommands,
+  auditLog: AuditLogCommands,
   gachaRecord: GachaRecordCommands,
   keyValuePair: KeyValuePairCommands,
+  recycleBin: RecycleBinCommands,
 } as const
 
 Object.freeze(DatabaseCommands)
@@ -9,6 +9,7 @@ export type NamedMetadataError = typeof NamedMetadataError
 export enum MetadataErrorKind {
   Deserialize = 'Deserialize',
   Bake = 'Bake',
+  Compact = 'Compact',
 }
 
 export type MetadataError = AppError<NamedMetadataError,
@@ -20,6 +21,10 @@ export type MetadataError = AppError<NamedMetadataError,
     kind: MetadataErrorKind.Bake
     cause: string
   }
+  | {
+    kind: MetadataErrorKind.Compact
+    cause: string
+  }
 >
 
 export function isMetadataError (error: unknown): error is MetadataError {
@@ -33,6 +38,8 @@ export type NamedMetadataUpdateError = typeof NamedMetadataUpdateError
 export enum MetadataUpdateErrorKind {
   Reqwest = 'Reqwest',
   DownloadedMismatch = 'DownloadedMismatch',
+  InvalidSignature = 'InvalidSignature',
+  MissingSignature = 'MissingSignature',
 }
 
 export type MetadataUpdateError = AppError<NamedMetadataUpdateError,
@@ -43,6 +50,9 @@ export type MetadataUpdateError = AppError<NamedMetadataUpdateError,
   | {
     kind: MetadataUpdateErrorKind.DownloadedMismatch
   }
+  | {
+    kind: MetadataUpdateErrorKind.InvalidSignature | MetadataUpdateErrorKind.MissingSignature
+  }
 >
 
 export function isMetadataUpdateError (error: unknown): error is MetadataUpdateError {
@@ -62,6 +72,68 @@ export type MetadataUpdateResult
     | { [MetadataUpdateKind.Success]: string }
     | null // 'Feature disabled' only
 
+export interface MetadataBanner {
+  gachaType: number
+  gachaId?: number
+  startTime: string
+  endTime: string
+  version?: string
+  upGolden: number[]
+  upPurple: number[]
+}
+
+export interface MetadataDiffItem {
+  itemId: number
+  category: string
+  rankType: number
+}
+
+export interface MetadataDiffRenamed<K> {
+  locale: string
+  key: K
+  oldName: string
+  newName: string
+}
+
+export interface MetadataDiffBanner extends Omit<MetadataBanner, 'gachaId' | 'version'> {
+  gachaId: number | null
+  version: string | null
+}
+
+export interface MetadataBusinessDiff {
+  businessId: AccountBusiness
+  addedLocales: string[]
+  removedLocales: string[]
+  addedItems: MetadataDiffItem[]
+  removedItems: MetadataDiffItem[]
+  changedItems: [MetadataDiffItem, MetadataDiffItem][] // [old, new]
+  renamedItems: MetadataDiffRenamed<number>[]
+  renamedCategories: MetadataDiffRenamed<string>[]
+  addedBanners: MetadataDiffBanner[]
+  removedBanners: MetadataDiffBanner[]
+  changedBanners: [MetadataDiffBanner, MetadataDiffBanner][] // [old, new]
+}
+
+// Only the changed businesses
+export interface MetadataDiff {
+  businesses: MetadataBusinessDiff[]
+}
+
+export interface MetadataOverlayItem {
+  businessId: AccountBusiness
+  itemId: number
+  category: string
+  rankType: number
+  itemName: string
+}
+
+export interface MetadataOverlayReport {
+  items: MetadataOverlayItem[]
+  supersededItems: MetadataOverlayItem[]
+  banners: MetadataBanner[]
+  supersededBanners: MetadataBanner[]
+}
+
 const MetadataCommands = {
   hash:
     declareCommand<undefined, string>('metadata_hash'),
@@ -72,9 +144,19 @@ const MetadataCommands = {
   entries:
     declareCommand<{ business: AccountBusiness, category: ItemCategory }, number[] | null>('metadata_entries'),
 
+  banners:
+    declareCommand<{ business: AccountBusiness, gachaType: number }, MetadataBanner[] | null>('metadata_banners'),
+
+  overlay:
+    declareCommand<undefined, MetadataOverlayReport | null>('metadata_overlay'),
+
   isUpdating:
     declareCommand<undefined, boolean>('metadata_is_updating'),
 
+  // null: Not updated since the startup, or 'Feature disabled'
+  changes:
+    declareCommand<undefined, MetadataDiff | null>('metadata_changes'),
+
   /**
    * @throws `MetadataError`
    * @throws `MetadataUpdateError`
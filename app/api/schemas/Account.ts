@@ -45,6 +45,7 @@ export interface KnownAccountProperties extends JsonProperties {
   gachaUrlCreationTime?: string | null
   lastGachaRecordsUpdated?: string | null
   avatarId?: string | null
+  timezone_override?: number | null // Whole hours (snake_case, read by the backend)
 }
 
 export interface Account {
@@ -0,0 +1,46 @@
+import { Command, declareCommand } from '@/api/command'
+import { AccountBusiness } from './Account'
+import { JsonProperties } from './shared'
+
+// See: tauri/src/database/schemas/audit_log.rs
+
+export enum AuditOperation {
+  Fetch = 'Fetch',
+  FullUpdate = 'FullUpdate',
+  Import = 'Import',
+  LegacyMigration = 'LegacyMigration',
+  DeleteRecords = 'DeleteRecords',
+  CreateAccount = 'CreateAccount',
+  UpdateAccount = 'UpdateAccount',
+  DeleteAccount = 'DeleteAccount',
+}
+
+export interface AuditLog {
+  id: number
+  operation: AuditOperation
+  business: AccountBusiness | null
+  uid: number | null
+  counts: JsonProperties | null
+  source: string | null
+  createdAt: string
+}
+
+export interface FindAuditLogsArgs {
+  business?: AccountBusiness | null
+  uid?: number | null
+  limit: number
+  offset: number
+}
+
+// Newest first
+export type FindAuditLogs
+  = Command<FindAuditLogsArgs, AuditLog[]>
+
+// commands
+export const AuditLogCommands = {
+  /** @throws `DatabaseError` */
+  find:
+    declareCommand('database_find_audit_logs') as FindAuditLogs,
+} as const
+
+Object.freeze(AuditLogCommands)
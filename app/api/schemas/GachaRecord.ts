@@ -51,6 +51,38 @@ export type DeleteGachaRecordsArgs<T extends AccountBusiness>
 export type DeleteGachaRecords
   = <T extends AccountBusiness> (args: DeleteGachaRecordsArgs<T>) => Promise<number>
 
+export enum GachaRecordQueryOrder {
+  Asc = 'Asc',
+  Desc = 'Desc',
+}
+
+// The id is only unique within the gacha type
+export type GachaRecordCursor<T extends AccountBusiness>
+  = Pick<GachaRecord<T>, 'id' | 'gachaType'>
+
+// The null or empty filters match all records
+export interface GachaRecordQuery<T extends AccountBusiness>
+  extends Pick<GachaRecord<T>, 'business' | 'uid'> {
+  gachaTypes?: GachaType<T>[] | null
+  rankTypes?: RankType<T>[] | null
+  itemId?: number | null
+  itemName?: string | null
+  timeFrom?: string | null // Inclusive
+  timeTo?: string | null // Exclusive
+  isUp?: boolean | null
+  after?: GachaRecordCursor<T> | null
+  order?: GachaRecordQueryOrder
+  limit?: number | null
+}
+
+export interface GachaRecordPage<T extends AccountBusiness> {
+  records: GachaRecord<T>[]
+  next: GachaRecordCursor<T> | null // Null if this is the last page
+}
+
+export type QueryGachaRecords
+  = <T extends AccountBusiness> (args: { query: GachaRecordQuery<T> }) => Promise<GachaRecordPage<T>>
+
 // commands
 
 export const GachaRecordCommands = {
@@ -58,6 +90,10 @@ export const GachaRecordCommands = {
   findWithLimit:
     declareCommand('database_find_gacha_records_with_limit') as FindGachaRecordsWithLimit,
 
+  /** @throws `DatabaseError` */
+  query:
+    declareCommand('database_query_gacha_records') as QueryGachaRecords,
+
   /** @throws `DatabaseError` */
   delete:
     declareCommand('database_delete_gacha_records') as DeleteGachaRecords,
@@ -0,0 +1,94 @@
+import { Command, declareCommand } from '@/api/command'
+import { Account, AccountBusiness } from './Account'
+import { GachaRecord } from './GachaRecord'
+
+// See: tauri/src/database/schemas/recycle_bin.rs
+
+// The retention period in days of the recycle bin. (KeyValuePair, default: 30)
+export const KeyRecycleBinRetentionDays = 'HG_RECYCLE_BIN_RETENTION_DAYS' as const
+
+export interface DeletedAccount extends Account {
+  deletedAt: string
+}
+
+export type DeletedGachaRecord<T extends AccountBusiness>
+  = GachaRecord<T> & { deletedAt: string }
+
+// The deleted gacha records of an account
+export interface DeletedGachaRecords {
+  business: AccountBusiness
+  uid: number
+  records: number
+  deletedAt: string // The latest
+}
+
+export type FindDeletedAccounts
+  = Command<undefined, DeletedAccount[]>
+
+export type FindDeletedGachaRecordsGroups
+  = Command<undefined, DeletedGachaRecords[]>
+
+export type FindDeletedGachaRecordsArgs<T extends AccountBusiness>
+  = Pick<GachaRecord<T>, 'business' | 'uid'>
+
+export type FindDeletedGachaRecords
+  = <T extends AccountBusiness> (args: FindDeletedGachaRecordsArgs<T>) => Promise<DeletedGachaRecord<T>[]>
+
+export type RestoreAccountArgs
+  = Pick<Account, 'business' | 'uid'>
+
+// Returns null if the account already exists
+export type RestoreAccount
+  = Command<RestoreAccountArgs, Account | null>
+
+export type RestoreGachaRecordsArgs
+  = Pick<Account, 'business' | 'uid'>
+
+// The existing records are kept
+export type RestoreGachaRecords
+  = Command<RestoreGachaRecordsArgs, number>
+
+export type PurgeDeletedAccountArgs
+  = Pick<Account, 'business' | 'uid'>
+
+export type PurgeDeletedAccount
+  = Command<PurgeDeletedAccountArgs, DeletedAccount | null>
+
+export type PurgeDeletedGachaRecordsArgs
+  = Pick<Account, 'business' | 'uid'>
+
+export type PurgeDeletedGachaRecords
+  = Command<PurgeDeletedGachaRecordsArgs, number>
+
+// commands
+export const RecycleBinCommands = {
+  /** @throws `DatabaseError` */
+  findAccounts:
+    declareCommand('database_find_deleted_accounts') as FindDeletedAccounts,
+
+  /** @throws `DatabaseError` */
+  findGachaRecordsGroups:
+    declareCommand('database_find_deleted_gacha_records_groups') as FindDeletedGachaRecordsGroups,
+
+  /** @throws `DatabaseError` */
+  findGachaRecords:
+    declareCommand('database_find_deleted_gacha_records') as FindDeletedGachaRecords,
+
+  /** @throws `DatabaseError` */
+  restoreAccount:
+    declareCommand('database_restore_account') as RestoreAccount,
+
+  /** @throws `DatabaseError` */
+  restoreGachaRecords:
+    declareCommand('database_restore_gacha_records') as RestoreGachaRecords,
+
+  /** @throws `DatabaseError` */
+  purgeAccount:
+    declareCommand('database_purge_deleted_account') as PurgeDeletedAccount,
+
+  /** @throws `DatabaseError` */
+  purgeGachaRecords:
+    declareCommand('database_purge_deleted_gacha_records') as PurgeDeletedGachaRecords,
+} as const
+
+Object.freeze(RecycleBinCommands)
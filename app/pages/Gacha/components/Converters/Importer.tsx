@@ -188,12 +188,14 @@ function useImporter ({
 
     let changes: number
     try {
-      changes = await BusinessCommands.importRecords({
+      const report = await BusinessCommands.importRecords({
         input: file,
         reader,
         saveOnConflict,
         progressChannel,
       })
+
+      changes = report.inserted.length + report.updated.length
     } catch (error) {
       produceState((draft) => {
         draft.error = errorTrans(t, error)
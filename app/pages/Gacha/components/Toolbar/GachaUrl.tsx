@@ -276,6 +276,9 @@ function stringifyFetcherEvent (
   } else if (FetchRecordsEventKind.Completed in event) {
     subkey = FetchRecordsEventKind.Completed
     options = { value: event.Completed, keyof }
+  } else if (FetchRecordsEventKind.Alternate in event) {
+    subkey = FetchRecordsEventKind.Alternate
+    options = { value: event.Alternate }
   } else {
     // HACK: should never reach here
     throw new Error('unreachable')
@@ -393,14 +396,15 @@ function useUserAction (language: Language | string, t: TFunction) {
       loading: {
         title: t('Toolbar.GachaUrl.Fetch.Loading', { keyof: business.keyof }),
       },
-      success (changes) {
-        // Positive numbers are added, negative numbers are deleted
-        changes ??= 0
-        const body = changes >= 0 ? 'AddedBody' : 'DeletedBody'
+      success (report) {
+        // Added first, deleted only if nothing is added
+        const added = (report?.inserted.length ?? 0) + (report?.updated.length ?? 0)
+        const deleted = report?.deleted.length ?? 0
+        const body = added > 0 || deleted === 0 ? 'AddedBody' : 'DeletedBody'
         return {
           title: t('Toolbar.GachaUrl.Fetch.Success.Title', { keyof: business.keyof }),
           body: t(`Toolbar.GachaUrl.Fetch.Success.${body}`, {
-            changes: Math.abs(changes),
+            changes: body === 'AddedBody' ? added : deleted,
           }),
           timeout: DefaultNotifierTimeouts.success * 2,
           dismissible: true,
@@ -419,7 +423,10 @@ function useUserAction (language: Language | string, t: TFunction) {
     // Fetch records
     let changes: number
     try {
-      changes = await promise ?? 0
+      const report = await promise
+      changes = report
+        ? report.inserted.length + report.updated.length + report.deleted.length
+        : 0
     } catch (error) {
       if (isGachaUrlRequestError(error) && error.details.kind === GachaUrlRequestErrorKind.AuthkeyTimeout) {
         // Expired, remove fields
@@ -3,7 +3,7 @@ import { SubmitHandler, UseControllerProps, UseControllerReturn, useController,
 import { Button, Field, FieldProps, Input, InputProps, Textarea, TextareaProps, makeStyles, tokens } from '@fluentui/react-components'
 import { CursorHoverRegular, FolderSearchRegular, PersonTagRegular } from '@fluentui/react-icons'
 import { produce } from 'immer'
-import BusinessCommands, { DataFolder, LocateDataFolderFactory, LocateDataFolderFactoryKind } from '@/api/commands/business'
+import BusinessCommands, { DataFolder, LocateDataFolderFactory, LocateDataFolderFactoryKind, isUidError } from '@/api/commands/business'
 import errorTrans from '@/api/errorTrans'
 import { Account, AccountBusiness, CreateAccountArgs, UpdateAccountDataFolderAndPropertiesArgs } from '@/api/schemas/Account'
 import { WithTrans, withTrans } from '@/i18n'
@@ -221,17 +221,26 @@ export default withTrans.GachaPage(function UpsertAccountForm (
             }
 
             const uid = value && Number(value)
-            const validated = uid && isSafeU32(uid)
-              ? await BusinessCommands.validateUid({
-                  business,
-                  uid,
-                })
-              : null
-
-            if (!validated) {
+            if (!uid || !isSafeU32(uid)) {
               return tForm('Uid.Pattern')
             }
 
+            try {
+              await BusinessCommands.validateUid({ business, uid })
+            } catch (error) {
+              const message = errorTrans(t, error)
+              if (!isUidError(error) || !error.details.candidates.length) {
+                return message
+              }
+
+              // The probable regions of the ambiguous uid
+              const candidates = error.details.candidates
+                .map((candidate) => candidate.region)
+                .join(', ')
+
+              return `${message} ${tForm('Uid.Candidates', { candidates })}`
+            }
+
             if (accounts.find((el) => el.uid === uid)) {
               return tForm('Uid.Exists')
             }
@@ -0,0 +1,155 @@
+import { useCallback, useState } from 'react'
+import { Body1, Button, Caption1, MessageBar, MessageBarBody, MessageBarTitle, Spinner, Title3, makeStyles, tokens } from '@fluentui/react-components'
+import { ArrowClockwiseRegular, FolderOpenRegular, HistoryRegular } from '@fluentui/react-icons'
+import { useQuery } from '@tanstack/react-query'
+import { relaunch } from '@tauri-apps/plugin-process'
+import AppCommands, { MigrationError } from '@/api/commands/app'
+import BusinessCommands, { DatabaseBackup } from '@/api/commands/business'
+import { DatabaseError } from '@/api/commands/database'
+import errorTrans from '@/api/errorTrans'
+import { WithTrans, withTrans } from '@/i18n'
+
+const useStyles = makeStyles({
+  root: {
+    display: 'flex',
+    flexDirection: 'column',
+    rowGap: tokens.spacingVerticalL,
+    padding: tokens.spacingVerticalL,
+    backgroundColor: tokens.colorNeutralBackgroundAlpha,
+    borderRadius: tokens.borderRadiusMedium,
+    boxShadow: tokens.shadow2,
+  },
+  backups: {
+    display: 'flex',
+    flexDirection: 'column',
+    rowGap: tokens.spacingVerticalS,
+  },
+  backup: {
+    display: 'flex',
+    flexDirection: 'row',
+    alignItems: 'center',
+    columnGap: tokens.spacingHorizontalM,
+    padding: tokens.spacingVerticalS,
+    backgroundColor: tokens.colorNeutralBackground3,
+    borderRadius: tokens.borderRadiusMedium,
+  },
+  backupInfo: {
+    display: 'flex',
+    flexDirection: 'column',
+    flex: '1 0 auto',
+  },
+  actions: {
+    display: 'inline-flex',
+    flexDirection: 'row',
+    columnGap: tokens.spacingHorizontalM,
+  },
+})
+
+interface MigrationRecoveryProps {
+  error: MigrationError | DatabaseError
+}
+
+// The database migrations failed on startup, the database is kept as is.
+// Offer to restore a backup instead of the pages, which would fail on the database.
+export default withTrans.RootPage(function MigrationRecovery (
+  { i18n, t, error }: WithTrans & MigrationRecoveryProps,
+) {
+  const styles = useStyles()
+  const backupsQuery = useQuery({
+    queryKey: ['MigrationRecovery', 'Backups'],
+    queryFn: () => BusinessCommands.listBackups(),
+  })
+
+  const [restored, setRestored] = useState<DatabaseBackup | null>(null)
+  const [restoreError, setRestoreError] = useState<unknown>(null)
+  const [busy, setBusy] = useState(false)
+
+  const handleRestore = useCallback(async (filename: DatabaseBackup['filename']) => {
+    setBusy(true)
+    setRestoreError(null)
+    try {
+      setRestored(await BusinessCommands.restoreBackup({ filename }))
+    } catch (error) {
+      setRestoreError(error)
+    } finally {
+      setBusy(false)
+    }
+  }, [])
+
+  return (
+    <div className={styles.root}>
+      <Title3>{t('MigrationRecovery.Title')}</Title3>
+      <MessageBar intent="error" layout="multiline">
+        <MessageBarBody>
+          <MessageBarTitle>{t('MigrationRecovery.Error')}</MessageBarTitle>
+          {errorTrans(t, error)}
+        </MessageBarBody>
+      </MessageBar>
+      <Body1>{t('MigrationRecovery.Subtitle')}</Body1>
+      {restored
+        ? (
+            <MessageBar intent="success" layout="multiline">
+              <MessageBarBody>
+                <MessageBarTitle>{t('MigrationRecovery.Restored', { filename: restored.filename })}</MessageBarTitle>
+                {t('MigrationRecovery.Relaunch.Subtitle')}
+              </MessageBarBody>
+            </MessageBar>
+          )
+        : restoreError
+          ? (
+              <MessageBar intent="error" layout="multiline">
+                <MessageBarBody>
+                  {errorTrans(t, restoreError)}
+                </MessageBarBody>
+              </MessageBar>
+            )
+          : null}
+      <div className={styles.backups}>
+        {backupsQuery.isLoading && <Spinner size="small" />}
+        {backupsQuery.isError && (
+          <Caption1>{errorTrans(t, backupsQuery.error)}</Caption1>
+        )}
+        {backupsQuery.isSuccess && !backupsQuery.data.length && (
+          <Caption1>{t('MigrationRecovery.Empty')}</Caption1>
+        )}
+        {backupsQuery.data?.map((backup) => (
+          <div key={backup.filename} className={styles.backup}>
+            <div className={styles.backupInfo}>
+              <Body1>{backup.filename}</Body1>
+              <Caption1>
+                {t('MigrationRecovery.Backup', {
+                  reason: backup.reason,
+                  createdAt: i18n.dayjs(backup.createdAt).format('LLLL'),
+                  version: backup.version,
+                })}
+              </Caption1>
+            </div>
+            <Button
+              icon={<HistoryRegular />}
+              disabled={busy || !!restored}
+              onClick={() => handleRestore(backup.filename)}
+            >
+              {t('MigrationRecovery.Restore')}
+            </Button>
+          </div>
+        ))}
+      </div>
+      <div className={styles.actions}>
+        <Button
+          appearance="primary"
+          icon={<ArrowClockwiseRegular />}
+          disabled={busy}
+          onClick={() => relaunch()}
+        >
+          {t('MigrationRecovery.Relaunch.Title')}
+        </Button>
+        <Button
+          icon={<FolderOpenRegular />}
+          onClick={() => AppCommands.openDatabaseFolder()}
+        >
+          {t('MigrationRecovery.OpenDatabaseFolder')}
+        </Button>
+      </div>
+    </div>
+  )
+})
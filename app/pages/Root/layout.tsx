@@ -8,6 +8,7 @@ import { combineGachaPathname } from '@/pages/Gacha/route'
 import router from '@/pages/router'
 import AppLayout from './components/AppLayout'
 import ErrorBoundary from './components/ErrorBoundary'
+import MigrationRecovery from './components/MigrationRecovery'
 import UpdaterAlert from './components/UpdaterAlert'
 import { MetadataProvider } from './contexts/Metadata'
 import { writeMemoryRouteIfEnabled } from './queries/business'
@@ -20,7 +21,7 @@ const AllowPathnames = AccountBusinessKeys
   }, {} as Record<string, KeyofAccountBusiness>)
 
 export default function RootLayout () {
-  const { environment, themeData, themeStore } = rootRoute.useLoaderData()
+  const { environment, migrationError, themeData, themeStore } = rootRoute.useLoaderData()
 
   useEffect(() => {
     // This listener is responsible for writing the memory route when navigating to a business route
@@ -44,7 +45,9 @@ export default function RootLayout () {
           <AppLayout>
             <UpdaterAlert />
             <ErrorBoundary>
-              <Outlet />
+              {migrationError
+                ? <MigrationRecovery error={migrationError} />
+                : <Outlet />}
             </ErrorBoundary>
           </AppLayout>
           <ReactQueryDevtools />
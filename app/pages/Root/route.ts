@@ -17,11 +17,13 @@ const rootRoute = createRootRouteWithContext<RootRouteContext>()({
   async loader () {
     console.debug('===== Root Route Loader =====')
     const environment = await AppCommands.environment()
+    const migrationError = await AppCommands.databaseMigrationError()
     const themeData = await themeStore.load()
     ensureNavbarVisibleQueryData()
     ensureMemoryRouteSwitchQueryData()
     return {
       environment,
+      migrationError,
       themeData,
       themeStore,
     }
@@ -1,4 +1,5 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "registry", derive(serde::Deserialize))]
 pub enum GachaLogEndpointType {
   /// All
   Standard,
@@ -15,6 +16,20 @@ macro_rules! impl_gacha_log_api_endpoints {
         &self,
         endpoint_type: GachaLogEndpointType,
       ) -> Option<&'static str> {
+        // The endpoints of the registry overlay take precedence,
+        // and fall back to the known endpoints of the same game and server.
+        #[cfg(feature = "registry")]
+        if let Some(custom) = self.custom {
+          let mut i = 0;
+          while i < custom.endpoints.len() {
+            let (r#type, base_url) = custom.endpoints[i];
+            if r#type as u8 == endpoint_type as u8 {
+              return Some(base_url);
+            }
+            i += 1;
+          }
+        }
+
         match ((self.game, self.server), endpoint_type) {
           $(
             (
@@ -3,6 +3,8 @@
 use std::fmt;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "registry", derive(serde::Deserialize))]
+#[cfg_attr(feature = "registry", serde(rename_all = "lowercase"))]
 pub enum Game {
   /// 'Genshin Impact'
   Hk4e,
@@ -24,6 +26,8 @@ impl Game {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "registry", derive(serde::Deserialize))]
+#[cfg_attr(feature = "registry", serde(rename_all = "lowercase"))]
 pub enum Server {
   /// CN
   Official,
@@ -41,7 +45,7 @@ impl Server {
   }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy)]
 pub struct GameBiz {
   // Avoid manual construction
   pub(crate) game: Game,
@@ -51,8 +55,26 @@ pub struct GameBiz {
   pub(crate) timezone: time::UtcOffset,
   #[cfg(not(feature = "timezone"))]
   pub(crate) timezone: i8,
+  /// Only the `GameBiz` loaded from the registry overlay.
+  #[cfg(feature = "registry")]
+  pub(crate) custom: Option<&'static registry::GameBizCustom>,
 }
 
+// The identity is the codename and region, so that the registry overlay
+// of a built-in `GameBiz` is still equal to it. (See: `registry`)
+// The timezone is not part of it: An overlay may correct the timezone of a region,
+// it is still the same server and region of the records and accounts.
+impl PartialEq for GameBiz {
+  fn eq(&self, other: &Self) -> bool {
+    self.game == other.game
+      && self.server == other.server
+      && self.region == other.region
+      && self.codename() == other.codename()
+  }
+}
+
+impl Eq for GameBiz {}
+
 impl fmt::Debug for GameBiz {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     f.debug_tuple("GameBiz") // Tuple, not Struct
@@ -161,6 +183,8 @@ macro_rules! impl_game_biz {
       server: Server::$server,
       region: $region,
       timezone: $timezone,
+      #[cfg(feature = "registry")]
+      custom: None,
     };
   };
 }
@@ -207,6 +231,11 @@ impl GameBiz {
 impl GameBiz {
   /// Returns the `codename` of this `GameBiz`.
   pub const fn codename(&self) -> &'static str {
+    #[cfg(feature = "registry")]
+    if let Some(custom) = self.custom {
+      return custom.codename;
+    }
+
     match (self.game, self.server) {
       (Game::Hk4e, Server::Official) => HK4E_CN,
       (Game::Hk4e, Server::Oversea) => HK4E_GLOBAL,
@@ -218,7 +247,20 @@ impl GameBiz {
   }
 
   /// Finds a `GameBiz` by its `codename` and `region`.
+  ///
+  /// When the `registry` feature is enabled, the registered overlay definitions
+  /// take precedence over the built-in variants.
   pub fn from_codename(s: &str, region: &str) -> Option<&'static Self> {
+    #[cfg(feature = "registry")]
+    if let Some(biz) = registry::global().find(s, region) {
+      return Some(biz);
+    }
+
+    Self::from_builtin_codename(s, region)
+  }
+
+  /// Finds a built-in `GameBiz` by its `codename` and `region`.
+  pub(crate) fn from_builtin_codename(s: &str, region: &str) -> Option<&'static Self> {
     macro_rules! case {
       ($(($s:pat, $region:pat) => $biz:ident,)*) => {
         match (s, region) {
@@ -256,6 +298,15 @@ impl GameBiz {
 
   /// Returns the display name of this `GameBiz`.
   pub const fn display_name(&self) -> &'static str {
+    #[cfg(feature = "registry")]
+    if let Some(registry::GameBizCustom {
+      display_name: Some(value),
+      ..
+    }) = self.custom
+    {
+      return value;
+    }
+
     match (self.game, self.server) {
       (Game::Hk4e, Server::Official) => HK4E_CN_NAME,
       (Game::Hk4e, Server::Oversea) => HK4E_GLOBAL_NAME,
@@ -268,6 +319,15 @@ impl GameBiz {
 
   /// Returns the binary executable name of this `GameBiz`. (Without extension)
   pub const fn bin_name(&self) -> &'static str {
+    #[cfg(feature = "registry")]
+    if let Some(registry::GameBizCustom {
+      bin_name: Some(value),
+      ..
+    }) = self.custom
+    {
+      return value;
+    }
+
     match (self.game, self.server) {
       (Game::Hk4e, Server::Official) => HK4E_CN_BIN,
       (Game::Hk4e, Server::Oversea) => HK4E_GLOBAL_BIN,
@@ -280,6 +340,15 @@ impl GameBiz {
 
   /// Returns the game data folder name of the game installed for this `GameBiz`.
   pub const fn data_folder_name(&self) -> &'static str {
+    #[cfg(feature = "registry")]
+    if let Some(registry::GameBizCustom {
+      data_folder_name: Some(value),
+      ..
+    }) = self.custom
+    {
+      return value;
+    }
+
     match (self.game, self.server) {
       (Game::Hk4e, Server::Official) => HK4E_CN_DATA,
       (Game::Hk4e, Server::Oversea) => HK4E_GLOBAL_DATA,
@@ -293,6 +362,8 @@ impl GameBiz {
 
 // Exports
 mod api;
+#[cfg(feature = "registry")]
+pub mod registry;
 mod uid;
 
 pub use api::*;
@@ -0,0 +1,596 @@
+//! Data-driven `GameBiz` definitions.
+//!
+//! Additional servers and regions can be registered at runtime from an overlay
+//! (e.g. shipped together with the metadata), without waiting for a new release.
+//! The built-in variants are always kept as the fallback.
+//!
+//! Note: An overlay can only describe a new server or region of the known [`Game`]s.
+//! [`Uid::try_validate`] resolves with the overlay, the const [`Uid::validate`]
+//! still resolves to the built-in variants only.
+//! An overlay of a built-in variant is equal to it, the identity is the codename and region.
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::RwLock;
+
+use serde::Deserialize;
+
+use crate::uid::resolve;
+use crate::{GachaLogEndpointType, Game, GameBiz, Server, Uid, UidError, UidInvalidReason};
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct GameBizCustom {
+  pub(crate) codename: &'static str,
+  pub(crate) display_name: Option<&'static str>,
+  pub(crate) bin_name: Option<&'static str>,
+  pub(crate) data_folder_name: Option<&'static str>,
+  pub(crate) endpoints: &'static [(GachaLogEndpointType, &'static str)],
+  pub(crate) uid_region_digits: &'static [u32],
+}
+
+/// A `GameBiz` definition of the overlay.
+///
+/// ```json
+/// {
+///   "game": "hk4e",
+///   "server": "oversea",
+///   "codename": "hk4e_global",
+///   "region": "os_asia",
+///   "timezone": 8,
+///   "uidRegionDigits": [3],
+///   "endpoints": {
+///     "Standard": "https://public-operation-hk4e-sg.hoyoverse.com/gacha_info/api/getGachaLog"
+///   }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameBizDefinition {
+  pub game: Game,
+  pub server: Server,
+  pub codename: String,
+  pub region: String,
+  /// Whole hours of the server timezone.
+  pub timezone: i8,
+  #[serde(default)]
+  pub display_name: Option<String>,
+  #[serde(default)]
+  pub bin_name: Option<String>,
+  #[serde(default)]
+  pub data_folder_name: Option<String>,
+  /// Missing endpoint types fall back to the built-in endpoints of the same game and server.
+  #[serde(default)]
+  pub endpoints: HashMap<GachaLogEndpointType, String>,
+  /// The region digits of the `uid`, only those unknown to the built-in variants.
+  /// (See: [`GameBizRegistry::validate_uid`])
+  #[serde(default)]
+  pub uid_region_digits: Vec<u32>,
+}
+
+#[derive(Debug)]
+pub enum GameBizRegistryError {
+  Json(serde_json::Error),
+  InvalidTimezone {
+    codename: String,
+    region: String,
+    value: i8,
+  },
+  InvalidUidRegionDigit {
+    codename: String,
+    region: String,
+    value: u32,
+  },
+}
+
+impl fmt::Display for GameBizRegistryError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Json(source) => write!(f, "Error parsing game biz definitions: {source}"),
+      Self::InvalidTimezone {
+        codename,
+        region,
+        value,
+      } => write!(
+        f,
+        "Invalid timezone of game biz definition: {value} ({codename}, {region})"
+      ),
+      Self::InvalidUidRegionDigit {
+        codename,
+        region,
+        value,
+      } => write!(
+        f,
+        "Invalid uid region digit of game biz definition: {value} ({codename}, {region})"
+      ),
+    }
+  }
+}
+
+impl StdError for GameBizRegistryError {
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Self::Json(source) => Some(source),
+      _ => None,
+    }
+  }
+}
+
+impl From<serde_json::Error> for GameBizRegistryError {
+  fn from(value: serde_json::Error) -> Self {
+    Self::Json(value)
+  }
+}
+
+#[inline]
+fn leak(s: &str) -> &'static str {
+  Box::leak(s.to_owned().into_boxed_str())
+}
+
+impl GameBizDefinition {
+  fn validate(&self) -> Result<(), GameBizRegistryError> {
+    // See: https://en.wikipedia.org/wiki/List_of_UTC_offsets
+    if !(-12..=14).contains(&self.timezone) {
+      return Err(GameBizRegistryError::InvalidTimezone {
+        codename: self.codename.clone(),
+        region: self.region.clone(),
+        value: self.timezone,
+      });
+    }
+
+    if let Some(&value) = self.uid_region_digits.iter().find(|&&digit| digit > 9) {
+      return Err(GameBizRegistryError::InvalidUidRegionDigit {
+        codename: self.codename.clone(),
+        region: self.region.clone(),
+        value,
+      });
+    }
+
+    Ok(())
+  }
+
+  // The definition is leaked, because `GameBiz` is always `&'static`.
+  // Only once for each distinct definition, see `GameBizRegistry::intern`.
+  fn leak(&self) -> &'static GameBiz {
+    #[cfg(feature = "timezone")]
+    let timezone = time::UtcOffset::from_hms(self.timezone, 0, 0)
+      .expect("The timezone has been checked to be in the valid range");
+    #[cfg(not(feature = "timezone"))]
+    let timezone = self.timezone;
+
+    let endpoints = self
+      .endpoints
+      .iter()
+      .map(|(r#type, base_url)| (*r#type, leak(base_url)))
+      .collect::<Vec<_>>();
+
+    let custom = Box::leak(Box::new(GameBizCustom {
+      codename: leak(&self.codename),
+      display_name: self.display_name.as_deref().map(leak),
+      bin_name: self.bin_name.as_deref().map(leak),
+      data_folder_name: self.data_folder_name.as_deref().map(leak),
+      endpoints: Box::leak(endpoints.into_boxed_slice()),
+      uid_region_digits: Box::leak(self.uid_region_digits.clone().into_boxed_slice()),
+    }));
+
+    Box::leak(Box::new(GameBiz {
+      game: self.game,
+      server: self.server,
+      region: leak(&self.region),
+      timezone,
+      custom: Some(custom),
+    }))
+  }
+}
+
+#[derive(Default)]
+struct Registry {
+  /// The registered, in the order of registration.
+  registered: Vec<&'static GameBiz>,
+  /// All leaked of the distinct definitions, reused when registered again.
+  interned: Vec<(GameBizDefinition, &'static GameBiz)>,
+}
+
+/// The overlay of the `GameBiz` definitions.
+///
+/// Use [`global`] for the overlay of [`GameBiz::from_codename`].
+pub struct GameBizRegistry {
+  inner: RwLock<Registry>,
+}
+
+impl Default for GameBizRegistry {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl GameBizRegistry {
+  pub const fn new() -> Self {
+    Self {
+      inner: RwLock::new(Registry {
+        registered: Vec::new(),
+        interned: Vec::new(),
+      }),
+    }
+  }
+
+  fn intern(registry: &mut Registry, definition: GameBizDefinition) -> &'static GameBiz {
+    if let Some((_, biz)) = registry
+      .interned
+      .iter()
+      .find(|(interned, _)| *interned == definition)
+    {
+      return biz;
+    }
+
+    let biz = definition.leak();
+    registry.interned.push((definition, biz));
+    biz
+  }
+
+  /// Registers the `GameBiz` definitions into the overlay.
+  ///
+  /// A definition with the same `codename` and `region` replaces the registered one in place.
+  /// Registering the same definitions again, e.g. reloading the overlay, leaks nothing.
+  /// Returns the number of registered definitions.
+  pub fn register(
+    &self,
+    definitions: impl IntoIterator<Item = GameBizDefinition>,
+  ) -> Result<usize, GameBizRegistryError> {
+    // Validate all definitions first, don't register partially.
+    let definitions = definitions.into_iter().collect::<Vec<_>>();
+    for definition in &definitions {
+      definition.validate()?;
+    }
+
+    let count = definitions.len();
+    let mut registry = self.inner.write().unwrap();
+    for definition in definitions {
+      let biz = Self::intern(&mut registry, definition);
+      match registry
+        .registered
+        .iter_mut()
+        .find(|exist| exist.codename() == biz.codename() && exist.region == biz.region)
+      {
+        Some(exist) => *exist = biz,
+        None => registry.registered.push(biz),
+      }
+    }
+
+    Ok(count)
+  }
+
+  /// Registers the `GameBiz` definitions from a JSON array.
+  pub fn register_from_json(&self, slice: &[u8]) -> Result<usize, GameBizRegistryError> {
+    let definitions: Vec<GameBizDefinition> = serde_json::from_slice(slice)?;
+    self.register(definitions)
+  }
+
+  /// Returns all `GameBiz` registered in the overlay.
+  pub fn registered(&self) -> Vec<&'static GameBiz> {
+    self.inner.read().unwrap().registered.clone()
+  }
+
+  /// Clears the overlay. The registered `GameBiz` remain valid.
+  pub fn clear(&self) {
+    self.inner.write().unwrap().registered.clear();
+  }
+
+  /// Finds a registered `GameBiz` by its `codename` and `region`.
+  pub fn find(&self, codename: &str, region: &str) -> Option<&'static GameBiz> {
+    self
+      .inner
+      .read()
+      .unwrap()
+      .registered
+      .iter()
+      .find(|biz| biz.codename() == codename && biz.region == region)
+      .copied()
+  }
+
+  /// Finds a `GameBiz` of the overlay, and falls back to the built-in.
+  pub fn from_codename(&self, codename: &str, region: &str) -> Option<&'static GameBiz> {
+    self
+      .find(codename, region)
+      .or_else(|| GameBiz::from_builtin_codename(codename, region))
+  }
+
+  /// Validates and constructs a `Uid` with the overlay.
+  ///
+  /// * The `uid` of a built-in variant resolves to its overlay, e.g. the timezone of it.
+  /// * The unknown region digit resolves to the registered `GameBiz` of the same game
+  ///   with the digit in [`GameBizDefinition::uid_region_digits`].
+  pub fn validate_uid(&self, game: Game, uid: u32) -> Result<Uid, UidError> {
+    Uid::from_resolved(game, uid, self.resolve_uid(game, uid))
+  }
+
+  pub(crate) fn resolve_uid(
+    &self,
+    game: Game,
+    uid: u32,
+  ) -> Result<&'static GameBiz, UidInvalidReason> {
+    match resolve(game, uid) {
+      Ok(biz) => Ok(self.find(biz.codename(), biz.region).unwrap_or(biz)),
+      Err(reason @ UidInvalidReason::UnknownRegionDigit { digit }) => self
+        .inner
+        .read()
+        .unwrap()
+        .registered
+        .iter()
+        .find(|biz| {
+          biz.game == game
+            && biz
+              .custom
+              .is_some_and(|custom| custom.uid_region_digits.contains(&digit))
+        })
+        .copied()
+        .ok_or(reason),
+      Err(reason) => Err(reason),
+    }
+  }
+}
+
+static GLOBAL: GameBizRegistry = GameBizRegistry::new();
+
+/// The global overlay of [`GameBiz::from_codename`].
+#[inline]
+pub fn global() -> &'static GameBizRegistry {
+  &GLOBAL
+}
+
+/// Registers the `GameBiz` definitions into the global overlay.
+/// See [`GameBizRegistry::register`].
+pub fn register(
+  definitions: impl IntoIterator<Item = GameBizDefinition>,
+) -> Result<usize, GameBizRegistryError> {
+  GLOBAL.register(definitions)
+}
+
+/// Registers the `GameBiz` definitions from a JSON array into the global overlay.
+pub fn register_from_json(slice: &[u8]) -> Result<usize, GameBizRegistryError> {
+  GLOBAL.register_from_json(slice)
+}
+
+/// Returns all `GameBiz` registered in the global overlay.
+pub fn registered() -> Vec<&'static GameBiz> {
+  GLOBAL.registered()
+}
+
+/// Clears the global overlay. The registered `GameBiz` remain valid.
+pub fn clear() {
+  GLOBAL.clear();
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // A registry of each test, the global is shared by the tests running in parallel.
+  #[test]
+  fn test_registry() {
+    let registry = GameBizRegistry::new();
+    let definitions = br#"[
+      {
+        "game": "hk4e",
+        "server": "oversea",
+        "codename": "hk4e_global",
+        "region": "os_new",
+        "timezone": 9,
+        "binName": "GenshinImpactNew",
+        "endpoints": {
+          "Standard": "https://example.com/gacha_info/api/getGachaLog"
+        }
+      },
+      {
+        "game": "nap",
+        "server": "official",
+        "codename": "nap_cn",
+        "region": "prod_gf_cn",
+        "timezone": 8,
+        "displayName": "ZZZ"
+      }
+    ]"#;
+
+    assert_eq!(registry.register_from_json(definitions).unwrap(), 2);
+    assert_eq!(registry.registered().len(), 2);
+
+    // New region
+    let biz = registry.from_codename("hk4e_global", "os_new").unwrap();
+    assert_eq!(biz.game(), Game::Hk4e);
+    assert_eq!(biz.server(), Server::Oversea);
+    assert_eq!(biz.region(), "os_new");
+    assert_eq!(biz.codename(), "hk4e_global");
+    assert_eq!(biz.bin_name(), "GenshinImpactNew");
+    assert_eq!(biz.display_name(), GameBiz::HK4E_GLOBAL_ASIA.display_name());
+    assert_eq!(
+      biz.data_folder_name(),
+      GameBiz::HK4E_GLOBAL_ASIA.data_folder_name()
+    );
+    assert_eq!(
+      biz.gacha_log_api_endpoint(GachaLogEndpointType::Standard),
+      Some("https://example.com/gacha_info/api/getGachaLog")
+    );
+    assert_eq!(
+      biz.gacha_log_api_endpoint(GachaLogEndpointType::Beyond),
+      GameBiz::HK4E_GLOBAL_ASIA.gacha_log_api_endpoint(GachaLogEndpointType::Beyond)
+    );
+
+    // Override the built-in, still equal to it
+    let biz = registry.from_codename("nap_cn", "prod_gf_cn").unwrap();
+    assert_eq!(biz, &GameBiz::NAP_CN);
+    assert_eq!(biz.display_name(), "ZZZ");
+    assert_eq!(biz.bin_name(), GameBiz::NAP_CN.bin_name());
+
+    // Registered again, the same are reused
+    assert_eq!(registry.register_from_json(definitions).unwrap(), 2);
+    assert_eq!(registry.registered().len(), 2);
+    assert!(std::ptr::eq(
+      registry.from_codename("nap_cn", "prod_gf_cn").unwrap(),
+      biz
+    ));
+
+    // Replace the registered in place
+    let replaced = GameBizDefinition {
+      game: Game::Hk4e,
+      server: Server::Oversea,
+      codename: "hk4e_global".to_owned(),
+      region: "os_new".to_owned(),
+      timezone: 8,
+      display_name: None,
+      bin_name: None,
+      data_folder_name: None,
+      endpoints: HashMap::new(),
+      uid_region_digits: Vec::new(),
+    };
+
+    assert_eq!(registry.register([replaced.clone()]).unwrap(), 1);
+    let registered = registry.registered();
+    assert_eq!(registered.len(), 2);
+    assert_eq!(registered[0].region(), "os_new");
+    assert_eq!(
+      registered[0].bin_name(),
+      GameBiz::HK4E_GLOBAL_ASIA.bin_name()
+    );
+
+    // Invalid timezone, nothing is registered
+    let invalid = GameBizDefinition {
+      region: "os_invalid".to_owned(),
+      timezone: 15,
+      ..replaced
+    };
+
+    assert!(matches!(
+      registry.register([invalid]),
+      Err(GameBizRegistryError::InvalidTimezone { value: 15, .. })
+    ));
+    assert!(
+      registry
+        .from_codename("hk4e_global", "os_invalid")
+        .is_none()
+    );
+
+    // Fallback to the built-in
+    registry.clear();
+    assert!(registry.registered().is_empty());
+    assert!(registry.from_codename("hk4e_global", "os_new").is_none());
+    assert_eq!(
+      registry
+        .from_codename("nap_cn", "prod_gf_cn")
+        .unwrap()
+        .display_name(),
+      GameBiz::NAP_CN.display_name()
+    );
+  }
+
+  #[test]
+  fn test_registry_uid() {
+    let registry = GameBizRegistry::new();
+    let definitions = br#"[
+      {
+        "game": "nap",
+        "server": "oversea",
+        "codename": "nap_global",
+        "region": "prod_gf_new",
+        "timezone": 9,
+        "uidRegionDigits": [4]
+      },
+      {
+        "game": "hk4e",
+        "server": "oversea",
+        "codename": "hk4e_global",
+        "region": "os_euro",
+        "timezone": 2
+      }
+    ]"#;
+
+    assert_eq!(registry.register_from_json(definitions).unwrap(), 2);
+
+    // Region only of the overlay
+    assert!(Uid::validate(Game::Nap, 1_400_000_000).is_none());
+    let uid = registry.validate_uid(Game::Nap, 1_400_000_000).unwrap();
+    assert_eq!(uid.game_biz().codename(), "nap_global");
+    assert_eq!(uid.game_biz().region(), "prod_gf_new");
+    assert_eq!(uid.value(), 1_400_000_000);
+
+    // Unknown to the overlay too
+    assert_eq!(
+      registry
+        .validate_uid(Game::Nap, 1_600_000_000)
+        .unwrap_err()
+        .reason,
+      UidInvalidReason::UnknownRegionDigit { digit: 6 }
+    );
+    assert_eq!(
+      registry.validate_uid(Game::Hk4e, 1_400_000_000).unwrap(),
+      Uid::validate(Game::Hk4e, 1_400_000_000).unwrap()
+    );
+
+    // Overlay of the built-in, the timezone of it
+    let uid = registry.validate_uid(Game::Hk4e, 700_000_000).unwrap();
+    assert_eq!(uid.game_biz(), &GameBiz::HK4E_GLOBAL_EURO);
+    assert_ne!(
+      uid.game_biz().timezone(),
+      GameBiz::HK4E_GLOBAL_EURO.timezone()
+    );
+
+    // Invalid region digit, nothing is registered
+    let invalid = GameBizDefinition {
+      game: Game::Nap,
+      server: Server::Oversea,
+      codename: "nap_global".to_owned(),
+      region: "prod_gf_invalid".to_owned(),
+      timezone: 8,
+      display_name: None,
+      bin_name: None,
+      data_folder_name: None,
+      endpoints: HashMap::new(),
+      uid_region_digits: vec![10],
+    };
+
+    assert!(matches!(
+      registry.register([invalid]),
+      Err(GameBizRegistryError::InvalidUidRegionDigit { value: 10, .. })
+    ));
+    assert_eq!(registry.registered().len(), 2);
+  }
+
+  #[test]
+  fn test_registry_global() {
+    // The region is only of this test
+    let definition = GameBizDefinition {
+      game: Game::Hkrpg,
+      server: Server::Oversea,
+      codename: "hkrpg_global".to_owned(),
+      region: "prod_official_test_global".to_owned(),
+      timezone: 8,
+      display_name: None,
+      bin_name: None,
+      data_folder_name: None,
+      endpoints: HashMap::new(),
+      uid_region_digits: Vec::new(),
+    };
+
+    assert!(GameBiz::from_codename("hkrpg_global", "prod_official_test_global").is_none());
+    assert_eq!(register([definition.clone()]).unwrap(), 1);
+
+    let biz = GameBiz::from_codename("hkrpg_global", "prod_official_test_global").unwrap();
+    assert_eq!(biz.game(), Game::Hkrpg);
+    assert!(registered().contains(&biz));
+
+    // The unknown region digit is only of this test
+    let definition = GameBizDefinition {
+      game: Game::Nap,
+      server: Server::Oversea,
+      codename: "nap_global".to_owned(),
+      region: "prod_gf_test_global".to_owned(),
+      uid_region_digits: vec![6],
+      ..definition
+    };
+
+    assert!(Uid::try_validate(Game::Nap, 1_600_000_000).is_err());
+    assert_eq!(register([definition]).unwrap(), 1);
+
+    let uid = Uid::try_validate(Game::Nap, 1_600_000_000).unwrap();
+    assert_eq!(uid.game_biz().region(), "prod_gf_test_global");
+  }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::{Game, GameBiz};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -29,99 +31,172 @@ impl Uid {
   /// Validates and constructs a `Uid` from the given `game` and `uid` number.
   ///
   /// Returns `None` if the `uid` is invalid for the specified game.
+  /// See [`Uid::try_validate`] for the reason.
+  ///
+  /// Note: Only the built-in `GameBiz` variants, see [`Uid::try_validate`] for the overlay.
   pub const fn validate(game: Game, uid: u32) -> Option<Self> {
-    // Count the number of digits in the UID
-    let digits = if uid == 0 {
-      return None;
-    } else {
-      // This inner function counts the digits in a u32 number.
-      // It is guaranteed not to be 0.
-      #[inline]
-      const fn digits(mut n: u32) -> u32 {
-        let mut c = 0;
-        while n > 0 {
-          n /= 10;
-          c += 1;
-        }
-        c
-      }
-
-      digits(uid)
-    };
+    match resolve(game, uid) {
+      Ok(biz) => Some(Self { biz, v: uid }),
+      Err(_) => None,
+    }
+  }
 
-    let biz = match (game, digits) {
-      // Genshin Impact
-      // 9-digit UID:  1 0000 0000
-      //               ↑
-      //               └── Region digit (1st position)
-      //
-      // 10-digit UID: 1 7 0000 0000
-      //               ↑ ↑
-      //               │ └── Region digit (2nd position)
-      //               └── Currently observed as 1, but not confirmed to be fixed.
-      //                   FIXME: May need adaptation if other starting digits appear.
-      (Game::Hk4e, 9 | 10) => match (uid / 10_u32.pow(9 - 1)) % 10 {
-        1..=4 => &GameBiz::HK4E_CN_GF01,
-        5 => &GameBiz::HK4E_CN_QD01,
-        6 => &GameBiz::HK4E_GLOBAL_USA,
-        7 => &GameBiz::HK4E_GLOBAL_EURO,
-        8 => &GameBiz::HK4E_GLOBAL_ASIA,
-        9 => &GameBiz::HK4E_GLOBAL_CHT,
-        _ => return None,
-      },
+  /// Validates and constructs a `Uid` from the given `game` and `uid` number.
+  ///
+  /// Returns an [`UidError`] with the reason and the probable `GameBiz` candidates
+  /// if the `uid` is invalid for the specified game.
+  ///
+  /// When the `registry` feature is enabled, it is resolved with the global overlay.
+  /// See [`GameBizRegistry::validate_uid`](crate::registry::GameBizRegistry::validate_uid).
+  pub fn try_validate(game: Game, uid: u32) -> Result<Self, UidError> {
+    #[cfg(feature = "registry")]
+    let resolved = crate::registry::global().resolve_uid(game, uid);
+    #[cfg(not(feature = "registry"))]
+    let resolved = resolve(game, uid);
 
-      // Honkai: Star Rail
-      //
-      // Like Genshin Impact,
-      // 9-digit UID:  1 0000 0000
-      //               ↑
-      //               └── Region digit (1st position)
-      //
-      // 10-digit UID: 1 7 0000 0000
-      //               ↑ ↑
-      //               │ └── Region digit (2nd position)
-      //               └── Currently observed as 1, but not confirmed to be fixed.
-      //                   FIXME: May need adaptation if other starting digits appear.
-      (Game::Hkrpg, 9 | 10) => match (uid / 10_u32.pow(9 - 1)) % 10 {
-        1..=4 => &GameBiz::HKRPG_CN_GF,
-        5 => &GameBiz::HKRPG_CN_QD,
-        6 => &GameBiz::HKRPG_GLOBAL_USA,
-        7 => &GameBiz::HKRPG_GLOBAL_EURO,
-        8 => &GameBiz::HKRPG_GLOBAL_ASIA,
-        9 => &GameBiz::HKRPG_GLOBAL_CHT,
-        _ => return None,
-      },
+    Self::from_resolved(game, uid, resolved)
+  }
 
-      // Zenless Zone Zero
-      // 8-digit UID:  0000 0000
-      //               (All are Official CN server)
-      //
-      // FIXME: Note: 9-digit UID might also be official. Unable to determine at this time,
-      //          as the 8-digit limit has not yet been reached.
-      (Game::Nap, 8) => {
-        // There are no Channel businesses, all are official CN business.
-        &GameBiz::NAP_CN
-      }
+  pub(crate) fn from_resolved(
+    game: Game,
+    uid: u32,
+    resolved: Result<&'static GameBiz, UidInvalidReason>,
+  ) -> Result<Self, UidError> {
+    resolved
+      .map(|biz| Self { biz, v: uid })
+      .map_err(|reason| UidError {
+        game,
+        value: uid,
+        reason,
+        candidates: candidates(game, reason),
+      })
+  }
+}
 
-      // Zenless Zone Zero
-      // 10-digit UID: 1 7 0000 0000
-      //               ↑ ↑
-      //               │ └── Region digit (2nd position)
-      //               └── Currently observed as 1, but not confirmed to be fixed.
-      //                   FIXME: May need adaptation if other starting digits appear.
-      (Game::Nap, 10) => match (uid / 10_u32.pow(9 - 1)) % 10 {
-        0 => &GameBiz::NAP_GLOBAL_US,
-        3 => &GameBiz::NAP_GLOBAL_JP,
-        5 => &GameBiz::NAP_GLOBAL_EU,
-        7 => &GameBiz::NAP_GLOBAL_SG,
-        _ => return None,
-      },
-
-      // Unsupported game or invalid digit count
-      _ => return None,
-    };
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UidInvalidReason {
+  /// The `uid` is zero.
+  Zero,
+  /// The number of digits is not supported by the game.
+  DigitCount { digits: u32 },
+  /// The region digit is unknown to the game.
+  UnknownRegionDigit { digit: u32 },
+}
 
-    Some(Self { biz, v: uid })
+impl fmt::Display for UidInvalidReason {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Zero => f.write_str("zero"),
+      Self::DigitCount { digits } => write!(f, "unsupported digit count {digits}"),
+      Self::UnknownRegionDigit { digit } => write!(f, "unknown region digit {digit}"),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UidError {
+  pub game: Game,
+  pub value: u32,
+  pub reason: UidInvalidReason,
+  /// The probable `GameBiz` of an ambiguous `uid`. May be empty.
+  pub candidates: Vec<&'static GameBiz>,
+}
+
+impl fmt::Display for UidError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "Invalid {:?} uid {}: {}",
+      self.game, self.value, self.reason
+    )
+  }
+}
+
+impl std::error::Error for UidError {}
+
+// Count the number of digits in the UID.
+// It is guaranteed not to be 0.
+#[inline]
+const fn digits(mut n: u32) -> u32 {
+  let mut c = 0;
+  while n > 0 {
+    n /= 10;
+    c += 1;
+  }
+  c
+}
+
+pub(crate) const fn resolve(game: Game, uid: u32) -> Result<&'static GameBiz, UidInvalidReason> {
+  if uid == 0 {
+    return Err(UidInvalidReason::Zero);
+  }
+
+  match (game, digits(uid)) {
+    // Genshin Impact & Honkai: Star Rail
+    // 9-digit UID:  1 0000 0000
+    //               ↑
+    //               └── Region digit (1st position)
+    (Game::Hk4e | Game::Hkrpg, 9) => region(game, uid / 10_u32.pow(8)),
+
+    // Zenless Zone Zero
+    // 8-digit UID:  0000 0000
+    //               (All are Official CN server)
+    //
+    // FIXME: Note: 9-digit UID might also be official. Unable to determine at this time,
+    //          as the 8-digit limit has not yet been reached. See `candidates`.
+    (Game::Nap, 8) => {
+      // There are no Channel businesses, all are official CN business.
+      Ok(&GameBiz::NAP_CN)
+    }
+
+    // Genshin Impact & Honkai: Star Rail & Zenless Zone Zero
+    // 10-digit UID: 1 7 0000 0000
+    //               ↑ ↑
+    //               │ └── Region digit (2nd position)
+    //               └── Currently observed as 1, but not confirmed to be fixed.
+    //                   FIXME: May need adaptation if other starting digits appear.
+    (Game::Hk4e | Game::Hkrpg | Game::Nap, 10) => region(game, (uid / 10_u32.pow(8)) % 10),
+
+    // Unsupported game or invalid digit count
+    (_, digits) => Err(UidInvalidReason::DigitCount { digits }),
+  }
+}
+
+const fn region(game: Game, digit: u32) -> Result<&'static GameBiz, UidInvalidReason> {
+  Ok(match (game, digit) {
+    // Genshin Impact
+    (Game::Hk4e, 1..=4) => &GameBiz::HK4E_CN_GF01,
+    (Game::Hk4e, 5) => &GameBiz::HK4E_CN_QD01,
+    (Game::Hk4e, 6) => &GameBiz::HK4E_GLOBAL_USA,
+    (Game::Hk4e, 7) => &GameBiz::HK4E_GLOBAL_EURO,
+    (Game::Hk4e, 8) => &GameBiz::HK4E_GLOBAL_ASIA,
+    (Game::Hk4e, 9) => &GameBiz::HK4E_GLOBAL_CHT,
+
+    // Honkai: Star Rail
+    (Game::Hkrpg, 1..=4) => &GameBiz::HKRPG_CN_GF,
+    (Game::Hkrpg, 5) => &GameBiz::HKRPG_CN_QD,
+    (Game::Hkrpg, 6) => &GameBiz::HKRPG_GLOBAL_USA,
+    (Game::Hkrpg, 7) => &GameBiz::HKRPG_GLOBAL_EURO,
+    (Game::Hkrpg, 8) => &GameBiz::HKRPG_GLOBAL_ASIA,
+    (Game::Hkrpg, 9) => &GameBiz::HKRPG_GLOBAL_CHT,
+
+    // Zenless Zone Zero (10-digit only)
+    (Game::Nap, 0) => &GameBiz::NAP_GLOBAL_US,
+    (Game::Nap, 3) => &GameBiz::NAP_GLOBAL_JP,
+    (Game::Nap, 5) => &GameBiz::NAP_GLOBAL_EU,
+    (Game::Nap, 7) => &GameBiz::NAP_GLOBAL_SG,
+
+    _ => return Err(UidInvalidReason::UnknownRegionDigit { digit }),
+  })
+}
+
+// The probable `GameBiz` of the invalid but ambiguous UID.
+fn candidates(game: Game, reason: UidInvalidReason) -> Vec<&'static GameBiz> {
+  match (game, reason) {
+    // The 8-digit limit of the official CN server may have been reached.
+    (Game::Nap, UidInvalidReason::DigitCount { digits: 9 }) => vec![&GameBiz::NAP_CN],
+
+    _ => Vec::new(),
   }
 }
 
@@ -246,6 +321,103 @@ mod tests {
     invalid_case! { Nap, 1_234 } // 4-digit
   }
 
+  #[test]
+  fn test_try_validate_regions() {
+    use UidInvalidReason::*;
+
+    // (game, uid, expected biz)
+    let valid_cases = [
+      // Genshin Impact
+      (Game::Hk4e, 100_000_000, &GameBiz::HK4E_CN_GF01),
+      (Game::Hk4e, 400_000_000, &GameBiz::HK4E_CN_GF01),
+      (Game::Hk4e, 500_000_000, &GameBiz::HK4E_CN_QD01),
+      (Game::Hk4e, 600_000_000, &GameBiz::HK4E_GLOBAL_USA),
+      (Game::Hk4e, 700_000_000, &GameBiz::HK4E_GLOBAL_EURO),
+      (Game::Hk4e, 800_000_000, &GameBiz::HK4E_GLOBAL_ASIA),
+      (Game::Hk4e, 900_000_000, &GameBiz::HK4E_GLOBAL_CHT),
+      (Game::Hk4e, 1_900_000_000, &GameBiz::HK4E_GLOBAL_CHT),
+      // Honkai: Star Rail
+      (Game::Hkrpg, 100_000_000, &GameBiz::HKRPG_CN_GF),
+      (Game::Hkrpg, 400_000_000, &GameBiz::HKRPG_CN_GF),
+      (Game::Hkrpg, 500_000_000, &GameBiz::HKRPG_CN_QD),
+      (Game::Hkrpg, 600_000_000, &GameBiz::HKRPG_GLOBAL_USA),
+      (Game::Hkrpg, 700_000_000, &GameBiz::HKRPG_GLOBAL_EURO),
+      (Game::Hkrpg, 800_000_000, &GameBiz::HKRPG_GLOBAL_ASIA),
+      (Game::Hkrpg, 900_000_000, &GameBiz::HKRPG_GLOBAL_CHT),
+      (Game::Hkrpg, 1_700_000_000, &GameBiz::HKRPG_GLOBAL_EURO),
+      // Zenless Zone Zero
+      (Game::Nap, 10_000_000, &GameBiz::NAP_CN),
+      (Game::Nap, 1_000_000_000, &GameBiz::NAP_GLOBAL_US),
+      (Game::Nap, 1_300_000_000, &GameBiz::NAP_GLOBAL_JP),
+      (Game::Nap, 1_500_000_000, &GameBiz::NAP_GLOBAL_EU),
+      (Game::Nap, 1_700_000_000, &GameBiz::NAP_GLOBAL_SG),
+    ];
+
+    for (game, v, expected_biz) in valid_cases {
+      let uid = Uid::try_validate(game, v).unwrap();
+      assert_eq!(uid.biz, expected_biz, "{game:?} {v}");
+      assert_eq!(uid.v, v);
+    }
+
+    // (game, uid, expected reason, expected candidates)
+    let invalid_cases: [(Game, u32, UidInvalidReason, &[&GameBiz]); 8] = [
+      (Game::Hk4e, 0, Zero, &[]),
+      (Game::Hk4e, 12_345_678, DigitCount { digits: 8 }, &[]),
+      (
+        Game::Hk4e,
+        1_000_000_000,
+        UnknownRegionDigit { digit: 0 },
+        &[],
+      ),
+      (Game::Hkrpg, 12_345, DigitCount { digits: 5 }, &[]),
+      (
+        Game::Hkrpg,
+        1_000_000_000,
+        UnknownRegionDigit { digit: 0 },
+        &[],
+      ),
+      (
+        Game::Nap,
+        123_456_789,
+        DigitCount { digits: 9 },
+        &[&GameBiz::NAP_CN],
+      ),
+      (
+        Game::Nap,
+        1_100_000_000,
+        UnknownRegionDigit { digit: 1 },
+        &[],
+      ),
+      (Game::Nap, 1_234, DigitCount { digits: 4 }, &[]),
+    ];
+
+    for (game, v, expected_reason, expected_candidates) in invalid_cases {
+      let err = Uid::try_validate(game, v).unwrap_err();
+      assert_eq!(err.game, game);
+      assert_eq!(err.value, v);
+      assert_eq!(err.reason, expected_reason, "{game:?} {v}");
+      assert_eq!(err.candidates, expected_candidates, "{game:?} {v}");
+      assert!(Uid::validate(game, v).is_none());
+    }
+  }
+
+  #[test]
+  fn test_leading_digit() {
+    // (game, uid, the region of the 2nd digit)
+    // The leading digit of a 10-digit UID is not checked, only the region digit.
+    let cases = [
+      (Game::Hk4e, 2_600_000_000, &GameBiz::HK4E_GLOBAL_USA),
+      (Game::Hkrpg, 4_100_000_000, &GameBiz::HKRPG_CN_GF),
+      (Game::Nap, 3_300_000_000, &GameBiz::NAP_GLOBAL_JP),
+    ];
+
+    for (game, v, expected_biz) in cases {
+      let uid = Uid::try_validate(game, v).unwrap();
+      assert_eq!(uid.biz, expected_biz, "{game:?} {v}");
+      assert!(Uid::is_valid(game, v), "{game:?} {v}");
+    }
+  }
+
   #[test]
   fn test_uid_properties() {
     // Test that Uid struct properties are correctly set for each game
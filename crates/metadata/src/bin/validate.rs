@@ -0,0 +1,66 @@
+//! Validate the raw metadata files before publishing.
+//!
+//! Usage: `hoyo_gacha_metadata_validate [--json] <FILE>...`
+//!
+//! Exits with code 1 if any file is unreadable or has errors.
+//! Warnings are reported but do not fail.
+
+use std::process::ExitCode;
+
+use hoyo_gacha_metadata::raw::RawMetadata;
+use hoyo_gacha_metadata::validate::{has_errors, validate};
+
+fn main() -> ExitCode {
+  let mut json = false;
+  let mut files = Vec::new();
+  for arg in std::env::args().skip(1) {
+    match arg.as_str() {
+      "--json" => json = true,
+      "-h" | "--help" => {
+        println!("Usage: hoyo_gacha_metadata_validate [--json] <FILE>...");
+        return ExitCode::SUCCESS;
+      }
+      _ => files.push(arg),
+    }
+  }
+
+  if files.is_empty() {
+    eprintln!("Usage: hoyo_gacha_metadata_validate [--json] <FILE>...");
+    return ExitCode::FAILURE;
+  }
+
+  let mut failed = false;
+  for file in files {
+    let raw = match std::fs::read(&file)
+      .map_err(|err| err.to_string())
+      .and_then(|bytes| RawMetadata::from_slice(&bytes).map_err(|err| err.to_string()))
+    {
+      Ok(raw) => raw,
+      Err(err) => {
+        eprintln!("{file}: error: {err}");
+        failed = true;
+        continue;
+      }
+    };
+
+    let issues = validate(&raw);
+    failed |= has_errors(&issues);
+
+    if json {
+      let output = serde_json::json!({ "file": file, "issues": issues });
+      println!("{output}");
+    } else {
+      for issue in &issues {
+        println!("{file}: {issue}");
+      }
+
+      eprintln!("{file}: {} issue(s)", issues.len());
+    }
+  }
+
+  if failed {
+    ExitCode::FAILURE
+  } else {
+    ExitCode::SUCCESS
+  }
+}
@@ -0,0 +1,765 @@
+use std::collections::hash_map::Entry as MapEntry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use snafu::{OptionExt, Snafu, ensure};
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::def::{MetadataBusinessImpl, MetadataImpl, bake_metadata_banners};
+use crate::raw::{
+  RawMetadata, RawMetadataBanner, RawMetadataBusiness, RawMetadataCategorization, RawMetadataI18n,
+};
+use crate::{Entry, Metadata, MetadataLocale};
+
+// Compact binary metadata (Little-endian)
+//
+// The JSON raw metadata is the source of truth, the compact format is generated from it.
+// Locales are not baked on load: the entries are read from the tables on demand,
+// lookups by item id and item name are binary searches without allocation.
+// Banners are small and decoded on load.
+//
+// Header:
+//   Magic    : [u8; 4]   // "HGMD"
+//   Version  : u16
+//   Reserved : u16
+//   Businesses  : u32
+//   Pool offset : u32      // String pool (UTF-8) at the end
+//   Pool length : u32
+// Str: (offset: u32, length: u32) into the pool, offset u32::MAX is none.
+// Business:
+//   Id, Reserved : u8, [u8; 3]
+//   Categories, Items, Locales, Banners : u32
+//   Categories : [Str]
+//   Items      : [(item_id: u32, category: u16, rank_type: u8, reserved: u8)] // Sorted by item_id
+//   Locales    : [
+//     Lang           : Str
+//     Category names : [Str; Categories]
+//     Item names     : [Str; Items]
+//     Name index     : (count: u32, [u32; count]) // Item indices sorted by item name
+//   ]
+//   Banners    : [
+//     GachaType, HasGachaId, GachaId : u32
+//     StartTime, EndTime : (unix_timestamp: i64, offset_seconds: i32)
+//     Version            : Str
+//     UpGolden, UpPurple : (count: u32, [u32; count])
+//   ]
+
+const MAGIC: &[u8; 4] = b"HGMD";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 20;
+const STR_LEN: usize = 8;
+const ITEM_LEN: usize = 8;
+const NONE: u32 = u32::MAX;
+
+#[derive(Debug, Snafu)]
+pub enum CompactMetadataError {
+  #[snafu(display("Invalid compact metadata magic"))]
+  InvalidMagic,
+
+  #[snafu(display("Unsupported compact metadata version: {version} (Expected: {VERSION})"))]
+  UnsupportedVersion { version: u16 },
+
+  #[snafu(display("Unexpected end of compact metadata at: {offset}"))]
+  UnexpectedEof { offset: usize },
+
+  #[snafu(display("Invalid string of compact metadata at: {offset}"))]
+  InvalidString { offset: usize },
+
+  #[snafu(display("Invalid date time of compact metadata at: {offset}"))]
+  InvalidDateTime { offset: usize },
+}
+
+// region: Encode
+
+#[derive(Default)]
+struct Writer {
+  body: Vec<u8>,
+  pool: Vec<u8>,
+  strings: HashMap<String, u32>, // Deduplicated: string : offset
+}
+
+impl Writer {
+  fn u8(&mut self, value: u8) {
+    self.body.push(value);
+  }
+
+  fn u16(&mut self, value: u16) {
+    self.body.extend_from_slice(&value.to_le_bytes());
+  }
+
+  fn u32(&mut self, value: u32) {
+    self.body.extend_from_slice(&value.to_le_bytes());
+  }
+
+  fn i32(&mut self, value: i32) {
+    self.body.extend_from_slice(&value.to_le_bytes());
+  }
+
+  fn i64(&mut self, value: i64) {
+    self.body.extend_from_slice(&value.to_le_bytes());
+  }
+
+  fn len(value: usize) -> u32 {
+    u32::try_from(value).expect("Compact metadata length overflow")
+  }
+
+  fn str(&mut self, value: Option<&str>) {
+    let Some(value) = value else {
+      self.u32(NONE);
+      self.u32(0);
+      return;
+    };
+
+    let offset = match self.strings.get(value) {
+      Some(offset) => *offset,
+      None => {
+        let offset = Self::len(self.pool.len());
+        self.pool.extend_from_slice(value.as_bytes());
+        self.strings.insert(value.to_owned(), offset);
+        offset
+      }
+    };
+
+    self.u32(offset);
+    self.u32(Self::len(value.len()));
+  }
+
+  fn date_time(&mut self, value: &OffsetDateTime) {
+    self.i64(value.unix_timestamp());
+    self.i32(value.offset().whole_seconds());
+  }
+
+  fn ids<'a>(&mut self, ids: impl IntoIterator<Item = &'a u32>) {
+    let mut ids = ids.into_iter().copied().collect::<Vec<_>>();
+    ids.sort_unstable();
+    self.u32(Self::len(ids.len()));
+    for id in ids {
+      self.u32(id);
+    }
+  }
+
+  fn business(&mut self, business: &RawMetadataBusiness) {
+    // item_id : (category index, rank_type)
+    let mut items = BTreeMap::<u32, (u16, u8)>::new();
+    // locale : (category index : category name, item_id : item name)
+    #[allow(clippy::type_complexity)]
+    let mut locales = BTreeMap::<&str, (HashMap<u16, &str>, HashMap<u32, &str>)>::new();
+
+    for (index, categorization) in business.categories.iter().enumerate() {
+      let index = u16::try_from(index).expect("Compact metadata categories overflow");
+      for (item_id, rank_type) in &categorization.entries {
+        items.insert(*item_id, (index, *rank_type));
+      }
+
+      for (locale, i18n) in &categorization.i18n {
+        let (category_names, item_names) = locales.entry(locale).or_default();
+        category_names.insert(index, &i18n.category);
+        for ((item_id, _), item_name) in categorization.entries.iter().zip(&i18n.entries) {
+          item_names.insert(*item_id, item_name);
+        }
+      }
+    }
+
+    self.u8(business.id);
+    self.body.extend_from_slice(&[0; 3]);
+    self.u32(Self::len(business.categories.len()));
+    self.u32(Self::len(items.len()));
+    self.u32(Self::len(locales.len()));
+    self.u32(Self::len(business.banners.len()));
+
+    for categorization in &business.categories {
+      self.str(Some(&categorization.category));
+    }
+
+    for (item_id, (category, rank_type)) in &items {
+      self.u32(*item_id);
+      self.u16(*category);
+      self.u8(*rank_type);
+      self.u8(0);
+    }
+
+    for (locale, (category_names, item_names)) in &locales {
+      self.str(Some(locale));
+
+      for index in 0..business.categories.len() {
+        self.str(category_names.get(&(index as u16)).copied());
+      }
+
+      let mut name_index = Vec::with_capacity(item_names.len());
+      for (index, (item_id, (category, _))) in items.iter().enumerate() {
+        // The item name belongs to the category of the item,
+        // if the item id is duplicated in another category.
+        let item_name = item_names
+          .get(item_id)
+          .filter(|_| category_names.contains_key(category))
+          .copied();
+
+        if let Some(item_name) = item_name {
+          name_index.push((item_name, *item_id, Self::len(index)));
+        }
+
+        self.str(item_name);
+      }
+
+      name_index.sort_unstable();
+      self.u32(Self::len(name_index.len()));
+      for (_, _, index) in name_index {
+        self.u32(index);
+      }
+    }
+
+    for banner in &business.banners {
+      self.u32(banner.gacha_type);
+      self.u32(u32::from(banner.gacha_id.is_some()));
+      self.u32(banner.gacha_id.unwrap_or_default());
+      self.date_time(&banner.start_time);
+      self.date_time(&banner.end_time);
+      self.str(banner.version.as_deref());
+      self.ids(&banner.up_golden);
+      self.ids(&banner.up_purple);
+    }
+  }
+}
+
+/// Encode the raw metadata into the compact binary format.
+pub fn encode(raw: &RawMetadata) -> Vec<u8> {
+  let mut writer = Writer::default();
+  for business in raw.as_ref() {
+    writer.business(business);
+  }
+
+  let Writer { body, pool, .. } = writer;
+  let mut out = Vec::with_capacity(HEADER_LEN + body.len() + pool.len());
+  out.extend_from_slice(MAGIC);
+  out.extend_from_slice(&VERSION.to_le_bytes());
+  out.extend_from_slice(&0u16.to_le_bytes());
+  out.extend_from_slice(&Writer::len(raw.as_ref().len()).to_le_bytes());
+  out.extend_from_slice(&Writer::len(HEADER_LEN + body.len()).to_le_bytes());
+  out.extend_from_slice(&Writer::len(pool.len()).to_le_bytes());
+  out.extend_from_slice(&body);
+  out.extend_from_slice(&pool);
+  out
+}
+
+// endregion
+
+// region: Decode
+
+// Read the fixed-size values at the offset, None if out of bounds.
+#[inline]
+fn read<const N: usize>(bytes: &[u8], offset: usize) -> Option<[u8; N]> {
+  bytes.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+#[inline]
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+  read(bytes, offset).map(u32::from_le_bytes)
+}
+
+struct Reader<'b> {
+  bytes: &'b [u8],
+  pool: &'b str,
+  offset: usize,
+}
+
+impl<'b> Reader<'b> {
+  fn take<const N: usize>(&mut self) -> Result<[u8; N], CompactMetadataError> {
+    let value = read(self.bytes, self.offset).context(UnexpectedEofSnafu {
+      offset: self.offset,
+    })?;
+
+    self.offset += N;
+    Ok(value)
+  }
+
+  fn u8(&mut self) -> Result<u8, CompactMetadataError> {
+    self.take::<1>().map(|[value]| value)
+  }
+
+  fn u16(&mut self) -> Result<u16, CompactMetadataError> {
+    self.take().map(u16::from_le_bytes)
+  }
+
+  fn u32(&mut self) -> Result<u32, CompactMetadataError> {
+    self.take().map(u32::from_le_bytes)
+  }
+
+  fn usize(&mut self) -> Result<usize, CompactMetadataError> {
+    self.u32().map(|value| value as usize)
+  }
+
+  fn skip(&mut self, len: usize, count: usize) -> Result<usize, CompactMetadataError> {
+    let start = self.offset;
+    let end = len
+      .checked_mul(count)
+      .and_then(|n| start.checked_add(n))
+      .filter(|end| *end <= self.bytes.len())
+      .context(UnexpectedEofSnafu { offset: start })?;
+
+    self.offset = end;
+    Ok(start)
+  }
+
+  fn str(&mut self) -> Result<Option<&'b str>, CompactMetadataError> {
+    let start = self.offset;
+    let (offset, len) = (self.usize()?, self.usize()?);
+    if offset == NONE as usize {
+      return Ok(None);
+    }
+
+    str_at(self.pool, offset, len)
+      .map(Some)
+      .context(InvalidStringSnafu { offset: start })
+  }
+
+  fn date_time(&mut self) -> Result<OffsetDateTime, CompactMetadataError> {
+    let start = self.offset;
+    let timestamp = self.take().map(i64::from_le_bytes)?;
+    let offset = self.take().map(i32::from_le_bytes)?;
+
+    UtcOffset::from_whole_seconds(offset)
+      .ok()
+      .and_then(|offset| {
+        OffsetDateTime::from_unix_timestamp(timestamp)
+          .ok()
+          .map(|utc| utc.to_offset(offset))
+      })
+      .context(InvalidDateTimeSnafu { offset: start })
+  }
+
+  fn ids(&mut self) -> Result<HashSet<u32>, CompactMetadataError> {
+    let count = self.usize()?;
+    (0..count).map(|_| self.u32()).collect()
+  }
+}
+
+#[inline]
+fn str_at(pool: &str, offset: usize, len: usize) -> Option<&str> {
+  pool.get(offset..offset.checked_add(len)?)
+}
+
+// Split the compact metadata into the tables and the string pool.
+fn split(bytes: &[u8]) -> Result<(&[u8], &str, usize), CompactMetadataError> {
+  ensure!(bytes.get(..4) == Some(MAGIC), InvalidMagicSnafu);
+
+  let mut reader = Reader {
+    bytes,
+    pool: "",
+    offset: MAGIC.len(),
+  };
+
+  let version = reader.u16()?;
+  ensure!(version == VERSION, UnsupportedVersionSnafu { version });
+
+  let _reserved = reader.u16()?;
+  let businesses = reader.usize()?;
+  let pool_offset = reader.usize()?;
+  let pool_len = reader.usize()?;
+
+  let pool = pool_offset
+    .checked_add(pool_len)
+    .and_then(|end| bytes.get(pool_offset..end))
+    .context(UnexpectedEofSnafu {
+      offset: pool_offset,
+    })?;
+
+  let pool = std::str::from_utf8(pool).ok().context(InvalidStringSnafu {
+    offset: pool_offset,
+  })?;
+
+  Ok((&bytes[..pool_offset], pool, businesses))
+}
+
+// Offsets of a business tables
+struct BusinessLayout {
+  id: u8,
+  categories: usize,
+  items: usize,
+  categories_offset: usize,
+  items_offset: usize,
+  locales: Vec<LocaleLayout>,
+  banners: Vec<RawMetadataBanner>,
+}
+
+struct LocaleLayout {
+  lang: Range<usize>,
+  category_names_offset: usize,
+  item_names_offset: usize,
+  name_index_offset: usize,
+  name_index: usize,
+}
+
+impl Reader<'_> {
+  fn business(&mut self) -> Result<BusinessLayout, CompactMetadataError> {
+    let id = self.u8()?;
+    self.skip(1, 3)?;
+
+    let categories = self.usize()?;
+    let items = self.usize()?;
+    let locales = self.usize()?;
+    let banners = self.usize()?;
+
+    let categories_offset = self.skip(STR_LEN, categories)?;
+    for index in 0..categories {
+      str_at_table(self.bytes, self.pool, categories_offset, index)
+        .flatten()
+        .context(InvalidStringSnafu {
+          offset: categories_offset + index * STR_LEN,
+        })?;
+    }
+
+    let items_offset = self.skip(ITEM_LEN, items)?;
+
+    let locales = (0..locales)
+      .map(|_| {
+        let start = self.offset;
+        let lang = self.str()?.context(InvalidStringSnafu { offset: start })?;
+        let lang = str_range(self.pool, lang);
+
+        let category_names_offset = self.skip(STR_LEN, categories)?;
+        let item_names_offset = self.skip(STR_LEN, items)?;
+        let name_index = self.usize()?;
+        let name_index_offset = self.skip(4, name_index)?;
+
+        Ok(LocaleLayout {
+          lang,
+          category_names_offset,
+          item_names_offset,
+          name_index_offset,
+          name_index,
+        })
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let banners = (0..banners)
+      .map(|_| {
+        let gacha_type = self.u32()?;
+        let has_gacha_id = self.u32()? != 0;
+        let gacha_id = self.u32()?;
+
+        Ok(RawMetadataBanner {
+          gacha_type,
+          gacha_id: has_gacha_id.then_some(gacha_id),
+          start_time: self.date_time()?,
+          end_time: self.date_time()?,
+          version: self.str()?.map(ToOwned::to_owned),
+          up_golden: self.ids()?,
+          up_purple: self.ids()?,
+        })
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(BusinessLayout {
+      id,
+      categories,
+      items,
+      categories_offset,
+      items_offset,
+      locales,
+      banners,
+    })
+  }
+}
+
+// The range of the sub string in the pool.
+fn str_range(pool: &str, sub: &str) -> Range<usize> {
+  let start = sub.as_ptr() as usize - pool.as_ptr() as usize;
+  start..start + sub.len()
+}
+
+// Read the Str of the table at the index.
+// Returns None if out of bounds, Some(None) if it's none.
+fn str_at_table<'p>(
+  bytes: &[u8],
+  pool: &'p str,
+  table_offset: usize,
+  index: usize,
+) -> Option<Option<&'p str>> {
+  let offset = table_offset + index * STR_LEN;
+  let (str_offset, len) = (read_u32(bytes, offset)?, read_u32(bytes, offset + 4)?);
+  if str_offset == NONE {
+    Some(None)
+  } else {
+    str_at(pool, str_offset as usize, len as usize).map(Some)
+  }
+}
+
+fn layouts(bytes: &[u8]) -> Result<(&[u8], &str, Vec<BusinessLayout>), CompactMetadataError> {
+  let (tables, pool, businesses) = split(bytes)?;
+  let mut reader = Reader {
+    bytes: tables,
+    pool,
+    offset: HEADER_LEN,
+  };
+
+  let layouts = (0..businesses)
+    .map(|_| reader.business())
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok((tables, pool, layouts))
+}
+
+// endregion
+
+// region: Locale
+
+struct CompactLocaleImpl {
+  tables: Arc<[u8]>,
+  pool: Arc<str>,
+  lang: Range<usize>,
+  categories: usize,
+  categories_offset: usize,
+  items: usize,
+  items_offset: usize,
+  category_names_offset: usize,
+  item_names_offset: usize,
+  name_index_offset: usize,
+  name_index: usize,
+}
+
+impl fmt::Debug for CompactLocaleImpl {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CompactLocaleImpl")
+      .field("lang", &self.lang())
+      .field("categories", &self.categories)
+      .field("items", &self.items)
+      .finish_non_exhaustive()
+  }
+}
+
+impl CompactLocaleImpl {
+  #[inline]
+  fn str_at(&self, table_offset: usize, index: usize) -> Option<&str> {
+    str_at_table(&self.tables, &self.pool, table_offset, index).flatten()
+  }
+
+  #[inline]
+  fn item_id(&self, index: usize) -> Option<u32> {
+    read_u32(&self.tables, self.items_offset + index * ITEM_LEN)
+  }
+
+  #[inline]
+  fn item_name(&self, index: usize) -> Option<&str> {
+    self.str_at(self.item_names_offset, index)
+  }
+
+  fn entry(&self, index: usize) -> Option<Entry<'_>> {
+    let offset = self.items_offset + index * ITEM_LEN;
+    let [id0, id1, id2, id3, category0, category1, rank_type, _] =
+      read::<ITEM_LEN>(&self.tables, offset)?;
+
+    let category = u16::from_le_bytes([category0, category1]) as usize;
+    if category >= self.categories {
+      return None;
+    }
+
+    Some(Entry {
+      locale: self.lang(),
+      category: self.str_at(self.categories_offset, category)?,
+      category_name: self.str_at(self.category_names_offset, category)?,
+      item_id: u32::from_le_bytes([id0, id1, id2, id3]),
+      item_name: self.item_name(index)?,
+      rank_type,
+    })
+  }
+
+  // Binary search the item index by item id.
+  fn find_by_id(&self, item_id: u32) -> Option<usize> {
+    let (mut low, mut high) = (0, self.items);
+    while low < high {
+      let mid = low + (high - low) / 2;
+      match self.item_id(mid)?.cmp(&item_id) {
+        std::cmp::Ordering::Less => low = mid + 1,
+        std::cmp::Ordering::Greater => high = mid,
+        std::cmp::Ordering::Equal => return Some(mid),
+      }
+    }
+
+    None
+  }
+
+  #[inline]
+  fn name_index_at(&self, position: usize) -> Option<usize> {
+    read_u32(&self.tables, self.name_index_offset + position * 4).map(|index| index as usize)
+  }
+
+  // The item indices with the item name.
+  fn find_by_name<'s>(&'s self, item_name: &'s str) -> impl Iterator<Item = usize> + 's {
+    // Lower bound of the item name
+    let (mut low, mut high) = (0, self.name_index);
+    while low < high {
+      let mid = low + (high - low) / 2;
+      let name = self
+        .name_index_at(mid)
+        .and_then(|index| self.item_name(index))
+        .unwrap_or_default();
+
+      if name < item_name {
+        low = mid + 1;
+      } else {
+        high = mid;
+      }
+    }
+
+    (low..self.name_index).map_while(move |position| {
+      let index = self.name_index_at(position)?;
+      (self.item_name(index)? == item_name).then_some(index)
+    })
+  }
+}
+
+impl MetadataLocale for CompactLocaleImpl {
+  fn lang(&self) -> &str {
+    &self.pool[self.lang.clone()]
+  }
+
+  fn iter_entries(&self) -> Box<dyn Iterator<Item = Entry<'_>> + '_> {
+    Box::new((0..self.items).filter_map(|index| self.entry(index)))
+  }
+
+  fn entry_from_id(&self, item_id: u32) -> Option<Entry<'_>> {
+    self.entry(self.find_by_id(item_id)?)
+  }
+
+  fn entry_from_name<'a, 'n: 'a>(&'a self, item_name: &'n str) -> Option<HashSet<Entry<'a>>> {
+    let entries = self
+      .find_by_name(item_name)
+      .filter_map(|index| self.entry(index))
+      .collect::<HashSet<_>>();
+
+    (!entries.is_empty()).then_some(entries)
+  }
+
+  fn entry_from_name_first<'a, 'n: 'a>(&'a self, item_name: &'n str) -> Option<Entry<'a>> {
+    self
+      .find_by_name(item_name)
+      .find_map(|index| self.entry(index))
+  }
+}
+
+// endregion
+
+/// Load the compact binary metadata.
+///
+/// The locales are read lazily from the bytes, only the banners are decoded.
+pub fn load(bytes: impl Into<Arc<[u8]>>) -> Result<Box<dyn Metadata>, CompactMetadataError> {
+  // The tables are the prefix of the bytes, the offsets are the same.
+  let tables: Arc<[u8]> = bytes.into();
+  let (_, pool, layouts) = layouts(&tables)?;
+  let pool = Arc::<str>::from(pool);
+
+  let mut businesses = HashMap::with_capacity(layouts.len());
+  for layout in layouts {
+    let mut locales = HashMap::with_capacity(layout.locales.len());
+    for locale in layout.locales {
+      let lang = Arc::<str>::from(&pool[locale.lang.clone()]);
+      let locale = CompactLocaleImpl {
+        tables: Arc::clone(&tables),
+        pool: Arc::clone(&pool),
+        lang: locale.lang,
+        categories: layout.categories,
+        categories_offset: layout.categories_offset,
+        items: layout.items,
+        items_offset: layout.items_offset,
+        category_names_offset: locale.category_names_offset,
+        item_names_offset: locale.item_names_offset,
+        name_index_offset: locale.name_index_offset,
+        name_index: locale.name_index,
+      };
+
+      locales.insert(lang, Box::new(locale) as Box<dyn MetadataLocale>);
+    }
+
+    let (banners, banner_ids) = bake_metadata_banners(layout.banners);
+    businesses.insert(
+      layout.id,
+      MetadataBusinessImpl {
+        locales,
+        banners,
+        banner_ids,
+      },
+    );
+  }
+
+  Ok(Box::new(MetadataImpl { businesses }))
+}
+
+/// Decode the compact binary metadata back into the raw metadata.
+///
+/// The order of the categories and entries may differ from the original.
+pub fn decode(bytes: &[u8]) -> Result<RawMetadata, CompactMetadataError> {
+  let (tables, pool, layouts) = layouts(bytes)?;
+
+  let businesses = layouts
+    .into_iter()
+    .map(|layout| {
+      let category = |index| {
+        str_at_table(tables, pool, layout.categories_offset, index)
+          .flatten()
+          .unwrap_or_default()
+      };
+
+      // category index : entries
+      let mut categories = (0..layout.categories)
+        .map(|index| RawMetadataCategorization {
+          category: category(index).to_owned(),
+          entries: Vec::new(),
+          i18n: HashMap::new(),
+        })
+        .collect::<Vec<_>>();
+
+      // item index : category index
+      let mut item_categories = Vec::with_capacity(layout.items);
+      for index in 0..layout.items {
+        let offset = layout.items_offset + index * ITEM_LEN;
+        let [id0, id1, id2, id3, category0, category1, rank_type, _] =
+          read::<ITEM_LEN>(tables, offset).context(UnexpectedEofSnafu { offset })?;
+
+        let category = u16::from_le_bytes([category0, category1]) as usize;
+        let categorization = categories
+          .get_mut(category)
+          .context(UnexpectedEofSnafu { offset })?;
+
+        categorization
+          .entries
+          .push((u32::from_le_bytes([id0, id1, id2, id3]), rank_type));
+        item_categories.push(category);
+      }
+
+      for locale in &layout.locales {
+        let lang = &pool[locale.lang.clone()];
+        for (index, item_category) in item_categories.iter().enumerate() {
+          let Some(category_name) =
+            str_at_table(tables, pool, locale.category_names_offset, *item_category).flatten()
+          else {
+            continue;
+          };
+
+          let item_name = str_at_table(tables, pool, locale.item_names_offset, index)
+            .flatten()
+            .unwrap_or_default();
+
+          match categories[*item_category].i18n.entry(lang.to_owned()) {
+            MapEntry::Occupied(mut o) => o.get_mut().entries.push(item_name.to_owned()),
+            MapEntry::Vacant(o) => {
+              o.insert(RawMetadataI18n {
+                category: category_name.to_owned(),
+                entries: vec![item_name.to_owned()],
+              });
+            }
+          }
+        }
+      }
+
+      Ok(RawMetadataBusiness {
+        id: layout.id,
+        categories,
+        banners: layout.banners,
+      })
+    })
+    .collect::<Result<Vec<_>, CompactMetadataError>>()?;
+
+  Ok(RawMetadata::from(businesses))
+}
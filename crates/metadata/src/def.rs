@@ -11,14 +11,15 @@ use crate::raw::{RawMetadataBanner, RawMetadataCategorization, RawMetadataI18n};
 use crate::{Entry, Metadata, MetadataBanner, MetadataLocale};
 
 #[derive(Debug)]
-struct MetadataImpl {
-  businesses: HashMap<u8, MetadataBusinessImpl>, // business_id : _
+pub(crate) struct MetadataImpl {
+  pub(crate) businesses: HashMap<u8, MetadataBusinessImpl>, // business_id : _
 }
 
 #[derive(Debug)]
-struct MetadataBusinessImpl {
-  locales: HashMap<Arc<str>, Box<dyn MetadataLocale>>, // locale name: _
-  banners: HashMap<u32, MetadataBannersImpl>,          // gacha_type : banners
+pub(crate) struct MetadataBusinessImpl {
+  pub(crate) locales: HashMap<Arc<str>, Box<dyn MetadataLocale>>, // locale name: _
+  pub(crate) banners: HashMap<u32, MetadataBannersImpl>,          // gacha_type : banners
+  pub(crate) banner_ids: HashMap<u32, (u32, usize)>,              // gacha_id : (gacha_type, index)
 }
 
 #[derive(Debug)]
@@ -84,7 +85,7 @@ enum EntryNameId {
 }
 
 #[derive(Debug)]
-struct MetadataBannersImpl {
+pub(crate) struct MetadataBannersImpl {
   inner: Vec<Box<dyn MetadataBanner>>,
   start_time_index: BTreeMap<OffsetDateTime, Vec<usize>>,
   end_time_index: BTreeMap<OffsetDateTime, Vec<usize>>,
@@ -114,6 +115,15 @@ impl MetadataBannersImpl {
     }
   }
 
+  // Ordered by start time, and the original order for the same start time.
+  fn timeline(&self) -> Vec<&dyn MetadataBanner> {
+    self
+      .start_time_index
+      .values()
+      .flat_map(|indices| indices.iter().map(|&idx| &*self.inner[idx]))
+      .collect()
+  }
+
   fn find_active_at(&self, point: OffsetDateTime) -> Option<&dyn MetadataBanner> {
     let started_indices: Vec<usize> = self
       .start_time_index
@@ -154,6 +164,7 @@ impl MetadataBannersImpl {
 #[derive(Debug)]
 struct MetadataBannerImpl {
   gacha_type: u32,
+  gacha_id: Option<u32>,
   start_time: OffsetDateTime,
   end_time: OffsetDateTime,
   up_golden: HashSet<u32>,
@@ -221,6 +232,28 @@ impl Metadata for MetadataImpl {
       .get(&gacha_type)?
       .find_active_at(point)
   }
+
+  fn query_banner_by_id(&self, business_id: u8, gacha_id: u32) -> Option<&dyn MetadataBanner> {
+    let business = self.businesses.get(&business_id)?;
+    let (gacha_type, index) = business.banner_ids.get(&gacha_id)?;
+    business
+      .banners
+      .get(gacha_type)?
+      .inner
+      .get(*index)
+      .map(Box::deref)
+  }
+
+  fn banners(&self, business_id: u8, gacha_type: u32) -> Option<Vec<&dyn MetadataBanner>> {
+    Some(
+      self
+        .businesses
+        .get(&business_id)?
+        .banners
+        .get(&gacha_type)?
+        .timeline(),
+    )
+  }
 }
 
 impl MetadataLocale for MetadataLocaleImpl {
@@ -228,12 +261,13 @@ impl MetadataLocale for MetadataLocaleImpl {
     &self.locale
   }
 
-  fn entries(&self) -> HashMap<u32, Entry<'_>> {
-    self
-      .entries
-      .iter()
-      .map(|(item_id, entry)| (*item_id, entry.as_entry(self, *item_id)))
-      .collect()
+  fn iter_entries(&self) -> Box<dyn Iterator<Item = Entry<'_>> + '_> {
+    Box::new(
+      self
+        .entries
+        .iter()
+        .map(|(item_id, entry)| entry.as_entry(self, *item_id)),
+    )
   }
 
   fn entry_from_id(&self, item_id: u32) -> Option<Entry<'_>> {
@@ -275,6 +309,10 @@ impl MetadataBanner for MetadataBannerImpl {
     self.gacha_type
   }
 
+  fn gacha_id(&self) -> Option<u32> {
+    self.gacha_id
+  }
+
   fn start_time(&self) -> &OffsetDateTime {
     &self.start_time
   }
@@ -287,6 +325,14 @@ impl MetadataBanner for MetadataBannerImpl {
     self.version.as_deref()
   }
 
+  fn up_golden(&self) -> &HashSet<u32> {
+    &self.up_golden
+  }
+
+  fn up_purple(&self) -> &HashSet<u32> {
+    &self.up_purple
+  }
+
   fn is_up_golden(&self, item_id: u32) -> bool {
     self.up_golden.contains(&item_id)
   }
@@ -331,8 +377,15 @@ fn bake_metadata(raw: RawMetadata) -> Result<MetadataImpl, BakeMetadataError> {
   } in raw
   {
     let locales = bake_metadata_locales(business_id, categories)?;
-    let banners = bake_metadata_banners(banners);
-    businesses.insert(business_id, MetadataBusinessImpl { locales, banners });
+    let (banners, banner_ids) = bake_metadata_banners(banners);
+    businesses.insert(
+      business_id,
+      MetadataBusinessImpl {
+        locales,
+        banners,
+        banner_ids,
+      },
+    );
   }
 
   Ok(MetadataImpl { businesses })
@@ -425,11 +478,20 @@ fn bake_metadata_locales(
 }
 
 // Bake raw metadata banners into baked metadata banners. (Grouped by gacha_type)
-fn bake_metadata_banners(banners: Vec<RawMetadataBanner>) -> HashMap<u32, MetadataBannersImpl> {
+// And the index of the banners with gacha_id.
+#[allow(clippy::type_complexity)]
+pub(crate) fn bake_metadata_banners(
+  banners: Vec<RawMetadataBanner>,
+) -> (
+  HashMap<u32, MetadataBannersImpl>,
+  HashMap<u32, (u32, usize)>,
+) {
   let mut groups = HashMap::new();
+  let mut banner_ids = HashMap::new();
   for raw in banners {
     let banner = MetadataBannerImpl {
       gacha_type: raw.gacha_type,
+      gacha_id: raw.gacha_id,
       start_time: raw.start_time,
       end_time: raw.end_time,
       up_golden: raw.up_golden,
@@ -437,16 +499,21 @@ fn bake_metadata_banners(banners: Vec<RawMetadataBanner>) -> HashMap<u32, Metada
       version: raw.version,
     };
 
-    groups
-      .entry(banner.gacha_type)
-      .or_insert_with(Vec::new)
-      .push(Box::new(banner) as Box<dyn MetadataBanner>);
+    let group = groups.entry(banner.gacha_type).or_insert_with(Vec::new);
+    if let Some(gacha_id) = banner.gacha_id {
+      // The gacha_id is unique, the last one wins if duplicated.
+      banner_ids.insert(gacha_id, (banner.gacha_type, group.len()));
+    }
+
+    group.push(Box::new(banner) as Box<dyn MetadataBanner>);
   }
 
-  groups
+  let groups = groups
     .into_iter()
     .map(|(gacha_type, inner)| (gacha_type, MetadataBannersImpl::new(inner)))
-    .collect()
+    .collect();
+
+  (groups, banner_ids)
 }
 
 // endregion
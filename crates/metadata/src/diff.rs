@@ -0,0 +1,375 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::serde::rfc3339;
+
+use crate::raw::{RawMetadata, RawMetadataBanner, RawMetadataBusiness};
+
+/// The differences between two raw metadata.
+///
+/// Only the changed businesses are included, sorted by business id.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataDiff {
+  pub businesses: Vec<MetadataBusinessDiff>,
+}
+
+impl MetadataDiff {
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.businesses.is_empty()
+  }
+
+  pub fn business(&self, business_id: u8) -> Option<&MetadataBusinessDiff> {
+    self
+      .businesses
+      .iter()
+      .find(|business| business.business_id == business_id)
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataBusinessDiff {
+  pub business_id: u8,
+  pub added_locales: Vec<String>,
+  pub removed_locales: Vec<String>,
+  pub added_items: Vec<DiffItem>,
+  pub removed_items: Vec<DiffItem>,
+  /// The category or rank type of the item has changed. (old, new)
+  pub changed_items: Vec<(DiffItem, DiffItem)>,
+  /// The item name has changed in the locale.
+  pub renamed_items: Vec<DiffRenamed<u32>>,
+  /// The category local name has changed in the locale.
+  pub renamed_categories: Vec<DiffRenamed<String>>,
+  pub added_banners: Vec<DiffBanner>,
+  pub removed_banners: Vec<DiffBanner>,
+  /// The gacha id, up golden, up purple or version of the banner has changed. (old, new)
+  pub changed_banners: Vec<(DiffBanner, DiffBanner)>,
+}
+
+impl MetadataBusinessDiff {
+  pub fn is_empty(&self) -> bool {
+    self.added_locales.is_empty()
+      && self.removed_locales.is_empty()
+      && self.added_items.is_empty()
+      && self.removed_items.is_empty()
+      && self.changed_items.is_empty()
+      && self.renamed_items.is_empty()
+      && self.renamed_categories.is_empty()
+      && self.added_banners.is_empty()
+      && self.removed_banners.is_empty()
+      && self.changed_banners.is_empty()
+  }
+
+  /// Returns the number of added items grouped by category.
+  pub fn added_items_by_category(&self) -> BTreeMap<&str, usize> {
+    self
+      .added_items
+      .iter()
+      .fold(BTreeMap::new(), |mut acc, item| {
+        *acc.entry(item.category.as_str()).or_default() += 1;
+        acc
+      })
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffItem {
+  pub item_id: u32,
+  pub category: String,
+  pub rank_type: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffRenamed<K> {
+  pub locale: String,
+  pub key: K,
+  pub old_name: String,
+  pub new_name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffBanner {
+  pub gacha_type: u32,
+  pub gacha_id: Option<u32>,
+  #[serde(with = "rfc3339")]
+  pub start_time: OffsetDateTime,
+  #[serde(with = "rfc3339")]
+  pub end_time: OffsetDateTime,
+  pub up_golden: BTreeSet<u32>,
+  pub up_purple: BTreeSet<u32>,
+  pub version: Option<String>,
+}
+
+impl From<&RawMetadataBanner> for DiffBanner {
+  fn from(value: &RawMetadataBanner) -> Self {
+    Self {
+      gacha_type: value.gacha_type,
+      gacha_id: value.gacha_id,
+      start_time: value.start_time,
+      end_time: value.end_time,
+      up_golden: value.up_golden.iter().copied().collect(),
+      up_purple: value.up_purple.iter().copied().collect(),
+      version: value.version.clone(),
+    }
+  }
+}
+
+impl fmt::Display for MetadataDiff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_empty() {
+      return f.write_str("No changes");
+    }
+
+    for (index, business) in self.businesses.iter().enumerate() {
+      if index > 0 {
+        f.write_str("; ")?;
+      }
+
+      write!(
+        f,
+        "Business {}: +{} -{} ~{} items, {} renamed, +{} -{} ~{} banners",
+        business.business_id,
+        business.added_items.len(),
+        business.removed_items.len(),
+        business.changed_items.len(),
+        business.renamed_items.len(),
+        business.added_banners.len(),
+        business.removed_banners.len(),
+        business.changed_banners.len(),
+      )?;
+    }
+
+    Ok(())
+  }
+}
+
+// region: Diff
+
+// Flatten view of a raw metadata business
+#[derive(Default)]
+struct Flatten<'a> {
+  items: BTreeMap<u32, (&'a str, u8)>, // item_id : (category, rank_type)
+  names: BTreeMap<&'a str, HashMap<u32, &'a str>>, // locale : item_id : item_name
+  category_names: BTreeMap<&'a str, BTreeMap<&'a str, &'a str>>, // locale : category : name
+  banners: BTreeMap<BannerKey, Vec<&'a RawMetadataBanner>>,
+}
+
+// Banner identity. The up golden is not a part of it, so that a corrected
+// up golden is a change. The double banners share the same key. (See: `diff_banners`)
+type BannerKey = (u32, OffsetDateTime, OffsetDateTime); // gacha_type, start_time, end_time
+
+impl<'a> Flatten<'a> {
+  fn new(business: &'a RawMetadataBusiness) -> Self {
+    let mut flatten = Self::default();
+
+    for categorization in &business.categories {
+      let category = categorization.category.as_str();
+      for (item_id, rank_type) in &categorization.entries {
+        flatten.items.insert(*item_id, (category, *rank_type));
+      }
+
+      for (locale, i18n) in &categorization.i18n {
+        let names = flatten.names.entry(locale.as_str()).or_default();
+        for ((item_id, _), item_name) in categorization.entries.iter().zip(&i18n.entries) {
+          names.insert(*item_id, item_name.as_str());
+        }
+
+        flatten
+          .category_names
+          .entry(locale.as_str())
+          .or_default()
+          .insert(category, i18n.category.as_str());
+      }
+    }
+
+    for banner in &business.banners {
+      let key = (banner.gacha_type, banner.start_time, banner.end_time);
+      flatten.banners.entry(key).or_default().push(banner);
+    }
+
+    flatten
+  }
+}
+
+#[inline]
+fn diff_item(item_id: u32, (category, rank_type): (&str, u8)) -> DiffItem {
+  DiffItem {
+    item_id,
+    category: category.to_owned(),
+    rank_type,
+  }
+}
+
+fn diff_business(
+  business_id: u8,
+  old: Option<&RawMetadataBusiness>,
+  new: Option<&RawMetadataBusiness>,
+) -> MetadataBusinessDiff {
+  let old = old.map(Flatten::new).unwrap_or_default();
+  let new = new.map(Flatten::new).unwrap_or_default();
+  let mut diff = MetadataBusinessDiff {
+    business_id,
+    ..Default::default()
+  };
+
+  // Locales
+  for locale in old.names.keys() {
+    if !new.names.contains_key(locale) {
+      diff.removed_locales.push((*locale).to_owned());
+    }
+  }
+  for locale in new.names.keys() {
+    if !old.names.contains_key(locale) {
+      diff.added_locales.push((*locale).to_owned());
+    }
+  }
+
+  // Items
+  for (item_id, old_item) in &old.items {
+    match new.items.get(item_id) {
+      None => diff.removed_items.push(diff_item(*item_id, *old_item)),
+      Some(new_item) if new_item != old_item => diff.changed_items.push((
+        diff_item(*item_id, *old_item),
+        diff_item(*item_id, *new_item),
+      )),
+      Some(_) => {}
+    }
+  }
+  for (item_id, new_item) in &new.items {
+    if !old.items.contains_key(item_id) {
+      diff.added_items.push(diff_item(*item_id, *new_item));
+    }
+  }
+
+  // Names of the same locale
+  for (locale, old_names) in &old.names {
+    let Some(new_names) = new.names.get(locale) else {
+      continue;
+    };
+
+    let mut renamed = old_names
+      .iter()
+      .filter_map(|(item_id, old_name)| {
+        let new_name = new_names.get(item_id)?;
+        (old_name != new_name).then(|| DiffRenamed {
+          locale: (*locale).to_owned(),
+          key: *item_id,
+          old_name: (*old_name).to_owned(),
+          new_name: (*new_name).to_owned(),
+        })
+      })
+      .collect::<Vec<_>>();
+
+    renamed.sort_by_key(|renamed| renamed.key);
+    diff.renamed_items.extend(renamed);
+  }
+
+  for (locale, old_names) in &old.category_names {
+    let Some(new_names) = new.category_names.get(locale) else {
+      continue;
+    };
+
+    for (category, old_name) in old_names {
+      if let Some(new_name) = new_names.get(category)
+        && old_name != new_name
+      {
+        diff.renamed_categories.push(DiffRenamed {
+          locale: (*locale).to_owned(),
+          key: (*category).to_owned(),
+          old_name: (*old_name).to_owned(),
+          new_name: (*new_name).to_owned(),
+        });
+      }
+    }
+  }
+
+  // Banners
+  for (key, old_banners) in &old.banners {
+    let new_banners = new.banners.get(key).map(Vec::as_slice).unwrap_or_default();
+    diff_banners(&mut diff, old_banners, new_banners);
+  }
+  for (key, new_banners) in &new.banners {
+    if !old.banners.contains_key(key) {
+      diff_banners(&mut diff, &[], new_banners);
+    }
+  }
+
+  diff
+}
+
+// Diff the banners of the same key. The double banners are paired by
+// the up golden first, then the rest in order.
+fn diff_banners(
+  diff: &mut MetadataBusinessDiff,
+  old_banners: &[&RawMetadataBanner],
+  new_banners: &[&RawMetadataBanner],
+) {
+  let mut old_banners = old_banners
+    .iter()
+    .map(|banner| DiffBanner::from(*banner))
+    .collect::<Vec<_>>();
+  let mut new_banners = new_banners
+    .iter()
+    .map(|banner| DiffBanner::from(*banner))
+    .collect::<Vec<_>>();
+
+  let mut index = 0;
+  while index < old_banners.len() {
+    let up_golden = &old_banners[index].up_golden;
+    match new_banners
+      .iter()
+      .position(|banner| banner.up_golden == *up_golden)
+    {
+      None => index += 1,
+      Some(position) => {
+        let old_banner = old_banners.remove(index);
+        let new_banner = new_banners.remove(position);
+        if old_banner != new_banner {
+          diff.changed_banners.push((old_banner, new_banner));
+        }
+      }
+    }
+  }
+
+  let mut new_banners = new_banners.into_iter();
+  for old_banner in old_banners {
+    match new_banners.next() {
+      Some(new_banner) => diff.changed_banners.push((old_banner, new_banner)),
+      None => diff.removed_banners.push(old_banner),
+    }
+  }
+  diff.added_banners.extend(new_banners);
+}
+
+/// Compare two raw metadata and returns the differences.
+pub fn diff(old: &RawMetadata, new: &RawMetadata) -> MetadataDiff {
+  let old = old
+    .as_ref()
+    .iter()
+    .map(|business| (business.id, business))
+    .collect::<BTreeMap<_, _>>();
+
+  let new = new
+    .as_ref()
+    .iter()
+    .map(|business| (business.id, business))
+    .collect::<BTreeMap<_, _>>();
+
+  let business_ids = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+  let businesses = business_ids
+    .into_iter()
+    .map(|id| diff_business(*id, old.get(id).copied(), new.get(id).copied()))
+    .filter(|business| !business.is_empty())
+    .collect();
+
+  MetadataDiff { businesses }
+}
+
+// endregion
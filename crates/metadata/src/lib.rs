@@ -1,8 +1,13 @@
 #![forbid(unsafe_code)]
 
+pub mod compact;
 pub mod def;
+pub mod diff;
+pub mod overlay;
 pub mod raw;
+pub mod resolve;
 mod types;
+pub mod validate;
 
 pub use types::*;
 
@@ -0,0 +1,312 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::diff::DiffBanner;
+use crate::raw::{
+  RawMetadata, RawMetadataBanner, RawMetadataBusiness, RawMetadataCategorization, RawMetadataI18n,
+};
+
+// User metadata overlay.
+//
+// The overlay has the same schema as the raw metadata, but is partial:
+//   * `Categories` and `Banners` can be omitted.
+//   * A locale can be omitted, or have fewer item names than the entries.
+//     The missing names fall back to the name of `en-us` or another locale.
+//
+// It is merged on top of the base (the official) metadata. The base always wins:
+// items and banners already present in the base are ignored and reported as superseded,
+// so they can be dropped from the overlay.
+
+/// Preferred fallback locale for the missing item names.
+const FALLBACK_LOCALE: &str = "en-us";
+
+/// The user metadata overlay, the partial raw metadata. (See above)
+/// The official raw metadata is strict, see `RawMetadata`.
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub struct OverlayMetadata(Vec<OverlayMetadataBusiness>);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct OverlayMetadataBusiness {
+  pub id: u8,
+  #[serde(default)]
+  pub categories: Vec<RawMetadataCategorization>,
+  #[serde(default)]
+  pub banners: Vec<RawMetadataBanner>,
+}
+
+impl AsRef<Vec<OverlayMetadataBusiness>> for OverlayMetadata {
+  fn as_ref(&self) -> &Vec<OverlayMetadataBusiness> {
+    &self.0
+  }
+}
+
+impl From<RawMetadata> for OverlayMetadata {
+  fn from(value: RawMetadata) -> Self {
+    Self(
+      value
+        .into_inner()
+        .into_iter()
+        .map(|business| OverlayMetadataBusiness {
+          id: business.id,
+          categories: business.categories,
+          banners: business.banners,
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(feature = "json")]
+impl OverlayMetadata {
+  #[inline]
+  pub fn from_slice(bytes: &[u8]) -> serde_json::Result<Self> {
+    serde_json::from_slice(bytes)
+  }
+
+  #[allow(clippy::should_implement_trait)]
+  #[inline]
+  pub fn from_str(s: &str) -> serde_json::Result<Self> {
+    serde_json::from_str(s)
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayReport {
+  /// Items added by the overlay.
+  pub items: Vec<OverlayItem>,
+  /// Items of the overlay that are already in the base metadata.
+  pub superseded_items: Vec<OverlayItem>,
+  /// Banners added by the overlay.
+  pub banners: Vec<DiffBanner>,
+  /// Banners of the overlay that are already in the base metadata.
+  pub superseded_banners: Vec<DiffBanner>,
+}
+
+impl OverlayReport {
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+      && self.superseded_items.is_empty()
+      && self.banners.is_empty()
+      && self.superseded_banners.is_empty()
+  }
+
+  /// Whether the item is added by the overlay.
+  pub fn contains_item(&self, business_id: u8, item_id: u32) -> bool {
+    self
+      .items
+      .iter()
+      .any(|item| item.business_id == business_id && item.item_id == item_id)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayItem {
+  pub business_id: u8,
+  pub item_id: u32,
+  pub category: String,
+  pub rank_type: u8,
+  /// The item name of the fallback locale.
+  pub item_name: String,
+}
+
+// Pick the item name of the overlay for the locale, or the fallback.
+fn item_name_of<'a>(
+  categorization: &'a RawMetadataCategorization,
+  index: usize,
+  locale: &str,
+) -> Option<&'a str> {
+  categorization
+    .i18n
+    .get(locale)
+    .and_then(|i18n| i18n.entries.get(index))
+    .map(String::as_str)
+    .filter(|item_name| !item_name.trim().is_empty())
+}
+
+fn fallback_item_name(categorization: &RawMetadataCategorization, index: usize) -> Option<&str> {
+  item_name_of(categorization, index, FALLBACK_LOCALE).or_else(|| {
+    let locales = categorization.i18n.keys().collect::<BTreeSet<_>>();
+    locales
+      .into_iter()
+      .find_map(|locale| item_name_of(categorization, index, locale))
+  })
+}
+
+fn clone_banner(banner: &RawMetadataBanner) -> RawMetadataBanner {
+  RawMetadataBanner {
+    gacha_type: banner.gacha_type,
+    gacha_id: banner.gacha_id,
+    start_time: banner.start_time,
+    end_time: banner.end_time,
+    up_golden: banner.up_golden.clone(),
+    up_purple: banner.up_purple.clone(),
+    version: banner.version.clone(),
+  }
+}
+
+fn merge_business(
+  report: &mut OverlayReport,
+  base: &mut RawMetadataBusiness,
+  overlay: &OverlayMetadataBusiness,
+) {
+  let business_id = base.id;
+  let base_items = base
+    .categories
+    .iter()
+    .flat_map(|categorization| categorization.entries.iter().map(|(item_id, _)| *item_id))
+    .collect::<HashSet<_>>();
+
+  // A new business has the locales of the overlay
+  let mut locales = base
+    .categories
+    .iter()
+    .flat_map(|categorization| categorization.i18n.keys().cloned())
+    .collect::<BTreeSet<_>>();
+
+  if locales.is_empty() {
+    locales.extend(
+      overlay
+        .categories
+        .iter()
+        .flat_map(|categorization| categorization.i18n.keys().cloned()),
+    );
+  }
+
+  let mut added = HashSet::new();
+  for overlay_categorization in &overlay.categories {
+    for (index, (item_id, rank_type)) in overlay_categorization.entries.iter().enumerate() {
+      let fallback = fallback_item_name(overlay_categorization, index)
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| item_id.to_string());
+
+      let item = OverlayItem {
+        business_id,
+        item_id: *item_id,
+        category: overlay_categorization.category.clone(),
+        rank_type: *rank_type,
+        item_name: fallback.clone(),
+      };
+
+      if base_items.contains(item_id) {
+        report.superseded_items.push(item);
+        continue;
+      } else if !added.insert(*item_id) {
+        // Duplicate in the overlay
+        continue;
+      }
+
+      let categorization = match base
+        .categories
+        .iter()
+        .position(|categorization| categorization.category == overlay_categorization.category)
+      {
+        Some(position) => &mut base.categories[position],
+        None => {
+          let i18n = locales
+            .iter()
+            .map(|locale| {
+              let category_name = overlay_categorization
+                .i18n
+                .get(locale)
+                .or_else(|| overlay_categorization.i18n.get(FALLBACK_LOCALE))
+                .map_or(&overlay_categorization.category, |i18n| &i18n.category);
+
+              let i18n = RawMetadataI18n {
+                category: category_name.clone(),
+                entries: Vec::new(),
+              };
+
+              (locale.clone(), i18n)
+            })
+            .collect::<HashMap<_, _>>();
+
+          base.categories.push(RawMetadataCategorization {
+            category: overlay_categorization.category.clone(),
+            entries: Vec::new(),
+            i18n,
+          });
+
+          base.categories.last_mut().unwrap() // SAFETY
+        }
+      };
+
+      // Keep the entries and the item names aligned
+      categorization.entries.push((*item_id, *rank_type));
+      for (locale, i18n) in categorization.i18n.iter_mut() {
+        let item_name = item_name_of(overlay_categorization, index, locale).unwrap_or(&fallback);
+        i18n.entries.push(item_name.to_owned());
+      }
+
+      report.items.push(item);
+    }
+  }
+
+  // Banner identity, see `diff`
+  let banner_key = |banner: &RawMetadataBanner| {
+    (
+      banner.gacha_type,
+      banner.start_time,
+      banner.up_golden.iter().copied().collect::<BTreeSet<_>>(),
+    )
+  };
+
+  let mut base_banners = base.banners.iter().map(banner_key).collect::<HashSet<_>>();
+  let mut base_gacha_ids = base
+    .banners
+    .iter()
+    .filter_map(|banner| banner.gacha_id)
+    .collect::<HashSet<_>>();
+
+  for banner in &overlay.banners {
+    let is_superseded = base_banners.contains(&banner_key(banner))
+      || banner
+        .gacha_id
+        .is_some_and(|gacha_id| base_gacha_ids.contains(&gacha_id));
+
+    if is_superseded {
+      report.superseded_banners.push(DiffBanner::from(banner));
+      continue;
+    }
+
+    base_banners.insert(banner_key(banner));
+    base_gacha_ids.extend(banner.gacha_id);
+    base.banners.push(clone_banner(banner));
+    report.banners.push(DiffBanner::from(banner));
+  }
+}
+
+/// Merge the overlay on top of the base metadata.
+///
+/// Returns the merged metadata and what the overlay added or was superseded.
+pub fn merge(base: RawMetadata, overlay: &OverlayMetadata) -> (RawMetadata, OverlayReport) {
+  let mut businesses = base.into_inner();
+  let mut report = OverlayReport::default();
+
+  for overlay_business in overlay.as_ref() {
+    let position = match businesses
+      .iter()
+      .position(|business| business.id == overlay_business.id)
+    {
+      Some(position) => position,
+      None => {
+        businesses.push(RawMetadataBusiness {
+          id: overlay_business.id,
+          categories: Vec::new(),
+          banners: Vec::new(),
+        });
+
+        businesses.len() - 1
+      }
+    };
+
+    merge_business(&mut report, &mut businesses[position], overlay_business);
+  }
+
+  (RawMetadata::from(businesses), report)
+}
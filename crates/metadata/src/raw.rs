@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use time::OffsetDateTime;
 use time::serde::rfc3339;
 
@@ -26,6 +26,7 @@ use time::serde::rfc3339;
 //     Banners: [
 //       {
 //         GachaType: u32,
+//         GachaId  : Option<u32>,                 // 'Honkai: Star Rail', 'Zenless Zone Zero' only, 0 is none
 //         StartTime: "2025-01-01T06:00:00+08:00", // RFC 3339
 //         EndTime  : "2025-01-01T06:00:00+08:00", // RFC 3339
 //         UpGolden : [u32],                       // Item id set
@@ -96,7 +97,8 @@ pub struct RawMetadataI18n {
 #[serde(rename_all = "PascalCase")]
 pub struct RawMetadataBanner {
   pub gacha_type: u32,
-  //pub gacha_id: Option<u32>, // 'Honkai: Star Rail' only // Deprecated since v1.2.0
+  #[serde(default, deserialize_with = "deserialize_gacha_id")]
+  pub gacha_id: Option<u32>, // 'Honkai: Star Rail', 'Zenless Zone Zero' only
   #[serde(with = "rfc3339")]
   pub start_time: OffsetDateTime,
   #[serde(with = "rfc3339")]
@@ -106,6 +108,14 @@ pub struct RawMetadataBanner {
   pub version: Option<String>,
 }
 
+// The unknown gacha id of the banners is `0` in the published metadata
+fn deserialize_gacha_id<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  Ok(Option::<u32>::deserialize(deserializer)?.filter(|gacha_id| *gacha_id != 0))
+}
+
 #[cfg(feature = "json")]
 impl RawMetadata {
   #[inline]
@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Deref;
+
+use crate::{Entry, Metadata, MetadataLocale};
+
+// Item name resolution.
+//
+// The item name of the record may not be found exactly in its declared locale:
+//   * The locale is an alias or in a different case. (e.g.: `en`, `zh-CN`)
+//   * The punctuation, whitespace or full-width characters are different.
+//   * The declared locale is wrong, the name is from another locale.
+//   * The same name is used by different items. (e.g.: Traveler)
+//
+// The resolver tries in order:
+//   1. The declared locale, exactly and then normalized.
+//   2. All other locales, exactly and then normalized.
+// Multiple items are tie-broken by the rank type and the item type of the record,
+// and reported as ambiguous if still not unique.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntryNameQuery<'q> {
+  pub lang: &'q str,
+  pub item_name: &'q str,
+  /// Rank type of the record, used for tie-breaking.
+  pub rank_type: Option<u8>,
+  /// Category or category local name of the record, used for tie-breaking.
+  pub item_type: Option<&'q str>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryNameMatch {
+  Exact,
+  Normalized,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ResolvedEntry<'a> {
+  /// The entry of the declared locale if it exists, otherwise of the matched locale.
+  pub entry: Entry<'a>,
+  pub matched: EntryNameMatch,
+  /// The locale in which the item name is matched.
+  pub matched_locale: &'a str,
+}
+
+impl ResolvedEntry<'_> {
+  /// Whether the item name is not matched exactly in the declared locale.
+  #[inline]
+  pub fn is_fallback(&self) -> bool {
+    self.matched != EntryNameMatch::Exact || self.matched_locale != self.entry.locale
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResolveEntryError<'a> {
+  NotFound,
+  /// Sorted by item id.
+  Ambiguous {
+    candidates: Vec<Entry<'a>>,
+  },
+}
+
+/// Normalize the item name for comparison.
+///
+/// Full-width ASCII characters are converted to half-width,
+/// whitespace and punctuation are removed, and letters are lowercased.
+pub fn normalize_item_name(item_name: &str) -> String {
+  item_name
+    .chars()
+    .map(|c| match c {
+      // Full-width ASCII variants
+      '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+      _ => c,
+    })
+    .filter(|c| c.is_alphanumeric())
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+// Find the item ids of the name in the locale.
+fn find_item_ids(
+  locale: &dyn MetadataLocale,
+  item_name: &str,
+  matched: EntryNameMatch,
+) -> Vec<u32> {
+  match matched {
+    EntryNameMatch::Exact => locale
+      .entry_from_name(item_name)
+      .map(|entries| entries.into_iter().map(|entry| entry.item_id).collect())
+      .unwrap_or_default(),
+    EntryNameMatch::Normalized => {
+      let item_name = normalize_item_name(item_name);
+      if item_name.is_empty() {
+        return Vec::new();
+      }
+
+      locale
+        .iter_entries()
+        .filter(|entry| normalize_item_name(entry.item_name) == item_name)
+        .map(|entry| entry.item_id)
+        .collect()
+    }
+  }
+}
+
+fn is_same_item_type(entry: &Entry<'_>, item_type: &str) -> bool {
+  let item_type = normalize_item_name(item_type);
+  normalize_item_name(entry.category_name) == item_type
+    || normalize_item_name(entry.category) == item_type
+}
+
+/// Resolve the entry from the item name of the record.
+pub fn resolve_entry_from_name<'a>(
+  metadata: &'a dyn Metadata,
+  business_id: u8,
+  query: EntryNameQuery<'_>,
+) -> Result<ResolvedEntry<'a>, ResolveEntryError<'a>> {
+  let declared = metadata
+    .locale(business_id, query.lang)
+    .or_else(|| metadata.locale(business_id, &query.lang.to_ascii_lowercase()));
+
+  let mut others = metadata
+    .locales(business_id)
+    .ok_or(ResolveEntryError::NotFound)?
+    .map(Box::deref)
+    .filter(|locale| declared.is_none_or(|declared| declared.lang() != locale.lang()))
+    .collect::<Vec<_>>();
+
+  // Deterministic order
+  others.sort_by(|a, b| a.lang().cmp(b.lang()));
+
+  let stages = declared
+    .into_iter()
+    .flat_map(|declared| {
+      [EntryNameMatch::Exact, EntryNameMatch::Normalized].map(|matched| (vec![declared], matched))
+    })
+    .chain(
+      [EntryNameMatch::Exact, EntryNameMatch::Normalized].map(|matched| (others.clone(), matched)),
+    );
+
+  for (locales, matched) in stages {
+    // item_id : matched locale
+    let mut found = BTreeMap::<u32, &'a dyn MetadataLocale>::new();
+    for locale in locales {
+      for item_id in find_item_ids(locale, query.item_name, matched) {
+        found.entry(item_id).or_insert(locale);
+      }
+    }
+
+    if found.is_empty() {
+      continue;
+    }
+
+    let candidates = found
+      .into_iter()
+      .filter_map(|(item_id, matched_locale)| {
+        let entry = declared
+          .and_then(|declared| declared.entry_from_id(item_id))
+          .or_else(|| matched_locale.entry_from_id(item_id))?;
+
+        Some((entry, matched_locale.lang()))
+      })
+      .collect::<Vec<_>>();
+
+    return pick(candidates, matched, query);
+  }
+
+  Err(ResolveEntryError::NotFound)
+}
+
+fn pick<'a>(
+  mut candidates: Vec<(Entry<'a>, &'a str)>,
+  matched: EntryNameMatch,
+  query: EntryNameQuery<'_>,
+) -> Result<ResolvedEntry<'a>, ResolveEntryError<'a>> {
+  if candidates.len() > 1 {
+    let filtered = candidates
+      .iter()
+      .filter(|(entry, _)| {
+        query
+          .rank_type
+          .is_none_or(|rank_type| entry.rank_type == rank_type)
+      })
+      .filter(|(entry, _)| {
+        query
+          .item_type
+          .is_none_or(|item_type| is_same_item_type(entry, item_type))
+      })
+      .map(|(entry, _)| entry.item_id)
+      .collect::<BTreeSet<_>>();
+
+    // Keep all candidates if none of them matches.
+    if !filtered.is_empty() {
+      candidates.retain(|(entry, _)| filtered.contains(&entry.item_id));
+    }
+  }
+
+  if candidates.len() == 1 {
+    let (entry, matched_locale) = candidates.pop().unwrap(); // SAFETY
+    return Ok(ResolvedEntry {
+      entry,
+      matched,
+      matched_locale,
+    });
+  }
+
+  Err(ResolveEntryError::Ambiguous {
+    candidates: candidates.into_iter().map(|(entry, _)| entry).collect(),
+  })
+}
@@ -2,9 +2,17 @@ use std::collections::{HashMap, HashSet};
 
 use time::macros::datetime;
 
+use crate::Metadata;
+use crate::compact;
 use crate::def::bake;
+use crate::diff::diff;
+use crate::overlay::{OverlayMetadata, merge as merge_overlay};
 use crate::raw::{RawMetadata, RawMetadataBusiness};
 use crate::raw::{RawMetadataBanner, RawMetadataCategorization, RawMetadataI18n};
+use crate::resolve::{
+  EntryNameMatch, EntryNameQuery, ResolveEntryError, normalize_item_name, resolve_entry_from_name,
+};
+use crate::validate::{Severity, ValidationIssueKind, has_errors, validate};
 
 macro_rules! raw_metadata {
   (
@@ -31,6 +39,7 @@ macro_rules! raw_metadata {
           $(
             {
               gacha_type: $gacha_type:expr,
+              $( gacha_id: $gacha_id:expr, )?
               start_time: $start_time:expr,
               end_time: $end_time:expr,
               up_golden: [$($golden:expr),* $(,)? ],
@@ -66,10 +75,12 @@ macro_rules! raw_metadata {
         });
       )*
 
+      #[allow(unused_mut)]
       let mut banners_vec: Vec<RawMetadataBanner> = Vec::new();
       $(
         banners_vec.push(RawMetadataBanner {
           gacha_type: $gacha_type as u32,
+          gacha_id: None $( .or(Some($gacha_id)) )?,
           start_time: $start_time,
           end_time: $end_time,
           up_golden: HashSet::from_iter([$($golden),*]),
@@ -159,3 +170,957 @@ fn test_bake_metadata() {
 
   // TODO: more asserts
 }
+
+#[test]
+fn test_diff_metadata() {
+  let old: RawMetadata = raw_metadata!({
+    id: 0,
+    categories: [
+      {
+        category: "Character",
+        entries: [
+          (10000002, 5),
+          (10000003, 5),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Character",
+            entries: ["Kamisato Ayaka", "Jean"]
+          },
+        }
+      },
+      {
+        category: "Weapon",
+        entries: [
+          (11509, 5),
+          (11401, 4),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Weapon",
+            entries: ["Mistsplitter Reforged", "Favonius Sword"]
+          },
+        }
+      }
+    ],
+    banners: [
+      {
+        gacha_type: 301,
+        start_time: datetime!(2021-07-21 06:00:00 +08:00),
+        end_time: datetime!(2021-08-10 15:59:59 +08:00),
+        up_golden: [10000002],
+        up_purple: [],
+        version: None
+      },
+    ]
+  });
+
+  let new: RawMetadata = raw_metadata!({
+    id: 0,
+    categories: [
+      {
+        category: "Character",
+        entries: [
+          (10000002, 5),
+          (10000003, 5),
+          (10000005, 5),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Characters",
+            entries: ["Kamisato Ayaka", "Jean Gunnhildr", "Traveler"]
+          },
+          "zh-cn" => {
+            category: "角色",
+            entries: ["神里绫华", "琴", "旅行者"]
+          },
+        }
+      },
+      {
+        category: "Weapon",
+        entries: [
+          (11509, 5),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Weapon",
+            entries: ["Mistsplitter Reforged"]
+          },
+          "zh-cn" => {
+            category: "武器",
+            entries: ["雾切之回光"]
+          },
+        }
+      }
+    ],
+    banners: [
+      {
+        gacha_type: 301,
+        start_time: datetime!(2021-07-21 06:00:00 +08:00),
+        end_time: datetime!(2021-08-10 15:59:59 +08:00),
+        up_golden: [10000002],
+        up_purple: [],
+        version: Some("2.0".to_owned())
+      },
+      {
+        gacha_type: 301,
+        start_time: datetime!(2021-08-10 18:00:00 +08:00),
+        end_time: datetime!(2021-08-31 14:59:59 +08:00),
+        up_golden: [10000005],
+        up_purple: [],
+        version: Some("2.0".to_owned())
+      },
+    ]
+  });
+
+  assert!(diff(&old, &old).is_empty());
+
+  let diff = diff(&old, &new);
+  assert_eq!(diff.businesses.len(), 1);
+
+  let business = diff.business(0).unwrap();
+  assert_eq!(business.added_locales, vec!["zh-cn"]);
+  assert!(business.removed_locales.is_empty());
+  assert_eq!(
+    business
+      .added_items
+      .iter()
+      .map(|item| item.item_id)
+      .collect::<Vec<_>>(),
+    vec![10000005]
+  );
+  assert_eq!(
+    business.added_items_by_category().get("Character"),
+    Some(&1)
+  );
+  assert_eq!(business.removed_items.len(), 1);
+  assert_eq!(business.removed_items[0].item_id, 11401);
+  assert!(business.changed_items.is_empty());
+
+  assert_eq!(business.renamed_items.len(), 1);
+  assert_eq!(business.renamed_items[0].key, 10000003);
+  assert_eq!(business.renamed_items[0].old_name, "Jean");
+  assert_eq!(business.renamed_items[0].new_name, "Jean Gunnhildr");
+  assert_eq!(business.renamed_categories.len(), 1);
+  assert_eq!(business.renamed_categories[0].key, "Character");
+
+  assert_eq!(business.added_banners.len(), 1);
+  assert!(business.added_banners[0].up_golden.contains(&10000005));
+  assert!(business.removed_banners.is_empty());
+  assert_eq!(business.changed_banners.len(), 1);
+  assert_eq!(business.changed_banners[0].0.version, None);
+  assert_eq!(
+    business.changed_banners[0].1.version.as_deref(),
+    Some("2.0")
+  );
+}
+
+#[test]
+fn test_diff_metadata_double_banners() {
+  let banners = |up_goldens: [u32; 2]| {
+    raw_metadata!({
+      id: 1,
+      categories: [
+        {
+          category: "Character",
+          entries: [(1205, 5), (1208, 5), (1212, 5)],
+          i18n: {
+            "en-us" => {
+              category: "Character",
+              entries: ["Blade", "Fu Xuan", "Jingliu"]
+            },
+          }
+        }
+      ],
+      banners: [
+        {
+          gacha_type: 11,
+          start_time: datetime!(2024-02-06 12:00:00 +08:00),
+          end_time: datetime!(2024-02-26 14:59:59 +08:00),
+          up_golden: [up_goldens[0]],
+          up_purple: [],
+          version: None
+        },
+        {
+          gacha_type: 11,
+          start_time: datetime!(2024-02-06 12:00:00 +08:00),
+          end_time: datetime!(2024-02-26 14:59:59 +08:00),
+          up_golden: [up_goldens[1]],
+          up_purple: [],
+          version: None
+        },
+      ]
+    })
+  };
+
+  // Paired by the up golden, regardless of the order
+  assert!(diff(&banners([1205, 1208]), &banners([1208, 1205])).is_empty());
+
+  // The corrected up golden is a change
+  let diff = diff(&banners([1205, 1208]), &banners([1212, 1205]));
+  let business = diff.business(1).unwrap();
+  assert!(business.added_banners.is_empty());
+  assert!(business.removed_banners.is_empty());
+  assert_eq!(business.changed_banners.len(), 1);
+  assert!(business.changed_banners[0].0.up_golden.contains(&1208));
+  assert!(business.changed_banners[0].1.up_golden.contains(&1212));
+}
+
+#[test]
+fn test_validate_metadata() {
+  let raw: RawMetadata = raw_metadata!({
+    id: 1,
+    categories: [
+      {
+        category: "Character",
+        entries: [
+          (1001, 4),
+          (1102, 5),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Character",
+            entries: ["March 7th"]
+          },
+          "zh-cn" => {
+            category: "角色",
+            entries: ["三月七", "希儿"]
+          },
+        }
+      },
+      {
+        category: "Weapon",
+        entries: [
+          (1102, 5),
+          (23001, 5),
+        ],
+        i18n: {
+          "zh-cn" => {
+            category: "光锥",
+            entries: ["希儿", " "]
+          },
+        }
+      }
+    ],
+    banners: [
+      {
+        gacha_type: 11,
+        start_time: datetime!(2023-04-26 10:00:00 +08:00),
+        end_time: datetime!(2023-05-17 15:00:00 +08:00),
+        up_golden: [1102],
+        up_purple: [],
+        version: None
+      },
+      {
+        gacha_type: 11,
+        start_time: datetime!(2023-04-26 10:00:00 +08:00),
+        end_time: datetime!(2023-05-17 15:00:00 +08:00),
+        up_golden: [1003],
+        up_purple: [1001],
+        version: None
+      },
+      {
+        gacha_type: 11,
+        start_time: datetime!(2023-05-17 12:00:00 +08:00),
+        end_time: datetime!(2023-05-17 12:00:00 +08:00),
+        up_golden: [],
+        up_purple: [],
+        version: None
+      },
+    ]
+  });
+
+  let issues = validate(&raw);
+  assert!(has_errors(&issues));
+
+  let find = |path: &str| {
+    issues
+      .iter()
+      .find(|issue| issue.path == path)
+      .unwrap_or_else(|| panic!("No issue of path: {path}"))
+  };
+
+  assert_eq!(
+    find("$[0].Categories[0].I18n[\"en-us\"].Entries").kind,
+    ValidationIssueKind::MismatchedEntriesI18nLength {
+      locale: "en-us".to_owned(),
+      entries: 2,
+      item_names: 1
+    }
+  );
+  assert_eq!(
+    find("$[0].Categories[1].Entries[0]").kind,
+    ValidationIssueKind::DuplicateItem {
+      item_id: 1102,
+      first: "$[0].Categories[0].Entries[1]".to_owned()
+    }
+  );
+
+  let missing_locale = find("$[0].Categories[1].I18n");
+  assert_eq!(missing_locale.severity, Severity::Warning);
+  assert_eq!(
+    missing_locale.kind,
+    ValidationIssueKind::MissingLocale {
+      locale: "en-us".to_owned()
+    }
+  );
+  assert_eq!(
+    find("$[0].Categories[1].I18n[\"zh-cn\"].Entries[1]").kind,
+    ValidationIssueKind::EmptyItemName { item_id: 23001 }
+  );
+
+  // Double banners are not overlapping
+  assert_eq!(
+    find("$[0].Banners[1].UpGolden").kind,
+    ValidationIssueKind::MissingUpItem { item_id: 1003 }
+  );
+  assert!(
+    issues
+      .iter()
+      .all(|issue| issue.path != "$[0].Banners[1].UpPurple" && issue.path != "$[0].Banners[1]")
+  );
+
+  assert!(matches!(
+    find("$[0].Banners[2].EndTime").kind,
+    ValidationIssueKind::InvalidBannerTime { .. }
+  ));
+  assert_eq!(
+    find("$[0].Banners[2]").kind,
+    ValidationIssueKind::OverlappingBanner {
+      gacha_type: 11,
+      other: "$[0].Banners[0]".to_owned()
+    }
+  );
+
+  assert_eq!(issues.len(), 8);
+}
+
+#[test]
+fn test_query_banner_by_id() {
+  // 'Honkai: Star Rail' double banners
+  let raw: RawMetadata = raw_metadata!({
+    id: 1,
+    categories: [
+      {
+        category: "Character",
+        entries: [
+          (1208, 5),
+          (1205, 5),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Character",
+            entries: ["Fu Xuan", "Blade"]
+          },
+        }
+      }
+    ],
+    banners: [
+      {
+        gacha_type: 11,
+        gacha_id: 2029,
+        start_time: datetime!(2024-01-24 12:00:00 +08:00),
+        end_time: datetime!(2024-02-06 11:59:59 +08:00),
+        up_golden: [1208],
+        up_purple: [],
+        version: Some("1.6".to_owned())
+      },
+      {
+        gacha_type: 11,
+        gacha_id: 2030,
+        start_time: datetime!(2024-01-24 12:00:00 +08:00),
+        end_time: datetime!(2024-02-06 11:59:59 +08:00),
+        up_golden: [1205],
+        up_purple: [],
+        version: Some("1.6".to_owned())
+      },
+      {
+        gacha_type: 1,
+        start_time: datetime!(2023-04-26 10:00:00 +08:00),
+        end_time: datetime!(9999-12-31 23:59:59 +08:00),
+        up_golden: [],
+        up_purple: [],
+        version: None
+      },
+    ]
+  });
+
+  let baked = bake(raw).unwrap();
+
+  let banner = baked.query_banner_by_id(1, 2029).unwrap();
+  assert_eq!(banner.gacha_id(), Some(2029));
+  assert!(banner.is_up_golden(1208));
+
+  let banner = baked.query_banner_by_id(1, 2030).unwrap();
+  assert_eq!(banner.gacha_type(), 11);
+  assert!(banner.is_up_golden(1205));
+
+  assert!(baked.query_banner_by_id(1, 1001).is_none());
+  assert!(baked.query_banner_by_id(0, 2029).is_none());
+
+  let banner = baked
+    .query_banner(1, 1, datetime!(2024-01-01 00:00:00 +08:00))
+    .unwrap();
+  assert_eq!(banner.gacha_id(), None);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_embedded_metadata() {
+  // The metadata embedded into the app
+  let json = include_bytes!("../../../tauri/src/assets/GachaMetadata.json");
+  let raw = RawMetadata::from_slice(json).unwrap();
+
+  // Published without the gacha id
+  let hkrpg = raw
+    .as_ref()
+    .iter()
+    .find(|business| business.id == 1)
+    .unwrap();
+  assert!(hkrpg.banners.iter().all(|banner| banner.gacha_id.is_none()));
+
+  let issues = validate(&raw);
+  assert!(!has_errors(&issues), "{issues:#?}");
+
+  let baked = bake(raw).unwrap();
+  assert!(baked.query_banner_by_id(1, 0).is_none());
+}
+
+#[test]
+fn test_banners_timeline() {
+  let raw: RawMetadata = raw_metadata!({
+    id: 0,
+    categories: [
+      {
+        category: "Character",
+        entries: [
+          (10000002, 5),
+          (10000029, 5),
+          (10000033, 5),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Character",
+            entries: ["Kamisato Ayaka", "Klee", "Tartaglia"]
+          },
+        }
+      }
+    ],
+    banners: [
+      {
+        gacha_type: 301,
+        start_time: datetime!(2021-07-21 06:00:00 +08:00),
+        end_time: datetime!(2021-08-10 15:59:59 +08:00),
+        up_golden: [10000002],
+        up_purple: [],
+        version: Some("2.0".to_owned())
+      },
+      {
+        gacha_type: 301,
+        start_time: datetime!(2020-10-20 18:00:00 +08:00),
+        end_time: datetime!(2020-11-10 15:59:59 +08:00),
+        up_golden: [10000029],
+        up_purple: [],
+        version: Some("1.0".to_owned())
+      },
+      {
+        gacha_type: 301,
+        start_time: datetime!(2020-11-11 06:00:00 +08:00),
+        end_time: datetime!(2020-12-01 15:59:59 +08:00),
+        up_golden: [10000033],
+        up_purple: [],
+        version: Some("1.1".to_owned())
+      },
+    ]
+  });
+
+  let baked = bake(raw).unwrap();
+  let timeline = baked.banners(0, 301).unwrap();
+
+  assert_eq!(
+    timeline
+      .iter()
+      .map(|banner| banner.version())
+      .collect::<Vec<_>>(),
+    vec![Some("1.0"), Some("1.1"), Some("2.0")]
+  );
+  assert!(timeline[0].up_golden().contains(&10000029));
+  assert!(timeline[2].up_purple().is_empty());
+
+  assert!(baked.banners(0, 302).is_none());
+  assert!(baked.banners(1, 301).is_none());
+}
+
+#[test]
+fn test_resolve_entry_from_name() {
+  let raw: RawMetadata = raw_metadata!({
+    id: 0,
+    categories: [
+      {
+        category: "Character",
+        entries: [
+          (10000005, 5),
+          (10000007, 5),
+          (10000042, 5),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Character",
+            entries: ["Traveler", "Traveler", "Keqing"]
+          },
+          "zh-cn" => {
+            category: "角色",
+            entries: ["旅行者", "旅行者", "刻晴"]
+          },
+        }
+      },
+      {
+        category: "Weapon",
+        entries: [
+          (11509, 5),
+          (15502, 5),
+          (99999, 4),
+        ],
+        i18n: {
+          "en-us" => {
+            category: "Weapon",
+            entries: ["Mistsplitter Reforged", "Amos' Bow", "Keqing"]
+          },
+          "zh-cn" => {
+            category: "武器",
+            entries: ["雾切之回光", "阿莫斯之弓", "刻晴"]
+          },
+        }
+      }
+    ],
+    banners: []
+  });
+
+  let baked = bake(raw).unwrap();
+  let query = |lang, item_name, rank_type, item_type| EntryNameQuery {
+    lang,
+    item_name,
+    rank_type,
+    item_type,
+  };
+
+  assert_eq!(normalize_item_name("Ａｍｏｓ’ Bow"), "amosbow");
+  assert_eq!(normalize_item_name("  雾切 · 之回光 "), "雾切之回光");
+
+  // Exact
+  let resolved =
+    resolve_entry_from_name(&*baked, 0, query("en-us", "Amos' Bow", None, None)).unwrap();
+  assert_eq!(resolved.entry.item_id, 15502);
+  assert_eq!(resolved.matched, EntryNameMatch::Exact);
+  assert!(!resolved.is_fallback());
+
+  // Alias and case of locale
+  let resolved =
+    resolve_entry_from_name(&*baked, 0, query("zh-CN", "雾切之回光", None, None)).unwrap();
+  assert_eq!(resolved.entry.item_id, 11509);
+  assert_eq!(resolved.entry.locale, "zh-cn");
+
+  // Normalized
+  let resolved =
+    resolve_entry_from_name(&*baked, 0, query("en", "Ａｍｏｓ’ Bow", None, None)).unwrap();
+  assert_eq!(resolved.entry.item_id, 15502);
+  assert_eq!(resolved.matched, EntryNameMatch::Normalized);
+  assert!(resolved.is_fallback());
+
+  // Cross locale, the entry is of the declared locale
+  let resolved =
+    resolve_entry_from_name(&*baked, 0, query("en-us", "阿莫斯之弓", None, None)).unwrap();
+  assert_eq!(resolved.entry.item_id, 15502);
+  assert_eq!(resolved.entry.item_name, "Amos' Bow");
+  assert_eq!(resolved.matched_locale, "zh-cn");
+  assert!(resolved.is_fallback());
+
+  // Tie-break by the rank type and the item type
+  let resolved =
+    resolve_entry_from_name(&*baked, 0, query("en-us", "Keqing", Some(5), None)).unwrap();
+  assert_eq!(resolved.entry.item_id, 10000042);
+  let resolved =
+    resolve_entry_from_name(&*baked, 0, query("zh-cn", "刻晴", None, Some("武器"))).unwrap();
+  assert_eq!(resolved.entry.item_id, 99999);
+
+  // Ambiguous
+  match resolve_entry_from_name(
+    &*baked,
+    0,
+    query("en-us", "Traveler", Some(5), Some("Character")),
+  ) {
+    Err(ResolveEntryError::Ambiguous { candidates }) => assert_eq!(
+      candidates
+        .iter()
+        .map(|entry| entry.item_id)
+        .collect::<Vec<_>>(),
+      vec![10000005, 10000007]
+    ),
+    other => panic!("Unexpected: {other:?}"),
+  }
+
+  // Not found
+  assert_eq!(
+    resolve_entry_from_name(&*baked, 0, query("en-us", "Unknown", None, None)),
+    Err(ResolveEntryError::NotFound)
+  );
+  assert_eq!(
+    resolve_entry_from_name(&*baked, 1, query("en-us", "Keqing", None, None)),
+    Err(ResolveEntryError::NotFound)
+  );
+}
+
+#[test]
+fn test_merge_overlay() {
+  let base: RawMetadata = raw_metadata!({
+    id: 0,
+    categories: [
+      {
+        category: "Character",
+        entries: [(10000002, 5)],
+        i18n: {
+          "en-us" => { category: "Character", entries: ["Kamisato Ayaka"] },
+          "zh-cn" => { category: "角色", entries: ["神里绫华"] },
+        }
+      }
+    ],
+    banners: [
+      {
+        gacha_type: 301,
+        start_time: datetime!(2020-09-28 10:00:00 +08:00),
+        end_time: datetime!(2020-10-18 17:59:59 +08:00),
+        up_golden: [10000002],
+        up_purple: [],
+        version: Some("1.0".into())
+      }
+    ]
+  });
+
+  // Partial: missing locale, fewer item names and the new category
+  let overlay = OverlayMetadata::from(raw_metadata!(
+    {
+      id: 0,
+      categories: [
+        {
+          category: "Character",
+          entries: [(10000002, 5), (10000999, 5), (10000998, 4)],
+          i18n: {
+            "en-us" => { category: "Character", entries: ["Kamisato Ayaka", "Newcomer"] },
+          }
+        },
+        {
+          category: "Weapon",
+          entries: [(11999, 5)],
+          i18n: {
+            "zh-cn" => { category: "武器", entries: ["新武器"] },
+          }
+        }
+      ],
+      banners: [
+        {
+          gacha_type: 301,
+          start_time: datetime!(2020-09-28 10:00:00 +08:00),
+          end_time: datetime!(2020-10-18 17:59:59 +08:00),
+          up_golden: [10000002],
+          up_purple: [],
+          version: Some("1.0".into())
+        },
+        {
+          gacha_type: 301,
+          start_time: datetime!(2099-01-01 10:00:00 +08:00),
+          end_time: datetime!(2099-01-21 17:59:59 +08:00),
+          up_golden: [10000999],
+          up_purple: [],
+          version: None
+        }
+      ]
+    },
+    {
+      id: 1,
+      categories: [
+        {
+          category: "Character",
+          entries: [(1999, 5)],
+          i18n: {
+            "en-us" => { category: "Character", entries: ["Trailblazer"] },
+          }
+        }
+      ],
+      banners: []
+    }
+  ));
+
+  let (merged, report) = merge_overlay(base, &overlay);
+  assert!(!has_errors(&validate(&merged)));
+
+  assert_eq!(
+    report
+      .items
+      .iter()
+      .map(|item| (item.business_id, item.item_id))
+      .collect::<Vec<_>>(),
+    vec![(0, 10000999), (0, 10000998), (0, 11999), (1, 1999)]
+  );
+  assert_eq!(report.superseded_items.len(), 1);
+  assert_eq!(report.superseded_items[0].item_id, 10000002);
+  assert_eq!(report.banners.len(), 1);
+  assert_eq!(report.superseded_banners.len(), 1);
+  assert!(report.contains_item(0, 11999));
+  assert!(!report.contains_item(0, 10000002));
+
+  // Item name without any locale falls back to the item id
+  assert_eq!(report.items[1].item_name, "10000998");
+
+  let baked = bake(merged).unwrap();
+  let en_us = baked.locale(0, "en-us").unwrap();
+  let zh_cn = baked.locale(0, "zh-cn").unwrap();
+
+  // Base is unchanged
+  assert_eq!(
+    en_us.entry_from_id(10000002).unwrap().item_name,
+    "Kamisato Ayaka"
+  );
+  assert_eq!(zh_cn.entry_from_id(10000002).unwrap().item_name, "神里绫华");
+
+  // Missing locale falls back to `en-us`, or other locales
+  assert_eq!(en_us.entry_from_id(10000999).unwrap().item_name, "Newcomer");
+  assert_eq!(zh_cn.entry_from_id(10000999).unwrap().item_name, "Newcomer");
+  assert_eq!(en_us.entry_from_id(11999).unwrap().item_name, "新武器");
+  assert_eq!(en_us.entry_from_id(11999).unwrap().category_name, "Weapon");
+  assert_eq!(zh_cn.entry_from_id(11999).unwrap().category_name, "武器");
+
+  // New business
+  assert!(
+    baked
+      .locale(1, "en-us")
+      .unwrap()
+      .entry_from_id(1999)
+      .is_some()
+  );
+
+  let timeline = baked.banners(0, 301).unwrap();
+  assert_eq!(timeline.len(), 2);
+  assert!(timeline[1].is_up_golden(10000999));
+}
+
+fn compact_fixture() -> RawMetadata {
+  raw_metadata!(
+    {
+      id: 0,
+      categories: [
+        {
+          category: "Character",
+          entries: [
+            (10000005, 5),
+            (10000007, 5),
+            (10000042, 5),
+            (10000023, 4),
+          ],
+          i18n: {
+            "en-us" => {
+              category: "Character",
+              entries: ["Traveler", "Traveler", "Keqing", "Xiangling"]
+            },
+            "zh-cn" => {
+              category: "角色",
+              entries: ["旅行者", "旅行者", "刻晴", "香菱"]
+            },
+          }
+        },
+        {
+          category: "Weapon",
+          entries: [
+            (11509, 5),
+            (13407, 4),
+          ],
+          i18n: {
+            "en-us" => {
+              category: "Weapon",
+              entries: ["Mistsplitter Reforged", "Favonius Lance"]
+            },
+          }
+        }
+      ],
+      banners: [
+        {
+          gacha_type: 301,
+          start_time: datetime!(2021-07-21 06:00:00 +08:00),
+          end_time: datetime!(2021-08-10 15:59:59 +08:00),
+          up_golden: [10000042],
+          up_purple: [10000023],
+          version: Some("2.0".to_owned())
+        },
+        {
+          gacha_type: 200,
+          start_time: datetime!(2020-09-15 06:00:00 +08:00),
+          end_time: datetime!(9999-12-31 23:59:59 +08:00),
+          up_golden: [],
+          up_purple: [],
+          version: None
+        },
+      ]
+    },
+    {
+      id: 1,
+      categories: [
+        {
+          category: "Character",
+          entries: [(1208, 5)],
+          i18n: {
+            "en-us" => {
+              category: "Character",
+              entries: ["Fu Xuan"]
+            },
+          }
+        }
+      ],
+      banners: [
+        {
+          gacha_type: 11,
+          gacha_id: 2029,
+          start_time: datetime!(2024-01-24 12:00:00 +08:00),
+          end_time: datetime!(2024-02-06 11:59:59 -05:00),
+          up_golden: [1208],
+          up_purple: [],
+          version: Some("1.6".to_owned())
+        },
+      ]
+    },
+  )
+}
+
+// Both metadata should produce identical lookups.
+fn assert_metadata_eq(expected: &dyn Metadata, actual: &dyn Metadata, businesses: &[u8]) {
+  for business_id in businesses.iter().copied().chain([u8::MAX]) {
+    let mut expected_langs = expected
+      .locales(business_id)
+      .map(|locales| locales.map(|locale| locale.lang()).collect::<Vec<_>>())
+      .unwrap_or_default();
+    let mut actual_langs = actual
+      .locales(business_id)
+      .map(|locales| locales.map(|locale| locale.lang()).collect::<Vec<_>>())
+      .unwrap_or_default();
+
+    expected_langs.sort_unstable();
+    actual_langs.sort_unstable();
+    assert_eq!(expected_langs, actual_langs);
+
+    for lang in expected_langs {
+      let expected_locale = expected.locale(business_id, lang).unwrap();
+      let actual_locale = actual.locale(business_id, lang).unwrap();
+
+      let entries = expected_locale.entries();
+      assert_eq!(entries, actual_locale.entries());
+
+      for entry in entries.values() {
+        assert_eq!(
+          actual_locale.entry_from_id(entry.item_id).as_ref(),
+          Some(entry)
+        );
+        assert_eq!(
+          expected_locale.entry_from_name(entry.item_name),
+          actual_locale.entry_from_name(entry.item_name)
+        );
+        assert!(
+          actual_locale
+            .entry_from_name_first(entry.item_name)
+            .is_some()
+        );
+      }
+
+      assert!(actual_locale.entry_from_id(u32::MAX).is_none());
+      assert!(actual_locale.entry_from_name("").is_none());
+    }
+
+    for gacha_type in 0..=u16::MAX as u32 {
+      let expected_banners = expected.banners(business_id, gacha_type);
+      let actual_banners = actual.banners(business_id, gacha_type);
+      assert_eq!(expected_banners.is_some(), actual_banners.is_some());
+
+      let (Some(expected_banners), Some(actual_banners)) = (expected_banners, actual_banners)
+      else {
+        continue;
+      };
+
+      assert_eq!(expected_banners.len(), actual_banners.len());
+      for (expected_banner, actual_banner) in expected_banners.iter().zip(&actual_banners) {
+        assert_eq!(expected_banner.gacha_id(), actual_banner.gacha_id());
+        assert_eq!(expected_banner.start_time(), actual_banner.start_time());
+        assert_eq!(
+          expected_banner.start_time().offset(),
+          actual_banner.start_time().offset()
+        );
+        assert_eq!(expected_banner.end_time(), actual_banner.end_time());
+        assert_eq!(expected_banner.version(), actual_banner.version());
+        assert_eq!(expected_banner.up_golden(), actual_banner.up_golden());
+        assert_eq!(expected_banner.up_purple(), actual_banner.up_purple());
+
+        let point = *expected_banner.start_time();
+        assert_eq!(
+          expected
+            .query_banner(business_id, gacha_type, point)
+            .map(|banner| banner.gacha_id()),
+          actual
+            .query_banner(business_id, gacha_type, point)
+            .map(|banner| banner.gacha_id())
+        );
+
+        if let Some(gacha_id) = expected_banner.gacha_id() {
+          assert_eq!(
+            actual
+              .query_banner_by_id(business_id, gacha_id)
+              .map(|banner| banner.gacha_type()),
+            Some(gacha_type)
+          );
+        }
+      }
+    }
+  }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_overlay_metadata_partial() {
+  let json = r#"[{ "Id": 0, "Categories": [] }]"#;
+
+  // The official raw metadata is strict
+  assert!(RawMetadata::from_str(json).is_err());
+
+  // The overlay may omit the categories or banners
+  let overlay = OverlayMetadata::from_str(json).unwrap();
+  assert_eq!(overlay.as_ref().len(), 1);
+  assert!(overlay.as_ref()[0].banners.is_empty());
+}
+
+#[test]
+fn test_compact_metadata() {
+  let bytes = compact::encode(&compact_fixture());
+  let expected = bake(compact_fixture()).unwrap();
+  let actual = compact::load(bytes.as_slice()).unwrap();
+  assert_metadata_eq(&*expected, &*actual, &[0, 1]);
+
+  // Aliases
+  assert_eq!(actual.locale(0, "en").unwrap().lang(), "en-us");
+
+  // Round trip
+  let decoded = bake(compact::decode(&bytes).unwrap()).unwrap();
+  assert_metadata_eq(&*expected, &*decoded, &[0, 1]);
+  assert!(diff(&compact_fixture(), &compact::decode(&bytes).unwrap()).is_empty());
+
+  // Invalid
+  assert!(matches!(
+    compact::load(&b"JSON"[..]),
+    Err(compact::CompactMetadataError::InvalidMagic)
+  ));
+  assert!(matches!(
+    compact::load(&bytes[..bytes.len() / 2]),
+    Err(compact::CompactMetadataError::UnexpectedEof { .. })
+  ));
+
+  let mut unsupported = bytes.clone();
+  unsupported[4] = 0xFF;
+  assert!(matches!(
+    compact::load(unsupported),
+    Err(compact::CompactMetadataError::UnsupportedVersion { .. })
+  ));
+}
@@ -26,11 +26,29 @@ pub trait Metadata: fmt::Debug + Send + Sync {
     gacha_type: u32,
     point: OffsetDateTime,
   ) -> Option<&dyn MetadataBanner>;
+
+  /// Query the banner by the `gacha_id` of the record.
+  /// ('Honkai: Star Rail' and 'Zenless Zone Zero' only)
+  ///
+  /// Unlike `query_banner`, it's deterministic for double banners.
+  fn query_banner_by_id(&self, business_id: u8, gacha_id: u32) -> Option<&dyn MetadataBanner>;
+
+  /// Returns the banners timeline of the gacha type, ordered by start time.
+  fn banners(&self, business_id: u8, gacha_type: u32) -> Option<Vec<&dyn MetadataBanner>>;
 }
 
 pub trait MetadataLocale: fmt::Debug + Send + Sync {
   fn lang(&self) -> &str;
-  fn entries(&self) -> HashMap<u32, Entry<'_>>;
+
+  /// Iterate over the entries without collecting.
+  fn iter_entries(&self) -> Box<dyn Iterator<Item = Entry<'_>> + '_>;
+
+  fn entries(&self) -> HashMap<u32, Entry<'_>> {
+    self
+      .iter_entries()
+      .map(|entry| (entry.item_id, entry))
+      .collect()
+  }
 
   fn entry_from_id(&self, item_id: u32) -> Option<Entry<'_>>;
 
@@ -40,10 +58,14 @@ pub trait MetadataLocale: fmt::Debug + Send + Sync {
 
 pub trait MetadataBanner: fmt::Debug + Send + Sync {
   fn gacha_type(&self) -> u32;
+  fn gacha_id(&self) -> Option<u32>;
   fn start_time(&self) -> &OffsetDateTime;
   fn end_time(&self) -> &OffsetDateTime;
   fn version(&self) -> Option<&str>;
 
+  fn up_golden(&self) -> &HashSet<u32>;
+  fn up_purple(&self) -> &HashSet<u32>;
+
   fn is_up_golden(&self, item_id: u32) -> bool;
   fn is_up_purple(&self, item_id: u32) -> bool;
 }
@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::serde::rfc3339;
+
+use crate::raw::{RawMetadata, RawMetadataBusiness};
+
+// Validation of the raw metadata.
+//
+// Baking only fails on a few structural errors,
+// the validator reports all problems with the JSON path of the raw metadata.
+// It should be run before a metadata file is published or loaded.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Severity {
+  Warning,
+  Error,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Warning => "warning",
+      Self::Error => "error",
+    })
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind")]
+pub enum ValidationIssueKind {
+  DuplicateBusiness {
+    business_id: u8,
+    first: String,
+  },
+  DuplicateCategory {
+    category: String,
+    first: String,
+  },
+  DuplicateItem {
+    item_id: u32,
+    first: String,
+  },
+  MismatchedEntriesI18nLength {
+    locale: String,
+    entries: usize,
+    item_names: usize,
+  },
+  MissingLocale {
+    locale: String,
+  },
+  EmptyItemName {
+    item_id: u32,
+  },
+  InvalidBannerTime {
+    #[serde(with = "rfc3339")]
+    start_time: OffsetDateTime,
+    #[serde(with = "rfc3339")]
+    end_time: OffsetDateTime,
+  },
+  DuplicateGachaId {
+    gacha_id: u32,
+    first: String,
+  },
+  OverlappingBanner {
+    gacha_type: u32,
+    other: String,
+  },
+  MissingUpItem {
+    item_id: u32,
+  },
+}
+
+impl fmt::Display for ValidationIssueKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::DuplicateBusiness { business_id, first } => {
+        write!(f, "Duplicate business id {business_id} (First: {first})")
+      }
+      Self::DuplicateCategory { category, first } => {
+        write!(f, "Duplicate category {category:?} (First: {first})")
+      }
+      Self::DuplicateItem { item_id, first } => {
+        write!(f, "Duplicate item id {item_id} (First: {first})")
+      }
+      Self::MismatchedEntriesI18nLength {
+        locale,
+        entries,
+        item_names,
+      } => write!(
+        f,
+        "Mismatched i18n entries length of locale {locale:?}: {item_names} (Expected: {entries})"
+      ),
+      Self::MissingLocale { locale } => {
+        write!(f, "Missing locale {locale:?} which other categories have")
+      }
+      Self::EmptyItemName { item_id } => write!(f, "Empty item name of item id {item_id}"),
+      Self::InvalidBannerTime {
+        start_time,
+        end_time,
+      } => write!(
+        f,
+        "Banner end time {end_time} is not after the start time {start_time}"
+      ),
+      Self::DuplicateGachaId { gacha_id, first } => {
+        write!(f, "Duplicate gacha id {gacha_id} (First: {first})")
+      }
+      Self::OverlappingBanner { gacha_type, other } => {
+        write!(
+          f,
+          "Overlapping banner of gacha type {gacha_type} (Other: {other})"
+        )
+      }
+      Self::MissingUpItem { item_id } => {
+        write!(f, "Up item id {item_id} is missing from the entries")
+      }
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+  pub severity: Severity,
+  /// JSON path of the raw metadata. e.g.: `$[0].Categories[1].I18n["en-us"].Entries`
+  pub path: String,
+  #[serde(flatten)]
+  pub kind: ValidationIssueKind,
+}
+
+impl fmt::Display for ValidationIssue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}: {}", self.severity, self.path, self.kind)
+  }
+}
+
+#[derive(Default)]
+struct Validator {
+  issues: Vec<ValidationIssue>,
+}
+
+impl Validator {
+  fn report(&mut self, severity: Severity, path: String, kind: ValidationIssueKind) {
+    self.issues.push(ValidationIssue {
+      severity,
+      path,
+      kind,
+    });
+  }
+
+  fn validate_business(&mut self, path: &str, business: &RawMetadataBusiness) {
+    // item_id : path of the entry
+    let mut items = HashMap::<u32, String>::new();
+    let mut categories = HashMap::<&str, String>::new();
+    let locales = business
+      .categories
+      .iter()
+      .flat_map(|categorization| categorization.i18n.keys())
+      .map(String::as_str)
+      .collect::<BTreeSet<_>>();
+
+    for (category_index, categorization) in business.categories.iter().enumerate() {
+      let category_path = format!("{path}.Categories[{category_index}]");
+
+      if let Some(first) = categories.get(categorization.category.as_str()) {
+        self.report(
+          Severity::Error,
+          format!("{category_path}.Category"),
+          ValidationIssueKind::DuplicateCategory {
+            category: categorization.category.clone(),
+            first: first.clone(),
+          },
+        );
+      } else {
+        categories.insert(&categorization.category, category_path.clone());
+      }
+
+      for (entry_index, (item_id, _)) in categorization.entries.iter().enumerate() {
+        let entry_path = format!("{category_path}.Entries[{entry_index}]");
+        if let Some(first) = items.get(item_id) {
+          self.report(
+            Severity::Error,
+            entry_path,
+            ValidationIssueKind::DuplicateItem {
+              item_id: *item_id,
+              first: first.clone(),
+            },
+          );
+        } else {
+          items.insert(*item_id, entry_path);
+        }
+      }
+
+      for locale in &locales {
+        let i18n_path = format!("{category_path}.I18n[{locale:?}]");
+        let Some(i18n) = categorization.i18n.get(*locale) else {
+          self.report(
+            Severity::Warning,
+            format!("{category_path}.I18n"),
+            ValidationIssueKind::MissingLocale {
+              locale: (*locale).to_owned(),
+            },
+          );
+          continue;
+        };
+
+        if i18n.entries.len() != categorization.entries.len() {
+          self.report(
+            Severity::Error,
+            format!("{i18n_path}.Entries"),
+            ValidationIssueKind::MismatchedEntriesI18nLength {
+              locale: (*locale).to_owned(),
+              entries: categorization.entries.len(),
+              item_names: i18n.entries.len(),
+            },
+          );
+        }
+
+        for (entry_index, ((item_id, _), item_name)) in
+          categorization.entries.iter().zip(&i18n.entries).enumerate()
+        {
+          if item_name.trim().is_empty() {
+            self.report(
+              Severity::Warning,
+              format!("{i18n_path}.Entries[{entry_index}]"),
+              ValidationIssueKind::EmptyItemName { item_id: *item_id },
+            );
+          }
+        }
+      }
+    }
+
+    // gacha_type : [(start_time, end_time, path)]
+    let mut timelines = BTreeMap::<u32, Vec<(OffsetDateTime, OffsetDateTime, String)>>::new();
+    // gacha_id : path of the banner
+    let mut gacha_ids = HashMap::<u32, String>::new();
+
+    for (banner_index, banner) in business.banners.iter().enumerate() {
+      let banner_path = format!("{path}.Banners[{banner_index}]");
+
+      if let Some(gacha_id) = banner.gacha_id {
+        if let Some(first) = gacha_ids.get(&gacha_id) {
+          self.report(
+            Severity::Error,
+            format!("{banner_path}.GachaId"),
+            ValidationIssueKind::DuplicateGachaId {
+              gacha_id,
+              first: first.clone(),
+            },
+          );
+        } else {
+          gacha_ids.insert(gacha_id, banner_path.clone());
+        }
+      }
+
+      if banner.end_time <= banner.start_time {
+        self.report(
+          Severity::Error,
+          format!("{banner_path}.EndTime"),
+          ValidationIssueKind::InvalidBannerTime {
+            start_time: banner.start_time,
+            end_time: banner.end_time,
+          },
+        );
+      }
+
+      for (field, up_items) in [
+        ("UpGolden", &banner.up_golden),
+        ("UpPurple", &banner.up_purple),
+      ] {
+        let mut missing = up_items
+          .iter()
+          .filter(|item_id| !items.contains_key(item_id))
+          .copied()
+          .collect::<Vec<_>>();
+
+        missing.sort_unstable();
+        for item_id in missing {
+          self.report(
+            Severity::Error,
+            format!("{banner_path}.{field}"),
+            ValidationIssueKind::MissingUpItem { item_id },
+          );
+        }
+      }
+
+      let timeline = timelines.entry(banner.gacha_type).or_default();
+      for (start_time, end_time, other) in timeline.iter() {
+        // Double banners run in the same period with the same gacha type.
+        // (e.g.: 'Honkai: Star Rail' character event warp)
+        let is_double = *start_time == banner.start_time && *end_time == banner.end_time;
+        if !is_double && banner.start_time < *end_time && *start_time < banner.end_time {
+          self.report(
+            Severity::Warning,
+            banner_path.clone(),
+            ValidationIssueKind::OverlappingBanner {
+              gacha_type: banner.gacha_type,
+              other: other.clone(),
+            },
+          );
+        }
+      }
+
+      timeline.push((banner.start_time, banner.end_time, banner_path));
+    }
+  }
+}
+
+/// Validate the raw metadata and returns all issues found.
+pub fn validate(raw: &RawMetadata) -> Vec<ValidationIssue> {
+  let mut validator = Validator::default();
+  let mut businesses = HashMap::<u8, String>::new();
+
+  for (business_index, business) in raw.as_ref().iter().enumerate() {
+    let path = format!("$[{business_index}]");
+
+    if let Some(first) = businesses.get(&business.id) {
+      validator.report(
+        Severity::Error,
+        format!("{path}.Id"),
+        ValidationIssueKind::DuplicateBusiness {
+          business_id: business.id,
+          first: first.clone(),
+        },
+      );
+    } else {
+      businesses.insert(business.id, path.clone());
+    }
+
+    validator.validate_business(&path, business);
+  }
+
+  validator.issues
+}
+
+/// Returns `true` if any issue is an error.
+pub fn has_errors(issues: &[ValidationIssue]) -> bool {
+  issues.iter().any(|issue| issue.severity == Severity::Error)
+}
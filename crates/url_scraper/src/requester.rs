@@ -1,5 +1,6 @@
 use std::pin::Pin;
-use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 
 use exponential_backoff::Backoff;
@@ -32,6 +33,39 @@ pub enum GachaUrlRequestError {
 
   #[snafu(display("Request reached max attempts"))]
   ReachedMaxAttempts,
+
+  #[snafu(display("Request cancelled"))]
+  Cancelled,
+}
+
+/// A cheap, cloneable token used to cancel in-flight requests.
+///
+/// Cancellation is cooperative: it is checked before each attempt and after each sleep,
+/// so a request that has already been sent will still be awaited.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub fn cancel(&self) {
+    self.0.store(true, Ordering::SeqCst);
+  }
+
+  #[inline]
+  pub fn is_cancelled(&self) -> bool {
+    self.0.load(Ordering::SeqCst)
+  }
+
+  /// Returns `true` if both are clones of the same token.
+  #[inline]
+  pub fn ptr_eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
 }
 
 #[derive(Clone, Debug)]
@@ -76,6 +110,7 @@ pub trait GachaUrlRequester {
     options: AsQueriesOptions<'a>,
     retry: RetryOptions,
     sleeper: fn(Duration) -> S,
+    cancellation: Option<&'a CancellationToken>,
   ) -> Pin<Box<dyn Future<Output = Result<GachaLogsResponse, GachaUrlRequestError>> + Send + 'a>>
   where
     S: Future<Output = ()> + Send + 'static;
@@ -164,13 +199,21 @@ impl GachaUrlRequester for ParsedGachaUrl<'_> {
     options: AsQueriesOptions<'a>,
     retry: RetryOptions,
     sleeper: fn(Duration) -> S,
+    cancellation: Option<&'a CancellationToken>,
   ) -> Pin<Box<dyn Future<Output = Result<GachaLogsResponse, GachaUrlRequestError>> + Send + 'a>>
   where
     S: Future<Output = ()> + Send + 'static,
   {
+    let is_cancelled = move || cancellation.is_some_and(CancellationToken::is_cancelled);
+
     let f = async move {
       let backoff = retry.into_backoff();
       for duration in &backoff {
+        // Check before each attempt, this also covers the sleep of the previous one
+        if is_cancelled() {
+          return Err(GachaUrlRequestError::Cancelled);
+        }
+
         match self.request(endpoint, options.clone(), None).await {
           Ok(response) => return Ok(response),
           Err(error) => {
@@ -3,7 +3,7 @@ use std::time::Duration;
 use hg_game_biz::GachaLogEndpointType;
 use hg_url_finder::parse::{AsQueriesOptions, ParsedGachaUrl};
 
-use crate::requester::{GachaUrlRequestError, GachaUrlRequester, RetryOptions};
+use crate::requester::{CancellationToken, GachaUrlRequestError, GachaUrlRequester, RetryOptions};
 use crate::{GachaLog, GachaLogs};
 
 #[derive(Debug)]
@@ -14,6 +14,7 @@ pub enum GachaLogsScraperNotify<'d> {
   Data(&'d [GachaLog]),
   Completed(u32),
   Finished,
+  Cancelled,
 }
 
 // for abbreviation
@@ -34,6 +35,8 @@ pub struct GachaLogsScraper<'a, S> {
   retry: RetryOptions,
   sleeper: fn(Duration) -> S,
   notifier: Option<Notifier>,
+  cancellation: Option<CancellationToken>,
+  lang: Option<&'a str>,
 }
 
 impl<'a, S> GachaLogsScraper<'a, S>
@@ -45,20 +48,39 @@ where
     retry: RetryOptions,
     sleeper: fn(Duration) -> S,
     notifier: Option<Notifier>,
+    cancellation: Option<CancellationToken>,
   ) -> Self {
     Self {
       url,
       retry,
       sleeper,
       notifier,
+      cancellation,
+      lang: None,
     }
   }
 
+  /// Override the `lang` of the gacha url for subsequent scrapes.
+  /// `None` restores the original one.
+  #[inline]
+  pub fn set_lang(&mut self, lang: Option<&'a str>) {
+    self.lang = lang;
+  }
+
   #[inline]
   pub const fn url(&self) -> &ParsedGachaUrl<'_> {
     &self.url
   }
 
+  /// Returns `true` if the cancellation token has been cancelled.
+  #[inline]
+  pub fn is_cancelled(&self) -> bool {
+    self
+      .cancellation
+      .as_ref()
+      .is_some_and(CancellationToken::is_cancelled)
+  }
+
   #[allow(clippy::type_complexity)]
   pub async fn scrapes(
     &self,
@@ -73,11 +95,22 @@ where
         .await?;
 
       results.extend(logs);
+
+      // Don't continue with the remaining endpoints
+      if self.is_cancelled() {
+        break;
+      }
     }
 
     Ok(results)
   }
 
+  /// Scrape the gacha logs of the given gacha types.
+  ///
+  /// When cancelled, the logs of the gacha types that have been completely scraped are returned,
+  /// and the partially scraped one is discarded. Check [`Self::is_cancelled`] after returning.
+  /// A partial gacha type must not be saved, otherwise the next incremental scrape
+  /// would start from its newest log and leave a gap in the older ones.
   pub async fn scrape(
     &self,
     endpoint: GachaLogEndpointType,
@@ -88,14 +121,23 @@ where
     let mut results = Vec::new();
 
     for (gacha_type, last_end_id) in gacha_type_and_last_end_ids {
-      let logs = self
+      let logs = match self
         .scrape_with(
           endpoint,
           *gacha_type,
           last_end_id.as_deref(),
           pagination_size,
         )
-        .await?;
+        .await
+      {
+        Ok(logs) => logs,
+        Err(GachaUrlRequestError::Cancelled) => {
+          // Tell the visitor we've been cancelled, keep the completed results
+          notify! { self.notifier => Notify::Cancelled };
+          return Ok(results);
+        }
+        Err(error) => return Err(error),
+      };
 
       results.extend(logs);
     }
@@ -125,10 +167,19 @@ where
     let mut end_id = String::from("0");
     let mut results = Vec::new();
     loop {
+      if self.is_cancelled() {
+        return Err(GachaUrlRequestError::Cancelled);
+      }
+
       // Avoid visiting too frequently.
       if pagination > 1 && pagination % PAGINATION_THRESHOLD == 0 {
         notify! { self.notifier => Notify::Sleeping };
         (self.sleeper)(WAIT_MOMENT).await;
+
+        // May be cancelled while sleeping
+        if self.is_cancelled() {
+          return Err(GachaUrlRequestError::Cancelled);
+        }
       }
 
       // Tell the visitor about the current pagination
@@ -141,6 +192,7 @@ where
         .request_with_retry(
           endpoint,
           AsQueriesOptions {
+            lang: self.lang,
             gacha_type: Some(gacha_type),
             end_id: Some(&end_id),
             size: pagination_size.or(PAGINATION_SIZE),
@@ -148,6 +200,7 @@ where
           },
           self.retry.clone(),
           self.sleeper,
+          self.cancellation.as_ref(),
         )
         .await?;
 
@@ -4,7 +4,7 @@ use exponential_backoff::Backoff;
 use hg_game_biz::GachaLogEndpointType;
 use hg_url_finder::parse::ParsedGachaUrl;
 
-use crate::requester::RetryOptions;
+use crate::requester::{CancellationToken, GachaUrlRequestError, GachaUrlRequester, RetryOptions};
 use crate::scraper::GachaLogsScraper;
 
 #[tokio::test]
@@ -19,6 +19,7 @@ async fn test_scrape_gacha_logs() {
     RetryOptions::default(),
     tokio::time::sleep,
     Some(Box::new(|notify| println!("Scraper notify: {notify:?}"))),
+    None,
   )
   .scrape(
     GachaLogEndpointType::Standard,
@@ -35,6 +36,45 @@ async fn test_scrape_gacha_logs() {
   // std::fs::write("./GachaLogs.txt", format!("{logs:#?}")).unwrap();
 }
 
+#[tokio::test]
+async fn test_request_cancelled() {
+  let dirty = "https://public-operation-hkrpg.mihoyo.com/common/gacha_record/api/getGachaLog?authkey_ver=1&sign_type=2&region=prod_gf_cn&default_gacha_type=11&lang=zh-cn&game_biz=hkrpg_cn&page=1&size=5&gacha_type=11&end_id=0&authkey=foo";
+
+  let parsed = ParsedGachaUrl::from_dirty(dirty).unwrap();
+  let cancellation = CancellationToken::new();
+  cancellation.cancel();
+
+  // Cancelled before the first attempt, no request is sent
+  let result = parsed
+    .request_with_retry(
+      GachaLogEndpointType::Standard,
+      Default::default(),
+      RetryOptions::default(),
+      tokio::time::sleep,
+      Some(&cancellation),
+    )
+    .await;
+
+  assert!(matches!(result, Err(GachaUrlRequestError::Cancelled)));
+
+  // The scraper returns no logs and reports the cancellation
+  let scraper = GachaLogsScraper::new(
+    parsed,
+    RetryOptions::default(),
+    tokio::time::sleep,
+    None,
+    Some(cancellation),
+  );
+
+  let logs = scraper
+    .scrape(GachaLogEndpointType::Standard, &[(11, None)], None)
+    .await
+    .unwrap();
+
+  assert!(logs.is_empty());
+  assert!(scraper.is_cancelled());
+}
+
 #[test]
 fn test_backoff() {
   use std::fmt::Write;
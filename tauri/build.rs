@@ -1,4 +1,5 @@
 use std::io;
+use std::path::PathBuf;
 use std::process::Command;
 
 fn main() {
@@ -12,6 +13,45 @@ fn main() {
     println!("cargo:rustc-env=GIT_COMMIT_DATE={}", git_info.commit_date);
     println!("cargo:rustc-env=GIT_REMOTE_URL={}", git_info.remote_url);
   }
+
+  // Compact Gacha Metadata
+  //   The JSON is the source of truth, generate the compact binary and its hash.
+  compact_gacha_metadata();
+}
+
+fn compact_gacha_metadata() {
+  use hg_metadata::raw::RawMetadata;
+  use hg_metadata::{compact, validate};
+  use sha1::{Digest, Sha1};
+
+  const GACHA_METADATA: &str = "src/assets/GachaMetadata.json";
+  println!("cargo:rerun-if-changed={GACHA_METADATA}");
+
+  let json = std::fs::read(GACHA_METADATA).expect("Failed to read gacha metadata");
+  let raw = RawMetadata::from_slice(&json).expect("Failed to deserialize gacha metadata");
+
+  let issues = validate::validate(&raw);
+  for issue in &issues {
+    println!("cargo:warning=GachaMetadata: {issue}");
+  }
+
+  if validate::has_errors(&issues) {
+    panic!("Invalid gacha metadata, see the warnings above");
+  }
+
+  let hash = Sha1::digest(&json)
+    .into_iter()
+    .fold(String::with_capacity(40), |mut output, b| {
+      use std::fmt::Write;
+      let _ = write!(output, "{b:02x}"); // lowercase
+      output
+    });
+
+  let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+  std::fs::write(out_dir.join("GachaMetadata.bin"), compact::encode(&raw))
+    .expect("Failed to write compact gacha metadata");
+  std::fs::write(out_dir.join("GachaMetadata.sha1"), hash)
+    .expect("Failed to write gacha metadata hash");
 }
 
 struct GitInfo {
@@ -2,7 +2,7 @@ use std::process;
 use std::sync::Arc;
 
 use hg_ffi::Singleton;
-use tracing::info;
+use tracing::{error, info};
 
 use crate::bootstrap::environment::Environment;
 use crate::bootstrap::state::AppState;
@@ -36,11 +36,31 @@ impl Context {
       .load()
       .await;
 
-    let database = Database::new().await.expect("Failed to connect database");
-    database
-      .apply_migrations()
-      .await
-      .expect("Failed to apply database migrations");
+    let mut database = Database::new().await.expect("Failed to connect database");
+    let state = AppState::default();
+
+    // Not fatal, a backup can be restored on the frontend. Otherwise it keeps crashing.
+    // The writes are blocked until then, the schema is unknown.
+    match database.apply_migrations().await {
+      Ok(()) => {
+        // Not fatal, try again on the next startup
+        let _ = crate::database::schemas::purge_expired(&database).await;
+        let _ = crate::database::schemas::purge_expired_audit_logs(&database).await;
+        // The embedded metadata may be newer since the last startup
+        let metadata = &*metadata.read().await;
+        let _ = crate::database::schemas::resolve_unknown_item_ids(&database, metadata).await;
+        let _ = crate::database::schemas::sync_item_names(&database, metadata).await;
+      }
+      Err(err) => {
+        error!(message = "Failed to apply database migrations", ?err);
+        state.set_migration_error(err);
+
+        database
+          .reconnect_read_only()
+          .await
+          .expect("Failed to reconnect database in read-only mode");
+      }
+    }
 
     Self {
       singleton,
@@ -48,7 +68,7 @@ impl Context {
       environment: Arc::new(environment),
       metadata: Arc::new(metadata),
       database: Arc::new(database),
-      state: Arc::new(AppState::default()),
+      state: Arc::new(state),
     }
   }
 }
@@ -5,6 +5,7 @@ use tauri::{Error as TauriError, Theme, WebviewWindow};
 use crate::bootstrap::{TauriAppState, TauriEnvironmentState};
 use crate::bootstrap::{TauriDatabaseState, resolve_theme_or_system};
 use crate::constants;
+use crate::error::ErrorDetails;
 
 #[cfg(debug_assertions)]
 #[tauri::command]
@@ -17,6 +18,14 @@ pub fn environment(environment: TauriEnvironmentState) -> serde_json::Value {
   environment.to_json()
 }
 
+/// The error of the database migrations on startup, if any.
+#[tauri::command]
+pub fn database_migration_error(state: TauriAppState) -> Option<serde_json::Value> {
+  state
+    .migration_error()
+    .and_then(|err| serde_json::to_value(err as &(dyn ErrorDetails + Send)).ok())
+}
+
 #[tauri::command]
 #[tracing::instrument(skip(state, window))]
 pub fn change_color_scheme(
@@ -1,5 +1,8 @@
+use std::sync::OnceLock;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::database::migrations::MigrationError;
+
 mod window;
 
 pub use window::WindowState;
@@ -11,6 +14,9 @@ pub struct AppState {
 
   // You can clone it freely because its internal is Arc.
   window_state: WindowState,
+
+  // The database is kept as is, the frontend offers to restore a backup.
+  migration_error: OnceLock<MigrationError>,
 }
 
 impl AppState {
@@ -32,4 +38,14 @@ impl AppState {
       None
     }
   }
+
+  /// Get the error of the database migrations on startup
+  pub fn migration_error(&self) -> Option<&MigrationError> {
+    self.migration_error.get()
+  }
+
+  /// Set the error of the database migrations on startup
+  pub fn set_migration_error(&self, error: MigrationError) {
+    let _ = self.migration_error.set(error);
+  }
 }
@@ -249,6 +249,7 @@ fn command_handlers() -> Box<dyn Fn(Invoke<Wry>) -> bool + Send + Sync + 'static
     #[cfg(debug_assertions)]
     crate::bootstrap::handlers::panic,
     crate::bootstrap::handlers::environment,
+    crate::bootstrap::handlers::database_migration_error,
     crate::bootstrap::handlers::change_color_scheme,
     crate::bootstrap::handlers::create_app_lnk,
     crate::bootstrap::handlers::system_fonts,
@@ -277,11 +278,25 @@ fn command_handlers() -> Box<dyn Fn(Invoke<Wry>) -> bool + Send + Sync + 'static
     // GachaRecord
     crate::database::schemas::gacha_record_handlers::database_find_gacha_records_with_limit,
     crate::database::schemas::gacha_record_handlers::database_delete_gacha_records,
+    crate::database::schemas::gacha_record_handlers::database_query_gacha_records,
+    // RecycleBin
+    crate::database::schemas::recycle_bin_handlers::database_find_deleted_accounts,
+    crate::database::schemas::recycle_bin_handlers::database_find_deleted_gacha_records_groups,
+    crate::database::schemas::recycle_bin_handlers::database_find_deleted_gacha_records,
+    crate::database::schemas::recycle_bin_handlers::database_restore_account,
+    crate::database::schemas::recycle_bin_handlers::database_restore_gacha_records,
+    crate::database::schemas::recycle_bin_handlers::database_purge_deleted_account,
+    crate::database::schemas::recycle_bin_handlers::database_purge_deleted_gacha_records,
+    // AuditLog
+    crate::database::schemas::audit_log_handlers::database_find_audit_logs,
     // Business
     crate::business::handlers::metadata_hash,
     crate::business::handlers::metadata_locales,
     crate::business::handlers::metadata_entries,
+    crate::business::handlers::metadata_banners,
+    crate::business::handlers::metadata_overlay,
     crate::business::handlers::metadata_is_updating,
+    crate::business::handlers::metadata_changes,
     crate::business::handlers::metadata_update,
     crate::business::handlers::business_validate_uid,
     crate::business::handlers::business_locate_data_folder,
@@ -291,9 +306,16 @@ fn command_handlers() -> Box<dyn Fn(Invoke<Wry>) -> bool + Send + Sync + 'static
     crate::business::handlers::business_resolve_image,
     crate::business::handlers::business_pretty_records,
     crate::business::handlers::business_fetch_records,
+    crate::business::handlers::business_fetch_records_abort,
     crate::business::handlers::business_legacy_migration,
+    crate::business::handlers::business_list_backups,
+    crate::business::handlers::business_restore_backup,
+    crate::business::handlers::business_check_database,
+    crate::business::handlers::business_repair_database,
     crate::business::handlers::business_export_records,
     crate::business::handlers::business_import_records,
+    crate::business::handlers::business_rebase_records_time,
+    crate::business::handlers::business_search_records,
   ])
 }
 
@@ -398,12 +398,12 @@ impl RecordsWriter for ClassicUigfWriter {
     );
 
     // Verify the uid
-    let uid = Uid::validate(ClassicUigf::BUSINESS.as_game(), self.uid).context(
-      uigf_error::InvalidUidSnafu {
+    let uid = Uid::try_validate(ClassicUigf::BUSINESS.as_game(), self.uid)
+      .ok()
+      .context(uigf_error::InvalidUidSnafu {
         business: ClassicUigf::BUSINESS,
         value: self.uid,
-      },
-    )?;
+      })?;
 
     // Structuring
     let mut uigf = ClassicUigf {
@@ -442,23 +442,28 @@ impl RecordsWriter for ClassicUigfWriter {
 
       // Convert the language of the items to preferred language.
       // If needed
-      let mut item_name = record.item_name;
-      let mut item_type = record.item_type;
-      if record.lang.as_str() != self.lang {
-        let entry = metadata
-          .locale(ClassicUigf::BUSINESS as _, &self.lang)
-          .and_then(|locale| locale.entry_from_id(record.item_id))
-          .context(uigf_error::MetadataEntrySnafu {
-            business: ClassicUigf::BUSINESS,
-            lang: self.lang.clone(),
-            field: "item_id",
-            value: record.item_id.to_string(),
-            cursor,
-          })?;
+      let (item_name, item_type) = if record.lang.as_str() == self.lang {
+        (record.item_name, record.item_type)
+      } else if let Some(entry) = metadata
+        .locale(ClassicUigf::BUSINESS as _, &self.lang)
+        .and_then(|locale| locale.entry_from_id(record.item_id))
+      {
+        (entry.item_name.to_owned(), entry.category_name.to_owned())
+      } else {
+        // Fallback to the names fetched with alternate languages
+        let (item_name, item_type) =
+          record
+            .i18n(&self.lang)
+            .context(uigf_error::MetadataEntrySnafu {
+              business: ClassicUigf::BUSINESS,
+              lang: self.lang.clone(),
+              field: "item_id",
+              value: record.item_id.to_string(),
+              cursor,
+            })?;
 
-        item_name = entry.item_name.to_owned();
-        item_type = entry.category_name.to_owned();
-      }
+        (item_name.to_owned(), item_type.to_owned())
+      };
 
       // Always fill in these optional fields to ensure compatibility
       uigf.list.push(ClassicUigfItem {
@@ -543,12 +548,12 @@ impl RecordsReader for ClassicUigfReader {
     );
 
     // Verify the uid
-    let uid = Uid::validate(ClassicUigf::BUSINESS.as_game(), self.uid).context(
-      uigf_error::InvalidUidSnafu {
+    let uid = Uid::try_validate(ClassicUigf::BUSINESS.as_game(), self.uid)
+      .ok()
+      .context(uigf_error::InvalidUidSnafu {
         business: ClassicUigf::BUSINESS,
         value: self.uid,
-      },
-    )?;
+      })?;
 
     // Check uid consistency
     ensure!(
@@ -769,12 +774,12 @@ impl RecordsWriter for ClassicSrgfWriter {
     );
 
     // Verify the uid
-    let uid = Uid::validate(ClassicSrgf::BUSINESS.as_game(), self.uid).context(
-      uigf_error::InvalidUidSnafu {
+    let uid = Uid::try_validate(ClassicSrgf::BUSINESS.as_game(), self.uid)
+      .ok()
+      .context(uigf_error::InvalidUidSnafu {
         business: ClassicSrgf::BUSINESS,
         value: self.uid,
-      },
-    )?;
+      })?;
 
     // Structuring
     let mut srgf = ClassicSrgf {
@@ -804,23 +809,28 @@ impl RecordsWriter for ClassicSrgfWriter {
 
       // Convert the language of the items to preferred language.
       // If needed
-      let mut item_name = record.item_name;
-      let mut item_type = record.item_type;
-      if record.lang.as_str() != self.lang {
-        let entry = metadata
-          .locale(ClassicSrgf::BUSINESS as _, &self.lang)
-          .and_then(|locale| locale.entry_from_id(record.item_id))
-          .context(uigf_error::MetadataEntrySnafu {
-            business: ClassicSrgf::BUSINESS,
-            lang: self.lang.clone(),
-            field: "item_id",
-            value: record.item_id.to_string(),
-            cursor,
-          })?;
+      let (item_name, item_type) = if record.lang.as_str() == self.lang {
+        (record.item_name, record.item_type)
+      } else if let Some(entry) = metadata
+        .locale(ClassicSrgf::BUSINESS as _, &self.lang)
+        .and_then(|locale| locale.entry_from_id(record.item_id))
+      {
+        (entry.item_name.to_owned(), entry.category_name.to_owned())
+      } else {
+        // Fallback to the names fetched with alternate languages
+        let (item_name, item_type) =
+          record
+            .i18n(&self.lang)
+            .context(uigf_error::MetadataEntrySnafu {
+              business: ClassicSrgf::BUSINESS,
+              lang: self.lang.clone(),
+              field: "item_id",
+              value: record.item_id.to_string(),
+              cursor,
+            })?;
 
-        item_name = entry.item_name.to_owned();
-        item_type = entry.category_name.to_owned();
-      }
+        (item_name.to_owned(), item_type.to_owned())
+      };
 
       // HACK: In 'Honkai: Star Rail' business,
       //   the gacha_id value of the Record must exist.
@@ -913,12 +923,12 @@ impl RecordsReader for ClassicSrgfReader {
     );
 
     // Verify the uid
-    let uid = Uid::validate(ClassicSrgf::BUSINESS.as_game(), self.uid).context(
-      uigf_error::InvalidUidSnafu {
+    let uid = Uid::try_validate(ClassicSrgf::BUSINESS.as_game(), self.uid)
+      .ok()
+      .context(uigf_error::InvalidUidSnafu {
         business: ClassicSrgf::BUSINESS,
         value: self.uid,
-      },
-    )?;
+      })?;
 
     // Check uid consistency
     ensure!(
@@ -1291,10 +1301,13 @@ impl RecordsWriter for UigfWriter {
           .context(uigf_error::VacantAccountSnafu { business, uid })?;
 
         // Verify the uid
-        let uid = Uid::validate(business.as_game(), uid).context(uigf_error::InvalidUidSnafu {
-          business,
-          value: uid,
-        })?;
+        let uid =
+          Uid::try_validate(business.as_game(), uid)
+            .ok()
+            .context(uigf_error::InvalidUidSnafu {
+              business,
+              value: uid,
+            })?;
 
         // Convert
         let mut items = Vec::with_capacity(records.len());
@@ -1303,17 +1316,44 @@ impl RecordsWriter for UigfWriter {
           let cursor = index + 1;
 
           // Lookup metadata entry
-          let entry = metadata
-            .locale(business as _, lang)
-            .context(uigf_error::MetadataLocaleSnafu { business, lang })?
-            .entry_from_id(record.item_id)
-            .context(uigf_error::MetadataEntrySnafu {
+          let locale = metadata.locale(business as _, lang);
+          let entry = locale.and_then(|locale| locale.entry_from_id(record.item_id));
+
+          // Fallback to the names fetched with alternate languages.
+          // The category is resolved from the entry of any other locale,
+          // it is the same for all locales. Only the names are localized.
+          let i18n = match entry {
+            Some(_) => None,
+            None => record.i18n(lang).and_then(|(item_name, item_type)| {
+              metadata
+                .locales(business as _)?
+                .find_map(|locale| locale.entry_from_id(record.item_id))
+                .map(|other| (other, item_name.to_owned(), item_type.to_owned()))
+            }),
+          };
+
+          let entry = match (entry, &i18n) {
+            (Some(entry), _) => entry,
+            (None, Some((other, item_name, item_type))) => Entry {
+              locale: lang,
+              category: other.category,
+              category_name: item_type,
+              item_id: record.item_id,
+              item_name,
+              rank_type: other.rank_type,
+            },
+            (None, None) if locale.is_none() => {
+              uigf_error::MetadataLocaleSnafu { business, lang }.fail()?
+            }
+            (None, None) => uigf_error::MetadataEntrySnafu {
               business,
               lang,
               field: "item_id",
               value: record.item_id.to_string(),
               cursor,
-            })?;
+            }
+            .fail()?,
+          };
 
           let item = mapper(record, cursor, entry, minimized)?;
           items.push(item);
@@ -1595,8 +1635,9 @@ impl RecordsReader for UigfReader {
         };
 
         // Verify the uid
-        let uid =
-          Uid::validate(business.as_game(), project.uid).context(uigf_error::InvalidUidSnafu {
+        let uid = Uid::try_validate(business.as_game(), project.uid)
+          .ok()
+          .context(uigf_error::InvalidUidSnafu {
             business,
             value: project.uid,
           })?;
@@ -1769,8 +1810,9 @@ impl RecordsWriter for CsvWriter {
     let is_miliastra_wonderland = self.business == AccountBusiness::MiliastraWonderland;
 
     // First, verify the uid
-    let uid =
-      Uid::validate(self.business.as_game(), self.uid).context(csv_error::InvalidUidSnafu {
+    let uid = Uid::try_validate(self.business.as_game(), self.uid)
+      .ok()
+      .context(csv_error::InvalidUidSnafu {
         business: self.business,
         value: self.uid,
       })?;
@@ -1,9 +1,9 @@
 use std::io;
 use std::path::{Path, PathBuf};
 
-use hg_game_biz::Uid;
+use hg_game_biz::{Uid, UidError};
 use serde::{Deserialize, Serialize};
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ResultExt, Snafu};
 use tokio::fs::File as TokioFile;
 use tokio::io::{AsyncBufReadExt, BufReader as TokioBufReader};
 use tracing::{info, warn};
@@ -20,10 +20,11 @@ pub struct DataFolder {
 
 #[derive(Debug, Snafu)]
 pub enum LocateDataFolderError {
-  #[snafu(display("Invalid {business:?} account uid: {value}"))]
+  #[snafu(display("Invalid {business:?} account uid: {value}, reason: {}", source.reason))]
   InvalidUid {
     business: AccountBusiness,
     value: u32,
+    source: UidError,
   },
 
   #[snafu(display("Unity log file not found: {}", path.display()))]
@@ -48,10 +49,15 @@ impl ErrorDetails for LocateDataFolderError {
     use serde_json::json;
 
     Some(match self {
-      Self::InvalidUid { business, value } => json!({
+      Self::InvalidUid {
+        business,
+        value,
+        source,
+      } => json!({
         "kind": stringify!(InvalidUid),
         "business": business,
         "value": value,
+        "cause": source.details(),
       }),
       Self::UnityLogNotFound { path } => json!({
         "kind": stringify!(UnityLogNotFound),
@@ -103,7 +109,7 @@ impl DataFolderLocator for UnityLogDataFolderLocator {
     uid: u32,
   ) -> Result<DataFolder, LocateDataFolderError> {
     // First, Verify the uid
-    let uid = Uid::validate(business.as_game(), uid).context(InvalidUidSnafu {
+    let uid = Uid::try_validate(business.as_game(), uid).context(InvalidUidSnafu {
       business,
       value: uid,
     })?;
@@ -222,7 +228,7 @@ impl DataFolderLocator for ManualDataFolderLocator {
     uid: u32,
   ) -> Result<DataFolder, LocateDataFolderError> {
     // First, Verify the uid
-    let uid = Uid::validate(business.as_game(), uid).context(InvalidUidSnafu {
+    let uid = Uid::try_validate(business.as_game(), uid).context(InvalidUidSnafu {
       business,
       value: uid,
     })?;
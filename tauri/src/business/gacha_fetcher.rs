@@ -1,22 +1,30 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 
-use hg_game_biz::{GachaLogEndpointType, Uid};
+use hg_game_biz::{GachaLogEndpointType, Uid, UidError};
 use hg_metadata::Metadata;
+use hg_metadata::resolve::{EntryNameQuery, ResolveEntryError, resolve_entry_from_name};
 use hg_url_finder::parse::{ParsedGachaUrl, ParsedGachaUrlError};
-use hg_url_scraper::requester::{GachaUrlRequestError, RetryOptions};
+use hg_url_scraper::GachaLog;
+use hg_url_scraper::requester::{CancellationToken, GachaUrlRequestError, RetryOptions};
 use hg_url_scraper::scraper::{GachaLogsScraper, GachaLogsScraperNotify};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use snafu::{OptionExt, ResultExt, Snafu};
 use tauri::ipc::Channel;
 use tracing::{debug, info};
 
+use crate::business::metadata::sha1sum;
 use crate::business::prettized::{
   HONKAI_STAR_RAIL_COLLABORATION_CHARACTER, HONKAI_STAR_RAIL_COLLABORATION_WEAPON,
   PrettizedCategory,
 };
+use crate::database::backup::DatabaseBackupReason;
 use crate::database::schemas::{
-  AccountBusiness, GachaRecord, GachaRecordQuestioner, GachaRecordSaveOnConflict, GachaRecordSaver,
-  JsonProperties,
+  AccountBusiness, AccountQuestioner, AuditOperation, GachaRecord, GachaRecordSaveOnConflict,
+  GachaRecordSaveReport, GachaRecordSaver, GachaRecordTimeOffsetSource, JsonProperties,
+  sync_item_names,
 };
 use crate::database::{Database, DatabaseError};
 use crate::error::{AppError, ErrorDetails};
@@ -24,10 +32,11 @@ use crate::error::{AppError, ErrorDetails};
 #[derive(Debug, Snafu)]
 #[snafu(visibility)]
 pub enum GachaFetcherError {
-  #[snafu(display("Invalid {business:?} account uid: {value}"))]
+  #[snafu(display("Invalid {business:?} account uid: {value}, reason: {}", source.reason))]
   InvalidUid {
     business: AccountBusiness,
     value: u32,
+    source: UidError,
   },
 
   #[snafu(display("{source}"))]
@@ -43,8 +52,21 @@ pub enum GachaFetcherError {
     item_name: String,
   },
 
+  #[snafu(display(
+    "Ambiguous metadata entry: {business:?}, lang: {lang}, item_name: {item_name}, candidates: {candidates:?}"
+  ))]
+  AmbiguousMetadataEntry {
+    business: AccountBusiness,
+    lang: String,
+    item_name: String,
+    candidates: Vec<u32>,
+  },
+
   #[snafu(display("{source}"))]
   Database { source: DatabaseError },
+
+  #[snafu(display("Fetch cancelled, changes: {changes}"))]
+  Cancelled { changes: i64 },
 }
 
 impl ErrorDetails for GachaFetcherError {
@@ -61,10 +83,15 @@ impl ErrorDetails for GachaFetcherError {
     use serde_json::json;
 
     match self {
-      Self::InvalidUid { business, value } => Some(json!({
+      Self::InvalidUid {
+        business,
+        value,
+        source,
+      } => Some(json!({
         "kind": stringify!(InvalidUid),
         "business": business,
         "value": value,
+        "cause": source.details(),
       })),
       Self::Parse { source } => source.details(),
       Self::Scrape { source } => source.details(),
@@ -78,7 +105,23 @@ impl ErrorDetails for GachaFetcherError {
         "lang": lang,
         "itemName": item_name,
       })),
+      Self::AmbiguousMetadataEntry {
+        business,
+        lang,
+        item_name,
+        candidates,
+      } => Some(json!({
+        "kind": stringify!(AmbiguousMetadataEntry),
+        "business": business,
+        "lang": lang,
+        "itemName": item_name,
+        "candidates": candidates,
+      })),
       Self::Database { source } => source.details(),
+      Self::Cancelled { changes } => Some(json!({
+        "kind": stringify!(Cancelled),
+        "changes": changes,
+      })),
     }
   }
 }
@@ -91,6 +134,15 @@ pub enum GachaRecordSaveToDatabase {
   FullUpdate,
 }
 
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
+pub enum GachaRecordSaveOnCancelled {
+  /// Nothing is saved.
+  #[default]
+  Discard,
+  /// Only the gacha types that have been completely fetched are saved.
+  Save,
+}
+
 #[derive(Serialize)]
 pub enum FetchEventPayload {
   Sleeping,
@@ -99,6 +151,9 @@ pub enum FetchEventPayload {
   Data(usize),
   Completed(Option<PrettizedCategory>),
   Finished,
+  Cancelled,
+  /// Scraping again with the alternate lang, the progress events are muted.
+  Alternate(String),
 }
 
 impl FetchEventPayload {
@@ -114,11 +169,117 @@ impl FetchEventPayload {
         Self::Completed(PrettizedCategory::from_gacha_type(business, gacha_type))
       }
       GachaLogsScraperNotify::Finished => Self::Finished,
+      GachaLogsScraperNotify::Cancelled => Self::Cancelled,
     }
   }
 }
 
-#[tracing::instrument(skip(database, metadata, event_channel))]
+// Cancellation tokens of the in-flight fetches, by the account.
+static IN_FLIGHT: LazyLock<Mutex<HashMap<(AccountBusiness, u32), CancellationToken>>> =
+  LazyLock::new(Mutex::default);
+
+/// Guard of the in-flight fetch, unregisters the cancellation token when dropped.
+pub(crate) struct InFlightGuard {
+  key: (AccountBusiness, u32),
+  cancellation: CancellationToken,
+}
+
+impl InFlightGuard {
+  pub(crate) fn register(
+    business: AccountBusiness,
+    uid: u32,
+    cancellation: &CancellationToken,
+  ) -> Self {
+    let key = (business, uid);
+    IN_FLIGHT.lock().unwrap().insert(key, cancellation.clone());
+
+    Self {
+      key,
+      cancellation: cancellation.clone(),
+    }
+  }
+}
+
+impl Drop for InFlightGuard {
+  fn drop(&mut self) {
+    let mut in_flight = IN_FLIGHT.lock().unwrap();
+
+    // Another fetch of the same account may have been registered since
+    if in_flight
+      .get(&self.key)
+      .is_some_and(|cancellation| cancellation.ptr_eq(&self.cancellation))
+    {
+      in_flight.remove(&self.key);
+    }
+  }
+}
+
+/// Cancel the in-flight fetch of the account. Returns `false` if there is none.
+pub(crate) fn cancel_in_flight(business: AccountBusiness, uid: u32) -> bool {
+  if let Some(cancellation) = IN_FLIGHT.lock().unwrap().remove(&(business, uid)) {
+    cancellation.cancel();
+    true
+  } else {
+    false
+  }
+}
+
+// Scrape the gacha logs of the given gacha types with the endpoints of the business.
+async fn scrape<S>(
+  scraper: &GachaLogsScraper<'_, S>,
+  business: AccountBusiness,
+  gacha_type_and_last_end_ids: &[(u32, Option<&str>)],
+) -> Result<Vec<GachaLog>, GachaUrlRequestError>
+where
+  S: Future<Output = ()> + Send + 'static,
+{
+  match business {
+    AccountBusiness::GenshinImpact | AccountBusiness::ZenlessZoneZero => {
+      scraper
+        .scrape(
+          GachaLogEndpointType::Standard,
+          gacha_type_and_last_end_ids,
+          None,
+        )
+        .await
+    }
+    AccountBusiness::MiliastraWonderland => {
+      scraper
+        .scrape(
+          GachaLogEndpointType::Beyond,
+          gacha_type_and_last_end_ids,
+          None,
+        )
+        .await
+    }
+    AccountBusiness::HonkaiStarRail => {
+      let mut standard = Vec::with_capacity(gacha_type_and_last_end_ids.len());
+      let mut collaborations = Vec::with_capacity(2);
+
+      for &(gacha_type, last_end_id) in gacha_type_and_last_end_ids {
+        if gacha_type == HONKAI_STAR_RAIL_COLLABORATION_CHARACTER
+          || gacha_type == HONKAI_STAR_RAIL_COLLABORATION_WEAPON
+        {
+          collaborations.push((gacha_type, last_end_id));
+        } else {
+          standard.push((gacha_type, last_end_id));
+        }
+      }
+
+      scraper
+        .scrapes(
+          vec![
+            (GachaLogEndpointType::Standard, &standard[..]),
+            (GachaLogEndpointType::Collaboration, &collaborations[..]),
+          ],
+          None,
+        )
+        .await
+    }
+  }
+}
+
+#[tracing::instrument(skip(database, metadata, event_channel, cancellation))]
 #[allow(clippy::too_many_arguments)]
 pub async fn fetch(
   database: &Database,
@@ -130,19 +291,38 @@ pub async fn fetch(
   event_channel: Channel<FetchEventPayload>,
   save_to_database: Option<GachaRecordSaveToDatabase>,
   save_on_conflict: Option<GachaRecordSaveOnConflict>,
-) -> Result<i64, AppError<GachaFetcherError>> {
+  save_on_cancelled: Option<GachaRecordSaveOnCancelled>,
+  alternate_langs: Option<Vec<String>>,
+  cancellation: Option<CancellationToken>,
+) -> Result<GachaRecordSaveReport, AppError<GachaFetcherError>> {
   // First, Verify the uid
-  let uid = Uid::validate(business.as_game(), uid).context(InvalidUidSnafu {
+  let uid = Uid::try_validate(business.as_game(), uid).context(InvalidUidSnafu {
     business,
     value: uid,
   })?;
 
+  // The timezone override of the account takes precedence over the server.
+  let (timezone, time_offset_source) = match database
+    .find_account(business, uid.value())
+    .await
+    .context(DatabaseSnafu)?
+    .and_then(|account| account.timezone_override())
+  {
+    Some(offset) => (offset, GachaRecordTimeOffsetSource::Account),
+    None => (
+      uid.game_biz().timezone(),
+      GachaRecordTimeOffsetSource::GameBiz,
+    ),
+  };
+
   // Second, Parse gacha url
   let url = ParsedGachaUrl::from_dirty(&gacha_url).context(ParseSnafu)?;
+  let url_hash = sha1sum(gacha_url.as_bytes()); // Audit log source, without the authkey
   info!("Fetching gacha records...");
 
   let save_to_database = save_to_database.unwrap_or_default();
   let save_on_conflict = save_on_conflict.unwrap_or_default();
+  let save_on_cancelled = save_on_cancelled.unwrap_or_default();
 
   // The last_end_id value is discarded on full update
   if matches!(save_to_database, GachaRecordSaveToDatabase::FullUpdate) {
@@ -153,61 +333,56 @@ pub async fn fetch(
 
   // Scrape...
   debug!("Scraping gacha logs...");
-  let scraper = GachaLogsScraper::new(
+  let muted = Arc::new(AtomicBool::new(false));
+  let mut scraper = GachaLogsScraper::new(
     url,
     RetryOptions::default(),
     tokio::time::sleep,
-    Some(Box::new(move |notify| {
-      let _ = event_channel.send(FetchEventPayload::from(business, notify));
-    })),
-  );
-
-  let logs = match business {
-    AccountBusiness::GenshinImpact | AccountBusiness::ZenlessZoneZero => scraper
-      .scrape(
-        GachaLogEndpointType::Standard,
-        &gacha_type_and_last_end_ids[..],
-        None,
-      )
-      .await
-      .context(ScrapeSnafu)?,
-    AccountBusiness::MiliastraWonderland => scraper
-      .scrape(
-        GachaLogEndpointType::Beyond,
-        &gacha_type_and_last_end_ids[..],
-        None,
-      )
-      .await
-      .context(ScrapeSnafu)?,
-    AccountBusiness::HonkaiStarRail => {
-      let mut standard = Vec::with_capacity(gacha_type_and_last_end_ids.len());
-      let mut collaborations = Vec::with_capacity(2);
-
-      for (gacha_type, last_end_id) in gacha_type_and_last_end_ids {
-        if gacha_type == HONKAI_STAR_RAIL_COLLABORATION_CHARACTER
-          || gacha_type == HONKAI_STAR_RAIL_COLLABORATION_WEAPON
+    Some(Box::new({
+      let event_channel = event_channel.clone();
+      let muted = Arc::clone(&muted);
+      move |notify| {
+        // The alternate passes would count the same records again
+        if muted.load(Ordering::Relaxed)
+          && !matches!(
+            notify,
+            GachaLogsScraperNotify::Sleeping | GachaLogsScraperNotify::Cancelled
+          )
         {
-          collaborations.push((gacha_type, last_end_id));
-        } else {
-          standard.push((gacha_type, last_end_id));
+          return;
         }
+
+        let _ = event_channel.send(FetchEventPayload::from(business, notify));
       }
+    })),
+    cancellation,
+  );
 
-      scraper
-        .scrapes(
-          vec![
-            (GachaLogEndpointType::Standard, &standard[..]),
-            (GachaLogEndpointType::Collaboration, &collaborations[..]),
-          ],
-          None,
-        )
-        .await
-        .context(ScrapeSnafu)?
+  let logs = scrape(&scraper, business, &gacha_type_and_last_end_ids[..])
+    .await
+    .context(ScrapeSnafu)?;
+
+  // When cancelled, the logs only contain the completed gacha types.
+  // See: `GachaLogsScraper::scrape`
+  let mut cancelled = scraper.is_cancelled();
+  if cancelled {
+    info!(
+      message = "Fetching gacha records cancelled",
+      completes = logs.len(),
+      ?save_on_cancelled
+    );
+
+    if matches!(save_on_cancelled, GachaRecordSaveOnCancelled::Discard) {
+      CancelledSnafu { changes: 0 }.fail()?
     }
-  };
+  }
 
   if logs.is_empty() || matches!(save_to_database, GachaRecordSaveToDatabase::No) {
-    return Ok(0);
+    if cancelled {
+      CancelledSnafu { changes: 0 }.fail()?
+    }
+
+    return Ok(GachaRecordSaveReport::default());
   }
 
   // Convert official logs to schema
@@ -230,6 +405,68 @@ pub async fn fetch(
     lang = std.to_string();
   }
 
+  // Scrape the same gacha types again with the alternate languages, if necessary.
+  // Their item names are merged into the `properties` of the records,
+  //   so that they can be exported even if the metadata lacks the locale.
+  let mut alternates: HashMap<(String, u32), JsonMap<String, JsonValue>> = HashMap::new();
+  for alternate_lang in alternate_langs.iter().flatten() {
+    if cancelled {
+      break;
+    }
+
+    let alternate_lang = hg_metadata::def::LOCALE_ALIASES
+      .get(alternate_lang.as_str())
+      .copied()
+      .unwrap_or(alternate_lang.as_str());
+
+    if alternate_lang == lang {
+      continue;
+    }
+
+    debug!(
+      message = "Scraping gacha logs with alternate lang...",
+      ?alternate_lang
+    );
+    muted.store(true, Ordering::Relaxed);
+    let _ = event_channel.send(FetchEventPayload::Alternate(alternate_lang.to_owned()));
+    scraper.set_lang(Some(alternate_lang));
+    let alternate_logs = match scrape(&scraper, business, &gacha_type_and_last_end_ids[..]).await {
+      Ok(logs) => logs,
+      Err(error) => {
+        // Not a critical error, the records are still complete.
+        tracing::warn!(
+          message = "Failed to scrape with alternate lang",
+          ?alternate_lang,
+          ?error
+        );
+        continue;
+      }
+    };
+
+    // Discard the incomplete names, see above
+    if scraper.is_cancelled() {
+      cancelled = true;
+      break;
+    }
+
+    for log in alternate_logs {
+      let gacha_type = log.gacha_type();
+      if let (Some(item_name), Some(item_type)) = (log.item_name, log.item_type) {
+        alternates.entry((log.id, gacha_type)).or_default().insert(
+          alternate_lang.to_owned(),
+          serde_json::json!({
+            GachaRecord::KEY_I18N_ITEM_NAME: item_name,
+            GachaRecord::KEY_I18N_ITEM_TYPE: item_type,
+          }),
+        );
+      }
+    }
+  }
+
+  if cancelled && matches!(save_on_cancelled, GachaRecordSaveOnCancelled::Discard) {
+    CancelledSnafu { changes: 0 }.fail()?
+  }
+
   for log in logs {
     // HACK:
     //   Genshin Impact:
@@ -271,10 +508,30 @@ pub async fn fetch(
       }
 
       let item_name = item_name.as_deref().unwrap(); // SAFETY, See above
-      metadata
-        .locale(business as _, &lang)
-        .and_then(|locale| locale.entry_from_name_first(item_name))
-        .with_context(|| {
+      let query = EntryNameQuery {
+        lang: &lang,
+        item_name,
+        rank_type: rank_type.and_then(|n| u8::try_from(n).ok()),
+        item_type: item_type.as_deref().filter(|s| !s.is_empty()),
+      };
+
+      match resolve_entry_from_name(metadata, business as _, query) {
+        Ok(resolved) => {
+          if resolved.is_fallback() {
+            info!(
+              message = "Item name resolved with fallback",
+              ?business,
+              ?lang,
+              ?item_name,
+              item_id = resolved.entry.item_id,
+              matched = ?resolved.matched,
+              matched_locale = resolved.matched_locale,
+            );
+          }
+
+          resolved.entry.item_id
+        }
+        Err(ResolveEntryError::NotFound) => {
           tracing::error!(
             message = "Failed to map item name to item id, outdated metadata?",
             ?business,
@@ -290,8 +547,34 @@ pub async fn fetch(
             lang: lang.clone(),
             item_name: item_name.to_owned(),
           }
-        })?
-        .item_id
+          .fail()?
+        }
+        Err(ResolveEntryError::Ambiguous { candidates }) => {
+          let candidates = candidates
+            .into_iter()
+            .map(|entry| entry.item_id)
+            .collect::<Vec<_>>();
+
+          tracing::error!(
+            message = "Ambiguous item name, unable to map to a unique item id",
+            ?business,
+            ?lang,
+            ?log.id,
+            ?item_name,
+            ?log.gacha_type,
+            ?log.rank_type,
+            ?candidates,
+          );
+
+          AmbiguousMetadataEntrySnafu {
+            business,
+            lang: lang.clone(),
+            item_name: item_name.to_owned(),
+            candidates,
+          }
+          .fail()?
+        }
+      }
     };
 
     // See above, issues
@@ -340,7 +623,7 @@ pub async fn fetch(
 
     // HACK: 'Genshin Impact: Miliastra Wonderland' needs to retain some special field values,
     //   which may be used in the future.
-    let properties = if is_miliastra_wonderland {
+    let mut properties = if is_miliastra_wonderland {
       let mut value = JsonProperties::default();
 
       if let Some(schedule_id) = log.schedule_id {
@@ -360,7 +643,13 @@ pub async fn fetch(
       None
     };
 
-    let record = GachaRecord {
+    if let Some(i18n) = alternates.remove(&(log.id.clone(), gacha_type)) {
+      properties
+        .get_or_insert_default()
+        .insert(GachaRecord::KEY_I18N.into(), i18n.into());
+    }
+
+    let mut record = GachaRecord {
       business,
       uid: uid.value(),
       id: log.id,
@@ -369,68 +658,92 @@ pub async fn fetch(
       rank_type: rank_type.unwrap(), // SAFETY, See above
       count: log.count,
       lang: lang.clone(),
-      time: log.time.assume_offset(uid.game_biz().timezone()),
+      time: log.time.assume_offset(timezone),
       item_name: item_name.unwrap(), // SAFETY, See above
       item_type: item_type.unwrap(), // SAFETY, See above
       item_id,
       properties,
     };
 
+    record.set_time_offset_source(time_offset_source);
     records.push(record);
   }
 
   // Final, Save to database if necessary
-  let changes = if matches!(save_to_database, GachaRecordSaveToDatabase::Yes) {
-    // Save normally, create and add directly
-    GachaRecordSaver::new(&records[..], save_on_conflict, Option::<fn(u64)>::None)
-      .save(database)
-      .await
-      .context(DatabaseSnafu)? as i64
-  } else if matches!(save_to_database, GachaRecordSaveToDatabase::FullUpdate) {
-    // Full update, group by gacha_type, delete the oldest, recreate and add.
-    let groups: HashMap<u32, Vec<_>> =
-      records.into_iter().fold(HashMap::new(), |mut acc, record| {
-        acc.entry(record.gacha_type).or_default().push(record);
-        acc
-      });
-
-    let mut deleted = 0;
-    let mut created = 0;
-
-    for (gacha_type, group) in groups {
-      if group.is_empty() {
-        continue;
-      }
-
-      // SAFETY
-      let oldest_end_id = group.last().map(|e| e.id.as_str()).unwrap();
-
-      // Delete oldest
-      deleted += database
-        .delete_gacha_records_with_newer_than_end_id(
-          business,
-          uid.value(),
-          gacha_type,
-          oldest_end_id,
-        )
+  let report = match save_to_database {
+    GachaRecordSaveToDatabase::Yes => {
+      // Save normally, create and add directly
+      GachaRecordSaver::new(&records[..], save_on_conflict, Option::<fn(u64)>::None)
+        .with_audit(AuditOperation::Fetch, Some(url_hash))
+        .save(database)
         .await
-        .context(DatabaseSnafu)? as i64;
+        .context(DatabaseSnafu)?
+    }
+    GachaRecordSaveToDatabase::FullUpdate => {
+      // Back up before deleting, failures are not fatal
+      if let Err(e) = database.backup(DatabaseBackupReason::FullUpdate).await {
+        tracing::error!(
+          message = "Failed to back up the database before full update",
+          ?e
+        );
+      }
 
-      // Recreate and add
-      created += GachaRecordSaver::new(&group[..], save_on_conflict, Option::<fn(u64)>::None)
+      // Full update, delete the newer than the oldest of each gacha type, recreate and add.
+      GachaRecordSaver::new(&records[..], save_on_conflict, Option::<fn(u64)>::None)
+        .with_audit(AuditOperation::FullUpdate, Some(url_hash))
+        .with_full_update()
         .save(database)
         .await
-        .context(DatabaseSnafu)? as i64
+        .context(DatabaseSnafu)?
+    }
+    GachaRecordSaveToDatabase::No => {
+      // Not saved
+      GachaRecordSaveReport::default()
     }
-
-    // If the return value is negative, it means that there are duplicates or errors in the old data;
-    // otherwise, it means that the data is newly added.
-    created - deleted
-  } else {
-    // unreachable!()
-    // See above
-    0
   };
 
-  Ok(changes)
+  // Not fatal, the names are for the search only
+  if report.changes() > 0 {
+    let _ = sync_item_names(database, metadata).await;
+  }
+
+  if cancelled {
+    CancelledSnafu {
+      changes: report.changes() as i64,
+    }
+    .fail()?
+  }
+
+  Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_in_flight_guard() {
+    let business = AccountBusiness::GenshinImpact;
+    let uid = 100_000_001;
+
+    let first = CancellationToken::new();
+    let second = CancellationToken::new();
+    let first_guard = InFlightGuard::register(business, uid, &first);
+    let second_guard = InFlightGuard::register(business, uid, &second);
+
+    // The first fetch finished later, the second is still registered
+    drop(first_guard);
+    assert!(cancel_in_flight(business, uid));
+    assert!(second.is_cancelled());
+    assert!(!first.is_cancelled());
+
+    drop(second_guard);
+    assert!(!cancel_in_flight(business, uid));
+
+    // Other accounts are not affected
+    let third = CancellationToken::new();
+    let _third_guard = InFlightGuard::register(business, uid + 1, &third);
+    assert!(!cancel_in_flight(business, uid));
+    assert!(!third.is_cancelled());
+  }
 }
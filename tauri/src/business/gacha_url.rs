@@ -1,13 +1,13 @@
 use std::collections::HashSet;
 use std::path::PathBuf;
 
-use hg_game_biz::{GachaLogEndpointType, Uid};
+use hg_game_biz::{GachaLogEndpointType, Uid, UidError};
 use hg_url_finder::dirty::{CreationTimePolicy, DirtyGachaUrl, DirtyGachaUrlError};
 use hg_url_finder::parse::{AsQueriesOptions, ParsedGachaUrl, ParsedGachaUrlError};
 use hg_url_scraper::GachaLogsResponse;
 use hg_url_scraper::requester::{GachaUrlRequestError, GachaUrlRequester, RetryOptions};
 use serde::Serialize;
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ResultExt, Snafu};
 use time::OffsetDateTime;
 use time::serde::rfc3339;
 use tracing::{debug, error, info, warn};
@@ -19,10 +19,11 @@ use crate::error::{AppError, ErrorDetails};
 
 #[derive(Debug, Snafu)]
 pub enum GachaUrlError {
-  #[snafu(display("Invalid {business:?} account uid: {value}"))]
+  #[snafu(display("Invalid {business:?} account uid: {value}, reason: {}", source.reason))]
   InvalidUid {
     business: AccountBusiness,
     value: u32,
+    source: UidError,
   },
 
   #[snafu(display("{source}"))]
@@ -63,10 +64,15 @@ impl ErrorDetails for GachaUrlError {
     use serde_json::json;
 
     match self {
-      Self::InvalidUid { business, value } => Some(json!({
+      Self::InvalidUid {
+        business,
+        value,
+        source,
+      } => Some(json!({
         "kind": stringify!(InvalidUid),
         "business": business,
         "value": value,
+        "cause": source.details(),
       })),
       Self::Dirty { source } => source.details(),
       Self::Parse { source } => source.details(),
@@ -137,7 +143,7 @@ impl GachaUrl {
     from_webcaches: bool,
   ) -> Result<Self, AppError<GachaUrlError>> {
     // First, Verify the uid
-    let uid = Uid::validate(business.as_game(), uid).context(InvalidUidSnafu {
+    let uid = Uid::try_validate(business.as_game(), uid).context(InvalidUidSnafu {
       business,
       value: uid,
     })?;
@@ -276,6 +282,7 @@ async fn fast_request_valid_gacha_type(
       },
       RetryOptions::default(),
       tokio::time::sleep,
+      None,
     )
     .await?;
 
@@ -303,6 +310,7 @@ async fn fast_request_valid_gacha_type(
           },
           RetryOptions::default(),
           tokio::time::sleep,
+          None,
         )
         .await?;
 
@@ -2,7 +2,9 @@ use std::path::PathBuf;
 use std::time::Instant;
 
 use cfg_if::cfg_if;
-use hg_game_biz::Uid;
+use hg_game_biz::{Uid, UidError};
+use hg_metadata::overlay::OverlayReport;
+use hg_url_scraper::requester::CancellationToken;
 use tauri::ipc::{Channel, IpcResponse};
 use tracing::debug;
 
@@ -12,15 +14,21 @@ use crate::business::data_folder::{
   DataFolder, DataFolderLocator, DataFolderLocatorFactory, LocateDataFolderError,
 };
 use crate::business::gacha_fetcher::{
-  FetchEventPayload, GachaFetcherError, GachaRecordSaveToDatabase,
+  FetchEventPayload, GachaFetcherError, GachaRecordSaveOnCancelled, GachaRecordSaveToDatabase,
+  InFlightGuard,
 };
 use crate::business::gacha_url::{GachaUrl, GachaUrlError};
 use crate::business::image_resolver::ImageResolver;
-use crate::business::prettized::PrettizedRecords;
+use crate::business::prettized::{PrettizedBanner, PrettizedRecords};
 use crate::database::DatabaseError;
+use crate::database::backup::{DatabaseBackup, DatabaseBackupError, DatabaseBackupReason};
+use crate::database::integrity::{IntegrityRepairOptions, IntegrityRepairReport, IntegrityReport};
 use crate::database::legacy::{LegacyMigration, LegacyMigrationError};
 use crate::database::schemas::{
-  AccountBusiness, GachaRecordQuestioner, GachaRecordSaveOnConflict, GachaRecordSaver,
+  AccountBusiness, AuditOperation, GachaRecordQuestioner, GachaRecordRebaseError,
+  GachaRecordSaveOnConflict, GachaRecordSaveReport, GachaRecordSaver, GachaRecordSearchResult,
+  GachaRecordTimeOffsetSource, GachaRecordTimeRebaser, audit_source_of, resolve_unknown_item_ids,
+  search_gacha_records, sync_item_names,
 };
 use crate::error::{AppError, BoxDynErrorDetails, ErrorDetails};
 
@@ -55,8 +63,7 @@ pub async fn metadata_entries(
     Some(mut locales) => {
       if let Some(first) = locales.next() {
         let entries = first
-          .entries()
-          .values()
+          .iter_entries()
           .filter(|entry| entry.category == category)
           .map(|entry| entry.item_id)
           .collect();
@@ -69,6 +76,28 @@ pub async fn metadata_entries(
   }
 }
 
+/// Returns the banners timeline of the gacha type, ordered by start time.
+#[tauri::command]
+pub async fn metadata_banners(
+  state: TauriMetadataState<'_>,
+  business: AccountBusiness,
+  gacha_type: u32,
+) -> Result<Option<Vec<PrettizedBanner>>, ()> {
+  let metadata = { &*state.read().await };
+  Ok(
+    metadata
+      .banners(business as _, gacha_type)
+      .map(|banners| banners.into_iter().map(PrettizedBanner::mapping).collect()),
+  )
+}
+
+/// Returns the items and banners added by the user metadata overlay,
+/// or `None` if no overlay is applied.
+#[tauri::command]
+pub async fn metadata_overlay(state: TauriMetadataState<'_>) -> Result<Option<OverlayReport>, ()> {
+  Ok(state.overlay().await)
+}
+
 cfg_if! {if #[cfg(not(feature = "disable-metadata-updater"))] {
   use std::sync::Arc;
 
@@ -79,17 +108,36 @@ cfg_if! {if #[cfg(not(feature = "disable-metadata-updater"))] {
     state.is_updating()
   }
 
+  /// Returns the changes of the last successful update,
+  /// or `None` if not updated since the startup.
+  #[tauri::command]
+  pub async fn metadata_changes(
+    state: TauriMetadataState<'_>,
+  ) -> Result<Option<hg_metadata::diff::MetadataDiff>, ()> {
+    Ok(state.changes().await)
+  }
+
   #[tauri::command]
-  #[tracing::instrument(skip(state))]
+  #[tracing::instrument(skip(state, database))]
   pub async fn metadata_update(
     state: TauriMetadataState<'_>,
+    database: TauriDatabaseState<'_>,
     max_attempts: Option<u8>,
   ) -> Result<MetadataUpdateKind, AppError<MetadataUpdateError>> {
-    Arc::clone(state.inner())
+    let kind = Arc::clone(state.inner())
       .update_with_retry(max_attempts)
       .await
       .expect("Failed join metadata update task")
-      .map_err(AppError::from)
+      .map_err(AppError::from)?;
+
+    if let MetadataUpdateKind::Success(_) = kind {
+      // Not fatal, the newer metadata may resolve the unknown item ids and have more names
+      let metadata = { &*state.read().await };
+      let _ = resolve_unknown_item_ids(&database, metadata).await;
+      let _ = sync_item_names(&database, metadata).await;
+    }
+
+    Ok(kind)
   }
 } else {
   // Feature disabled
@@ -97,13 +145,23 @@ cfg_if! {if #[cfg(not(feature = "disable-metadata-updater"))] {
   #[tauri::command]
   pub fn metadata_is_updating() -> bool { false }
 
+  #[tauri::command]
+  pub fn metadata_changes() -> Option<()> { None }
+
   #[tauri::command]
   pub fn metadata_update() {}
 }}
 
+/// Returns the server of the valid uid,
+/// otherwise the reason and the probable candidates of the invalid uid.
 #[tauri::command]
-pub fn business_validate_uid(business: AccountBusiness, uid: u32) -> Option<&'static str> {
-  Uid::validate(business.as_game(), uid).map(|res| res.game_biz().server().as_str())
+pub fn business_validate_uid(
+  business: AccountBusiness,
+  uid: u32,
+) -> Result<&'static str, AppError<UidError>> {
+  Uid::try_validate(business.as_game(), uid)
+    .map(|res| res.game_biz().server().as_str())
+    .map_err(Into::into)
 }
 
 #[tauri::command]
@@ -190,8 +248,15 @@ pub async fn business_fetch_records(
   event_channel: Channel<FetchEventPayload>,
   save_to_database: Option<GachaRecordSaveToDatabase>,
   save_on_conflict: Option<GachaRecordSaveOnConflict>,
-) -> Result<i64, AppError<GachaFetcherError>> {
+  save_on_cancelled: Option<GachaRecordSaveOnCancelled>,
+  alternate_langs: Option<Vec<String>>,
+) -> Result<GachaRecordSaveReport, AppError<GachaFetcherError>> {
   let metadata = { &*metadata.read().await };
+
+  // Register for `business_fetch_records_abort`
+  let cancellation = CancellationToken::new();
+  let _in_flight_guard = InFlightGuard::register(business, uid, &cancellation);
+
   let result = crate::business::gacha_fetcher::fetch(
     &database,
     metadata,
@@ -202,6 +267,9 @@ pub async fn business_fetch_records(
     event_channel,
     save_to_database,
     save_on_conflict,
+    save_on_cancelled,
+    alternate_langs,
+    Some(cancellation),
   )
   .await;
 
@@ -213,6 +281,11 @@ pub async fn business_fetch_records(
   result
 }
 
+#[tauri::command]
+pub fn business_fetch_records_abort(business: AccountBusiness, uid: u32) -> bool {
+  crate::business::gacha_fetcher::cancel_in_flight(business, uid)
+}
+
 #[tauri::command]
 pub async fn business_legacy_migration(
   database: TauriDatabaseState<'_>,
@@ -225,6 +298,40 @@ pub async fn business_legacy_migration(
     .map_err(Into::into)
 }
 
+#[tauri::command]
+pub async fn business_list_backups(
+  database: TauriDatabaseState<'_>,
+) -> Result<Vec<DatabaseBackup>, AppError<DatabaseBackupError>> {
+  database.backups().map_err(Into::into)
+}
+
+/// Stage the backup to be restored, the app should be relaunched.
+#[tauri::command]
+#[tracing::instrument(skip(database))]
+pub async fn business_restore_backup(
+  database: TauriDatabaseState<'_>,
+  filename: String,
+) -> Result<DatabaseBackup, AppError<DatabaseBackupError>> {
+  database.stage_restore(&filename).await.map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn business_check_database(
+  database: TauriDatabaseState<'_>,
+) -> Result<IntegrityReport, AppError<DatabaseError>> {
+  Ok(database.check_integrity().await?)
+}
+
+/// Repair the database with the options, the database is backed up first.
+#[tauri::command]
+#[tracing::instrument(skip(database))]
+pub async fn business_repair_database(
+  database: TauriDatabaseState<'_>,
+  options: IntegrityRepairOptions,
+) -> Result<IntegrityRepairReport, AppError<DatabaseError>> {
+  Ok(database.repair_integrity(options).await?)
+}
+
 #[tauri::command]
 #[tracing::instrument(skip(database, metadata))]
 pub async fn business_export_records(
@@ -289,12 +396,28 @@ pub async fn business_import_records(
   input: PathBuf, // File path
   save_on_conflict: Option<GachaRecordSaveOnConflict>,
   progress_channel: Channel<f32>,
-) -> Result<u64, BoxDynErrorDetails> {
+) -> Result<GachaRecordSaveReport, BoxDynErrorDetails> {
   let metadata = { &*metadata.read().await };
-  let records = reader.read(metadata, input)?;
+  let source = audit_source_of(&input);
+  let mut records = reader.read(metadata, input)?;
   let total = records.len() as f32;
 
-  GachaRecordSaver::new(
+  // The time has been converted from the timezone provided by the file
+  for record in records.iter_mut() {
+    record.set_time_offset_source(GachaRecordTimeOffsetSource::Imported);
+  }
+
+  // Back up before the bulk import, failures are not fatal
+  if !records.is_empty()
+    && let Err(e) = database.backup(DatabaseBackupReason::Import).await
+  {
+    tracing::error!(
+      message = "Failed to back up the database before importing",
+      ?e
+    );
+  }
+
+  let report = GachaRecordSaver::new(
     &records[..],
     save_on_conflict.unwrap_or_default(),
     Some(|completes| {
@@ -303,7 +426,42 @@ pub async fn business_import_records(
       let _ = progress_channel.send(progress);
     }),
   )
+  .with_audit(AuditOperation::Import, source)
   .save(&database)
   .await
-  .map_err(ErrorDetails::boxed)
+  .map_err(ErrorDetails::boxed)?;
+
+  // Not fatal, the names are for the search only
+  if report.changes() > 0 {
+    let _ = sync_item_names(&database, metadata).await;
+  }
+
+  Ok(report)
+}
+
+/// Re-base the time of all records of the account to the corrected offset. (Whole hours)
+#[tauri::command]
+#[tracing::instrument(skip(database))]
+pub async fn business_rebase_records_time(
+  database: TauriDatabaseState<'_>,
+  business: AccountBusiness,
+  uid: u32,
+  offset: i8,
+) -> Result<u64, AppError<GachaRecordRebaseError>> {
+  let changes = GachaRecordTimeRebaser::from_hours(business, uid, offset)?
+    .rebase(&database)
+    .await
+    .map_err(GachaRecordRebaseError::from)?;
+
+  Ok(changes)
+}
+
+/// Search the records of all accounts by the item name in any lang.
+#[tauri::command]
+#[tracing::instrument(skip(database))]
+pub async fn business_search_records(
+  database: TauriDatabaseState<'_>,
+  keyword: String,
+) -> Result<Vec<GachaRecordSearchResult>, AppError<DatabaseError>> {
+  Ok(search_gacha_records(&database, &keyword).await?)
 }
@@ -1,6 +1,8 @@
 use std::ops::Deref;
 
+use hg_metadata::compact::{self, CompactMetadataError};
 use hg_metadata::def::{BakeMetadataError, bake as bake_metadata};
+use hg_metadata::overlay::{OverlayMetadata, OverlayReport};
 use hg_metadata::raw::RawMetadata;
 use snafu::{ResultExt, Snafu};
 use tokio::sync::{RwLock, RwLockReadGuard};
@@ -15,6 +17,9 @@ pub enum MetadataError {
 
   #[snafu(display("Failed to bake metadata"))]
   Bake { source: BakeMetadataError },
+
+  #[snafu(display("Failed to load compact metadata"))]
+  Compact { source: CompactMetadataError },
 }
 
 impl ErrorDetails for MetadataError {
@@ -34,14 +39,23 @@ impl ErrorDetails for MetadataError {
         "kind": stringify!(Bake),
         "cause": format_args!("{source:?}"),
       }),
+      Self::Compact { source } => json!({
+        "kind": stringify!(Compact),
+        "cause": format_args!("{source:?}"),
+      }),
     })
   }
 }
 
 pub struct Metadata {
   inner: RwLock<MetadataInner>,
+  /// User metadata overlay, merged on top of the embedded or the caches.
+  overlay: Option<OverlayMetadata>,
   #[cfg(not(feature = "disable-metadata-updater"))]
   updating: std::sync::atomic::AtomicBool,
+  /// Changes of the last successful update, since the startup.
+  #[cfg(not(feature = "disable-metadata-updater"))]
+  changes: RwLock<Option<hg_metadata::diff::MetadataDiff>>,
 }
 
 impl Metadata {
@@ -49,13 +63,16 @@ impl Metadata {
   pub fn new() -> Result<Self, MetadataError> {
     info!("Initializing gacha metadata...");
 
-    let inner = MetadataInner::new()?;
+    let inner = MetadataInner::new(None)?;
     info!(message = "Gacha metadata initialized", hash = ?inner.hash);
 
     Ok(Self {
       inner: RwLock::new(inner),
+      overlay: None,
       #[cfg(not(feature = "disable-metadata-updater"))]
       updating: std::sync::atomic::AtomicBool::new(false),
+      #[cfg(not(feature = "disable-metadata-updater"))]
+      changes: RwLock::new(None),
     })
   }
 
@@ -64,13 +81,32 @@ impl Metadata {
     { self.inner.read().await }.hash.clone()
   }
 
+  /// Get the report of the user metadata overlay, if applied.
+  pub async fn overlay(&self) -> Option<OverlayReport> {
+    { self.inner.read().await }.overlay.clone()
+  }
+
   /// Load metadata, returning an `Self`.
   #[tracing::instrument(skip(self))]
-  pub async fn load(self) -> Self {
+  pub async fn load(mut self) -> Self {
+    // Load user metadata overlay if exists, and apply to the embedded
+    if let Some(overlay) = Self::read_overlay().await {
+      match MetadataInner::new(Some(&overlay)) {
+        Err(err) => tracing::error!(message = "Failed to apply metadata overlay", ?err),
+        Ok(inner) => {
+          *self.inner.get_mut() = inner;
+          self.overlay = Some(overlay);
+        }
+      }
+    }
+
     // Load caches file if exists
     #[cfg(not(feature = "disable-metadata-updater"))]
     self.load_caches().await;
 
+    // Load game biz overlay if exists
+    Self::load_game_biz_overlay().await;
+
     // Dump current metadata
     #[cfg(debug_assertions)]
     self.dump().await;
@@ -78,6 +114,83 @@ impl Metadata {
     self
   }
 
+  /// Read the user metadata overlay file.
+  /// Same schema as the raw metadata, but partial. See `hg_metadata::overlay::OverlayMetadata`.
+  #[tracing::instrument]
+  async fn read_overlay() -> Option<OverlayMetadata> {
+    const OVERLAY_FILE: &str = "Overlay.json";
+
+    let overlay_file = crate::constants::APP_LOCAL_DATA_DIR
+      .join("GachaMetadata")
+      .join(OVERLAY_FILE);
+
+    if !overlay_file.exists() {
+      debug!("No metadata overlay file found");
+      return None;
+    }
+
+    let bytes = match tokio::fs::read(&overlay_file).await {
+      Err(err) => {
+        tracing::error!(message = "Failed to read metadata overlay file", ?err);
+        return None;
+      }
+      Ok(bytes) => bytes,
+    };
+
+    match OverlayMetadata::from_slice(&bytes) {
+      Err(err) => {
+        // Keep the file, it may be an user mistake. Only the official are available.
+        tracing::error!(message = "Failed to deserialize metadata overlay", ?err);
+        None
+      }
+      Ok(overlay) => Some(overlay),
+    }
+  }
+
+  /// The `GameBiz` overlay shipped with the metadata. (See: `hg_game_biz::registry`)
+  const GAME_BIZ_OVERLAY_FILE: &str = "GameBiz.json";
+
+  fn game_biz_overlay_file() -> std::path::PathBuf {
+    crate::constants::APP_LOCAL_DATA_DIR
+      .join("GachaMetadata")
+      .join(Self::GAME_BIZ_OVERLAY_FILE)
+  }
+
+  /// Register the additional `GameBiz` definitions from the overlay file.
+  /// The built-in `GameBiz` are always the fallback.
+  #[tracing::instrument]
+  async fn load_game_biz_overlay() {
+    let overlay_file = Self::game_biz_overlay_file();
+    if !overlay_file.exists() {
+      debug!("No game biz overlay file found");
+      return;
+    }
+
+    let bytes = match tokio::fs::read(&overlay_file).await {
+      Err(err) => {
+        tracing::error!(message = "Failed to read game biz overlay file", ?err);
+        return;
+      }
+      Ok(bytes) => bytes,
+    };
+
+    Self::register_game_biz_overlay(&bytes);
+  }
+
+  fn register_game_biz_overlay(bytes: &[u8]) -> bool {
+    match hg_game_biz::registry::register_from_json(bytes) {
+      Err(err) => {
+        // Keep the file, it may be an user mistake. Only the built-in are available.
+        tracing::error!(message = "Failed to register game biz overlay", ?err);
+        false
+      }
+      Ok(count) => {
+        info!(message = "Registered game biz overlay", count);
+        true
+      }
+    }
+  }
+
   /// Dump the current baked metadata to a Debug output. (`debug_assertions`)
   #[cfg(debug_assertions)]
   #[tracing::instrument(skip(self))]
@@ -107,11 +220,13 @@ impl Metadata {
 // Inner
 struct MetadataInner {
   metadata: Box<dyn hg_metadata::Metadata>,
+  /// Hash of the official metadata, without the overlay.
   hash: String,
+  overlay: Option<OverlayReport>,
 }
 
 /// Compute the SHA-1 checksum of the input bytes and return it as a lowercase hexadecimal string.
-fn sha1sum(input: &[u8]) -> String {
+pub(crate) fn sha1sum(input: &[u8]) -> String {
   use sha1::{Digest, Sha1};
 
   Sha1::digest(input)
@@ -124,31 +239,108 @@ fn sha1sum(input: &[u8]) -> String {
 }
 
 impl MetadataInner {
-  /// Initialize from embedded metadata.
-  fn new() -> Result<Self, MetadataError> {
-    debug!("Loading embedded metadata...");
-    const EMBEDDED_METADATA: &[u8] = include_bytes!("../assets/GachaMetadata.json");
-    Self::from_raw(EMBEDDED_METADATA, None)
+  // Generated at build time from `../assets/GachaMetadata.json`, see `build.rs`
+  const EMBEDDED_METADATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/GachaMetadata.bin"));
+  const EMBEDDED_METADATA_HASH: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/GachaMetadata.sha1"));
+
+  /// Initialize from embedded compact metadata.
+  ///
+  /// With the overlay, the compact metadata has to be decoded and baked.
+  fn new(overlay: Option<&OverlayMetadata>) -> Result<Self, MetadataError> {
+    let hash = Self::EMBEDDED_METADATA_HASH.to_owned();
+    if let Some(overlay) = overlay {
+      debug!("Decoding embedded compact metadata...");
+      let raw = compact::decode(Self::EMBEDDED_METADATA).context(CompactSnafu)?;
+      return Self::bake(raw, hash, Some(overlay));
+    }
+
+    debug!("Loading embedded compact metadata...");
+    let metadata = compact::load(Self::EMBEDDED_METADATA).context(CompactSnafu)?;
+
+    Ok(Self {
+      metadata,
+      hash,
+      overlay: None,
+    })
   }
 
   /// Initialize from raw bytes.
   ///
   /// `hash` should be the SHA-1 hash of the bytes, if known.
   /// Avoid multiple calculations.
-  #[tracing::instrument(fields(bytes = bytes.len()))]
-  fn from_raw(bytes: &[u8], hash: Option<String>) -> Result<Self, MetadataError> {
+  #[tracing::instrument(skip(overlay), fields(bytes = bytes.len()))]
+  fn from_raw(
+    bytes: &[u8],
+    hash: Option<String>,
+    overlay: Option<&OverlayMetadata>,
+  ) -> Result<Self, MetadataError> {
     debug!("Deserializing metadata...");
     let raw = RawMetadata::from_slice(bytes).context(DeserializeSnafu)?;
 
+    // Compute hash if not provided
+    let hash = hash.unwrap_or_else(|| sha1sum(bytes));
+    Self::bake(raw, hash, overlay)
+  }
+
+  fn bake(
+    raw: RawMetadata,
+    hash: String,
+    overlay: Option<&OverlayMetadata>,
+  ) -> Result<Self, MetadataError> {
+    let (raw, overlay) = match overlay {
+      None => (raw, None),
+      Some(overlay) => {
+        let (raw, report) = hg_metadata::overlay::merge(raw, overlay);
+        Self::log_overlay(&report);
+        (raw, Some(report))
+      }
+    };
+
     debug!(message = "Baking metadata...", len = raw.as_ref().len());
     let metadata = bake_metadata(raw).context(BakeSnafu)?;
 
     Ok(Self {
       metadata,
-      // Compute hash if not provided
-      hash: hash.unwrap_or_else(|| sha1sum(bytes)),
+      hash,
+      overlay,
     })
   }
+
+  fn log_overlay(report: &OverlayReport) {
+    info!(
+      message = "Applied metadata overlay",
+      items = report.items.len(),
+      banners = report.banners.len(),
+    );
+
+    // Should be reported upstream
+    for item in &report.items {
+      info!(
+        message = "Overlay item",
+        business = item.business_id,
+        item_id = item.item_id,
+        category = %item.category,
+        item_name = %item.item_name,
+      );
+    }
+
+    // Included in the official, can be dropped from the overlay
+    for item in &report.superseded_items {
+      tracing::warn!(
+        message = "Overlay item is superseded by the metadata",
+        business = item.business_id,
+        item_id = item.item_id,
+      );
+    }
+
+    if !report.superseded_banners.is_empty() {
+      tracing::warn!(
+        message = "Overlay banners are superseded by the metadata",
+        count = report.superseded_banners.len(),
+      );
+    }
+  }
 }
 
 // Read guard
@@ -182,6 +374,12 @@ pub enum MetadataUpdateError {
   #[snafu(display("Downloaded metadata hash mismatch"))]
   DownloadedMismatch,
 
+  #[snafu(display("Downloaded metadata signature is invalid"))]
+  InvalidSignature,
+
+  #[snafu(display("Downloaded metadata signature is not published"))]
+  MissingSignature,
+
   #[snafu(display("Failed to process metadata"))]
   Process { source: MetadataError },
 }
@@ -206,6 +404,12 @@ impl ErrorDetails for MetadataUpdateError {
       Self::DownloadedMismatch => Some(json!({
         "kind": stringify!(DownloadedMismatch)
       })),
+      Self::InvalidSignature => Some(json!({
+        "kind": stringify!(InvalidSignature)
+      })),
+      Self::MissingSignature => Some(json!({
+        "kind": stringify!(MissingSignature)
+      })),
       Self::Process { source } => source.details(),
     }
   }
@@ -219,9 +423,68 @@ pub enum MetadataUpdateKind {
   Success(String),
 }
 
+// Delta update
+//
+// The manifest `{hash}.manifest.json` splits the metadata data into
+// ordered byte ranges (chunks), usually one per business. The data is
+// exactly the concatenation of the chunks, and a chunk is `chunks/{sha1}`.
+//
+// The chunks of the current caches are sliced by its manifest and reused,
+// only the changed chunks are downloaded.
+
+#[cfg(not(feature = "disable-metadata-updater"))]
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MetadataManifest {
+  chunks: Vec<MetadataManifestChunk>,
+}
+
+#[cfg(not(feature = "disable-metadata-updater"))]
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MetadataManifestChunk {
+  hash: String, // SHA-1
+  size: usize,
+}
+
+#[cfg(not(feature = "disable-metadata-updater"))]
+#[derive(Debug, Snafu)]
+enum MetadataDeltaError {
+  #[snafu(display("Failed to fetch metadata delta"))]
+  Fetch { source: reqwest::Error },
+
+  #[snafu(display("Metadata chunk {hash} mismatch"))]
+  ChunkMismatch { hash: String },
+
+  #[snafu(display("Assembled metadata hash mismatch"))]
+  AssembledMismatch,
+}
+
 #[cfg(not(feature = "disable-metadata-updater"))]
 impl Metadata {
   const API_BASE_URL: &str = "https://hoyo-gacha-v1.lgou2w.com/GachaMetadata/v2";
+  /// Ed25519 public keys `(key id, key)` of the metadata publisher, any of them is accepted.
+  ///
+  /// The private key is held by the publisher, it signs `{hash}.json` on the server.
+  /// Rotation: add the new key here and release it, then switch the signing key
+  /// on the server. The old key is removed in a later release.
+  ///
+  /// FIXME: The key below is a placeholder of the development, not the key of the publisher.
+  ///   No signing key has been published for `API_BASE_URL` yet. Replace it with the key
+  ///   of the publisher, and name the source it is published at, before enforcing.
+  const API_PUBLIC_KEYS: &[(&str, &[u8])] = &[(
+    "placeholder",
+    &[
+      0xb9, 0x3e, 0x5d, 0xb7, 0x95, 0x8a, 0x17, 0x8c, 0x14, 0xb2, 0x22, 0xfb, 0xc6, 0x7e, 0x8d,
+      0xc9, 0x0f, 0xeb, 0xe9, 0x5d, 0x4a, 0xb6, 0xe7, 0x7e, 0x89, 0x31, 0x5c, 0x72, 0x70, 0xcc,
+      0xcd, 0x47,
+    ],
+  )];
+  /// Whether a missing or invalid signature fails the update.
+  ///
+  /// Warn-only until the server publishes the signatures and the key of the publisher
+  /// is embedded, the data is still verified by its SHA-1 hash. (See: `API_PUBLIC_KEYS`)
+  const API_SIGNATURE_ENFORCED: bool = false;
   const API_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
   const API_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
   const OUT_DIRECTORY: &str = "GachaMetadata";
@@ -264,7 +527,7 @@ impl Metadata {
     }
 
     // Only `Deserialize` or `Bake` errors will occur; see `from_raw` for details.
-    match MetadataInner::from_raw(&bytes, Some(hash)) {
+    match MetadataInner::from_raw(&bytes, Some(hash), self.overlay.as_ref()) {
       Err(err) => {
         // JSON syntax error, possibly caused by manual modification by the user.
         // Remove this caches file
@@ -282,6 +545,11 @@ impl Metadata {
     self.updating.load(std::sync::atomic::Ordering::SeqCst)
   }
 
+  /// Get the changes of the last successful update, if any.
+  pub async fn changes(&self) -> Option<hg_metadata::diff::MetadataDiff> {
+    self.changes.read().await.clone()
+  }
+
   #[tracing::instrument(skip(self))]
   pub async fn update(&self) -> Result<MetadataUpdateKind, MetadataUpdateError> {
     if self
@@ -309,38 +577,42 @@ impl Metadata {
     // 1. Fetch latest metadata index and compare hash
     //   if matches, return early
     let latest_hash = Self::fetch_metadata_index().await.context(ReqwestSnafu)?;
-    {
-      let guard = self.inner.read().await;
-      if guard.hash == latest_hash {
-        info!(message = "Metadata is already up-to-date", ?latest_hash);
-        return Ok(MetadataUpdateKind::UpToDate(latest_hash));
-      }
+    let current_hash = { self.inner.read().await }.hash.clone();
+    if current_hash == latest_hash {
+      info!(message = "Metadata is already up-to-date", ?latest_hash);
+      return Ok(MetadataUpdateKind::UpToDate(latest_hash));
     }
 
-    // 2. Fetch latest metadata data and verify hash
-    //   if mismatched, retry fetch ?
-    let downloaded_data = Self::fetch_metadata_data(&latest_hash)
+    // 2. Fetch latest metadata data, verify hash and signature
+    //   the delta from the current caches is preferred, if it is the current.
+    //   if hash mismatched, retry fetch
+    let current_data = tokio::fs::read(Self::caches_file())
       .await
-      .context(ReqwestSnafu)?
-      .bytes()
-      .await
-      .context(ReqwestSnafu)?;
-
-    let downloaded_hash = sha1sum(&downloaded_data);
-    if downloaded_hash != latest_hash {
-      tracing::error!(
-        message = "Downloaded metadata hash mismatch",
-        expected = ?latest_hash,
-        actual = ?downloaded_hash,
-      );
-      return DownloadedMismatchSnafu.fail();
-    }
+      .ok()
+      .filter(|data| sha1sum(data) == current_hash);
+
+    let downloaded_data = Self::download_verified(
+      Self::API_BASE_URL,
+      &latest_hash,
+      Self::API_PUBLIC_KEYS,
+      Self::API_SIGNATURE_ENFORCED,
+      current_data
+        .as_deref()
+        .map(|data| (current_hash.as_str(), data)),
+    )
+    .await?;
 
     // 3. Bake metadata and write
-    let inner =
-      MetadataInner::from_raw(&downloaded_data, Some(downloaded_hash)).context(ProcessSnafu)?;
+    let changes = Self::inspect_downloaded(&downloaded_data).await;
+    let inner = MetadataInner::from_raw(
+      &downloaded_data,
+      Some(latest_hash.clone()),
+      self.overlay.as_ref(),
+    )
+    .context(ProcessSnafu)?;
     {
       *self.inner.write().await = inner;
+      *self.changes.write().await = changes;
     }
 
     // 4. Write to caches file
@@ -354,7 +626,10 @@ impl Metadata {
       );
     }
 
-    // 5. Done
+    // 5. The game biz overlay shipped with the metadata, not fatal
+    Self::update_game_biz_overlay(&latest_hash).await;
+
+    // 6. Done
     info!(
       message = "Metadata update completed",
       elapsed = ?start.elapsed(),
@@ -364,6 +639,45 @@ impl Metadata {
     Ok(MetadataUpdateKind::Success(latest_hash))
   }
 
+  /// Log the validation issues of the downloaded raw metadata, and the changes
+  /// between the current (the caches file, or the embedded) and the downloaded.
+  /// Returns the changes, if both can be deserialized.
+  #[tracing::instrument(skip_all)]
+  async fn inspect_downloaded(downloaded_data: &[u8]) -> Option<hg_metadata::diff::MetadataDiff> {
+    let caches_file = Self::caches_file();
+    let current = match tokio::fs::read(&caches_file).await {
+      Ok(bytes) => RawMetadata::from_slice(&bytes).ok(),
+      Err(_) => compact::decode(MetadataInner::EMBEDDED_METADATA).ok(),
+    };
+
+    // Only for logging, ignore the errors.
+    // The downloaded data will be checked later.
+    let (Some(current), Ok(downloaded)) = (current, RawMetadata::from_slice(downloaded_data))
+    else {
+      return None;
+    };
+
+    for issue in hg_metadata::validate::validate(&downloaded) {
+      tracing::warn!(message = "Downloaded metadata issue", %issue);
+    }
+
+    let diff = hg_metadata::diff::diff(&current, &downloaded);
+    info!(message = "Metadata changes", summary = %diff);
+
+    for business in &diff.businesses {
+      for (category, count) in business.added_items_by_category() {
+        info!(
+          message = "New items",
+          business = business.business_id,
+          category,
+          count
+        );
+      }
+    }
+
+    Some(diff)
+  }
+
   #[tracing::instrument(skip(self))]
   pub fn update_with_retry(
     self: std::sync::Arc<Self>,
@@ -430,8 +744,260 @@ impl Metadata {
     tokio::spawn(f)
   }
 
+  /// Download the metadata data of the hash, and verify its SHA-1 hash
+  /// and the detached ed25519 signature `{hash}.json.sig`. (Raw 64 bytes)
+  /// The signature of any of the `public_keys` is accepted. (See: `check_signature`)
+  ///
+  /// With the `current` (hash, data), the delta is tried first,
+  /// and falls back to the full download on any error.
+  #[tracing::instrument(skip(public_keys, current))]
+  async fn download_verified(
+    base_url: &str,
+    hash: &str,
+    public_keys: &[(&str, &[u8])],
+    enforce_signature: bool,
+    current: Option<(&str, &[u8])>,
+  ) -> Result<Vec<u8>, MetadataUpdateError> {
+    let delta = match current {
+      None => None,
+      Some((current_hash, current_data)) => {
+        match Self::download_delta(base_url, current_hash, current_data, hash).await {
+          Ok(data) => Some(data),
+          Err(err) => {
+            tracing::warn!(
+              message = "Metadata delta update failed, falling back to full download",
+              ?err
+            );
+            None
+          }
+        }
+      }
+    };
+
+    let data = match delta {
+      Some(data) => data,
+      None => {
+        let data = Self::fetch_metadata_data(base_url, hash)
+          .await
+          .context(ReqwestSnafu)?
+          .bytes()
+          .await
+          .context(ReqwestSnafu)?;
+
+        let data_hash = sha1sum(&data);
+        if data_hash != hash {
+          tracing::error!(
+            message = "Downloaded metadata hash mismatch",
+            expected = ?hash,
+            actual = ?data_hash,
+          );
+          return DownloadedMismatchSnafu.fail();
+        }
+
+        data.to_vec()
+      }
+    };
+
+    let signature = match Self::fetch_metadata_signature(base_url, hash).await {
+      Ok(signature) => Some(signature),
+      // The server has not published the signature of the data.
+      Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => None,
+      Err(source) => return Err(MetadataUpdateError::Reqwest { source }),
+    };
+
+    Self::check_signature(&data, signature.as_deref(), public_keys, enforce_signature)?;
+    Ok(data)
+  }
+
+  /// Download and register the `GameBiz` overlay shipped with the metadata of the hash.
+  /// If the metadata has none, the overlay file of the previous is removed.
+  #[tracing::instrument]
+  async fn update_game_biz_overlay(hash: &str) {
+    let overlay_file = Self::game_biz_overlay_file();
+
+    match Self::download_game_biz_overlay(
+      Self::API_BASE_URL,
+      hash,
+      Self::API_PUBLIC_KEYS,
+      Self::API_SIGNATURE_ENFORCED,
+    )
+    .await
+    {
+      Err(err) => {
+        // Keep the previous, try again on the next update.
+        tracing::error!(message = "Failed to download game biz overlay", ?err);
+      }
+      Ok(None) => {
+        debug!("No game biz overlay is shipped with the metadata");
+        hg_game_biz::registry::clear();
+        let _ = tokio::fs::remove_file(&overlay_file).await;
+      }
+      Ok(Some(data)) => {
+        hg_game_biz::registry::clear();
+        if !Self::register_game_biz_overlay(&data) {
+          // Keep the previous
+          Self::load_game_biz_overlay().await;
+        } else if let Err(err) = tokio::fs::write(&overlay_file, &data).await {
+          tracing::error!(
+            message = "Failed to write game biz overlay file",
+            ?overlay_file,
+            ?err,
+          );
+        }
+      }
+    }
+  }
+
+  /// Download the `GameBiz` overlay `{hash}.GameBiz.json` of the metadata, and verify
+  /// the detached ed25519 signature `{hash}.GameBiz.json.sig` like the metadata.
+  /// Returns `None` if the metadata has none.
+  #[tracing::instrument(skip(public_keys))]
+  async fn download_game_biz_overlay(
+    base_url: &str,
+    hash: &str,
+    public_keys: &[(&str, &[u8])],
+    enforce_signature: bool,
+  ) -> Result<Option<Vec<u8>>, MetadataUpdateError> {
+    let pathname = format!("{hash}.{}", Self::GAME_BIZ_OVERLAY_FILE);
+    let data = match Self::fetch_api(base_url, &pathname).await {
+      Ok(response) => response.bytes().await.context(ReqwestSnafu)?,
+      Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => return Ok(None),
+      Err(source) => return Err(MetadataUpdateError::Reqwest { source }),
+    };
+
+    let signature = match Self::fetch_api(base_url, &format!("{pathname}.sig")).await {
+      Ok(response) => Some(response.bytes().await.context(ReqwestSnafu)?),
+      Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => None,
+      Err(source) => return Err(MetadataUpdateError::Reqwest { source }),
+    };
+
+    Self::check_signature(&data, signature.as_deref(), public_keys, enforce_signature)?;
+    Ok(Some(data.to_vec()))
+  }
+
+  /// Check the detached signature of the data, `None` if it is not published.
+  ///
+  /// Unless `enforce`, a missing or invalid signature is only warned and the data
+  /// is accepted. (See: `API_SIGNATURE_ENFORCED`)
+  fn check_signature(
+    data: &[u8],
+    signature: Option<&[u8]>,
+    public_keys: &[(&str, &[u8])],
+    enforce: bool,
+  ) -> Result<(), MetadataUpdateError> {
+    let error = match signature {
+      None => MetadataUpdateError::MissingSignature,
+      Some(signature) => match Self::verify_signature(data, signature, public_keys) {
+        Some(key_id) => {
+          debug!(message = "Downloaded signature verified", ?key_id);
+          return Ok(());
+        }
+        None => MetadataUpdateError::InvalidSignature,
+      },
+    };
+
+    if enforce {
+      // Not retriable, the data is not published by us.
+      tracing::error!(message = "Downloaded signature is rejected", ?error);
+      Err(error)
+    } else {
+      tracing::warn!(message = "Downloaded signature is not verified", ?error);
+      Ok(())
+    }
+  }
+
+  /// Returns the key id of the `public_keys` that verifies the signature of the data.
+  fn verify_signature<'k>(
+    data: &[u8],
+    signature: &[u8],
+    public_keys: &[(&'k str, &[u8])],
+  ) -> Option<&'k str> {
+    use ring::signature::{ED25519, UnparsedPublicKey};
+
+    public_keys.iter().find_map(|(key_id, public_key)| {
+      UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(data, signature)
+        .is_ok()
+        .then_some(*key_id)
+    })
+  }
+
+  /// Assemble the metadata data of the hash from the chunks.
+  /// Reuse the chunks of the current data, only download the changed.
+  #[tracing::instrument(skip(current_data))]
+  async fn download_delta(
+    base_url: &str,
+    current_hash: &str,
+    current_data: &[u8],
+    hash: &str,
+  ) -> Result<Vec<u8>, MetadataDeltaError> {
+    use snafu::{OptionExt, ensure};
+    use std::collections::HashMap;
+
+    let current_manifest = Self::fetch_metadata_manifest(base_url, current_hash)
+      .await
+      .context(FetchSnafu)?;
+    let manifest = Self::fetch_metadata_manifest(base_url, hash)
+      .await
+      .context(FetchSnafu)?;
+
+    // Slice the current data into chunks: sha1 : bytes
+    let mut chunks = HashMap::<&str, &[u8]>::with_capacity(current_manifest.chunks.len());
+    let mut offset = 0;
+    for chunk in &current_manifest.chunks {
+      let bytes = current_data
+        .get(offset..)
+        .and_then(|rest| rest.get(..chunk.size))
+        .context(ChunkMismatchSnafu { hash: &chunk.hash })?;
+
+      ensure!(
+        sha1sum(bytes) == chunk.hash,
+        ChunkMismatchSnafu { hash: &chunk.hash }
+      );
+
+      chunks.insert(&chunk.hash, bytes);
+      offset += chunk.size;
+    }
+
+    let size = manifest.chunks.iter().map(|chunk| chunk.size).sum();
+    let mut data = Vec::with_capacity(size);
+    let mut downloaded = 0;
+
+    for chunk in &manifest.chunks {
+      if let Some(bytes) = chunks.get(chunk.hash.as_str()) {
+        data.extend_from_slice(bytes);
+        continue;
+      }
+
+      let bytes = Self::fetch_api(base_url, &format!("chunks/{}", chunk.hash))
+        .await
+        .context(FetchSnafu)?
+        .bytes()
+        .await
+        .context(FetchSnafu)?;
+
+      ensure!(
+        bytes.len() == chunk.size && sha1sum(&bytes) == chunk.hash,
+        ChunkMismatchSnafu { hash: &chunk.hash }
+      );
+
+      downloaded += bytes.len();
+      data.extend_from_slice(&bytes);
+    }
+
+    ensure!(sha1sum(&data) == hash, AssembledMismatchSnafu);
+
+    info!(
+      message = "Metadata delta downloaded",
+      downloaded,
+      reused = data.len() - downloaded,
+    );
+
+    Ok(data)
+  }
+
   #[inline]
-  async fn fetch_api(pathname: &str) -> reqwest::Result<reqwest::Response> {
+  async fn fetch_api(base_url: &str, pathname: &str) -> reqwest::Result<reqwest::Response> {
     use crate::constants;
     use reqwest::Client as Reqwest;
     use std::sync::LazyLock;
@@ -446,7 +1012,7 @@ impl Metadata {
     });
 
     REQWEST
-      .get(format!("{}/{pathname}", Self::API_BASE_URL))
+      .get(format!("{base_url}/{pathname}"))
       .send()
       .await?
       .error_for_status()
@@ -462,17 +1028,20 @@ impl Metadata {
 
     debug!("Checking for latest metadata version...");
     const PATHNAME: &str = "index.json";
-    let index: MetadataIndex = Self::fetch_api(PATHNAME).await?.json().await?;
+    let index: MetadataIndex = Self::fetch_api(Self::API_BASE_URL, PATHNAME)
+      .await?
+      .json()
+      .await?;
     info!("Latest metadata version: {:?}", index.latest);
 
     Ok(index.latest)
   }
 
   #[tracing::instrument]
-  async fn fetch_metadata_data(hash: &str) -> reqwest::Result<reqwest::Response> {
+  async fn fetch_metadata_data(base_url: &str, hash: &str) -> reqwest::Result<reqwest::Response> {
     debug!(message = "Fetching metadata data...");
     let pathname = format!("{hash}.json");
-    let data = Self::fetch_api(&pathname).await?;
+    let data = Self::fetch_api(base_url, &pathname).await?;
     info!(
       message = "Metadata data fetched",
       len = data.content_length()
@@ -480,12 +1049,31 @@ impl Metadata {
 
     Ok(data)
   }
+
+  #[tracing::instrument]
+  async fn fetch_metadata_manifest(
+    base_url: &str,
+    hash: &str,
+  ) -> reqwest::Result<MetadataManifest> {
+    debug!(message = "Fetching metadata manifest...");
+    let pathname = format!("{hash}.manifest.json");
+    Self::fetch_api(base_url, &pathname).await?.json().await
+  }
+
+  #[tracing::instrument]
+  async fn fetch_metadata_signature(base_url: &str, hash: &str) -> reqwest::Result<Vec<u8>> {
+    debug!(message = "Fetching metadata signature...");
+    let pathname = format!("{hash}.json.sig");
+    let signature = Self::fetch_api(base_url, &pathname).await?.bytes().await?;
+
+    Ok(signature.to_vec())
+  }
 }
 
 // Test only embedded metadata
 #[cfg(test)]
 static EMBEDDED: std::sync::LazyLock<MetadataInner> =
-  std::sync::LazyLock::new(|| MetadataInner::new().expect("Failed to load embedded metadata"));
+  std::sync::LazyLock::new(|| MetadataInner::new(None).expect("Failed to load embedded metadata"));
 
 #[cfg(test)]
 impl Metadata {
@@ -505,6 +1093,416 @@ mod tests {
     let _ = Metadata::embedded();
   }
 
+  // The embedded compact metadata must produce identical lookups as the JSON.
+  #[test]
+  fn test_embedded_compact() {
+    const JSON: &[u8] = include_bytes!("../assets/GachaMetadata.json");
+    assert_eq!(sha1sum(JSON), MetadataInner::EMBEDDED_METADATA_HASH);
+
+    let raw = RawMetadata::from_slice(JSON).unwrap();
+    let json = MetadataInner::from_raw(JSON, None, None).unwrap().metadata;
+    let compact = Metadata::embedded();
+
+    for business in raw.as_ref() {
+      let locales = json.locales(business.id).unwrap();
+      assert_eq!(locales.len(), compact.locales(business.id).unwrap().len());
+
+      for locale in locales {
+        let compact_locale = compact.locale(business.id, locale.lang()).unwrap();
+        let entries = locale.entries();
+        assert_eq!(entries, compact_locale.entries());
+
+        for entry in entries.values() {
+          assert_eq!(
+            locale.entry_from_name(entry.item_name),
+            compact_locale.entry_from_name(entry.item_name)
+          );
+        }
+      }
+
+      let gacha_types = business
+        .banners
+        .iter()
+        .map(|banner| banner.gacha_type)
+        .collect::<std::collections::BTreeSet<_>>();
+
+      for gacha_type in gacha_types {
+        let banners = json.banners(business.id, gacha_type).unwrap();
+        let compact_banners = compact.banners(business.id, gacha_type).unwrap();
+        assert_eq!(banners.len(), compact_banners.len());
+
+        for (banner, compact_banner) in banners.into_iter().zip(compact_banners) {
+          assert_eq!(banner.gacha_id(), compact_banner.gacha_id());
+          assert_eq!(banner.start_time(), compact_banner.start_time());
+          assert_eq!(banner.end_time(), compact_banner.end_time());
+          assert_eq!(banner.version(), compact_banner.version());
+          assert_eq!(banner.up_golden(), compact_banner.up_golden());
+          assert_eq!(banner.up_purple(), compact_banner.up_purple());
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_embedded_with_overlay() {
+    let overlay = OverlayMetadata::from_str(
+      r#"[
+        {
+          "Id": 0,
+          "Categories": [
+            {
+              "Category": "Character",
+              "Entries": [[10000002, 5], [10999999, 5]],
+              "I18n": {
+                "en-us": {
+                  "Category": "Character",
+                  "Entries": ["Kamisato Ayaka", "Unreleased"]
+                }
+              }
+            }
+          ]
+        }
+      ]"#,
+    )
+    .unwrap();
+
+    let inner = MetadataInner::new(Some(&overlay)).unwrap();
+    assert_eq!(inner.hash, MetadataInner::EMBEDDED_METADATA_HASH);
+
+    let report = inner.overlay.unwrap();
+    assert!(report.contains_item(RawMetadata::BUSINESS_HK4E, 10999999));
+    assert_eq!(report.superseded_items.len(), 1);
+
+    for locale in inner.metadata.locales(RawMetadata::BUSINESS_HK4E).unwrap() {
+      let entry = locale.entry_from_id(10999999).unwrap();
+      assert_eq!(entry.item_name, "Unreleased");
+      assert_eq!(entry.category, "Character");
+    }
+  }
+
+  // Serve the files over HTTP/1.1 on a random local port, returns the base url.
+  #[cfg(not(feature = "disable-metadata-updater"))]
+  fn serve_files(files: std::collections::HashMap<String, Vec<u8>>) -> String {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    std::thread::spawn(move || {
+      for mut stream in listener.incoming().flatten() {
+        let mut request_line = String::new();
+        let mut reader = BufReader::new(&stream);
+        if reader.read_line(&mut request_line).is_err() {
+          continue;
+        }
+
+        // Skip the headers
+        let mut line = String::new();
+        while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+          line.clear();
+        }
+
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let _ = match files.get(path) {
+          Some(body) => write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+          )
+          .and_then(|_| stream.write_all(body)),
+          None => write!(
+            stream,
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+          ),
+        };
+      }
+    });
+
+    format!("http://{addr}")
+  }
+
+  #[cfg(not(feature = "disable-metadata-updater"))]
+  #[tokio::test]
+  async fn test_download_verified() {
+    use std::collections::HashMap;
+
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    // Test only keypair
+    let keypair = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let public_key = keypair.public_key().as_ref().to_vec();
+    let other_public_key = Ed25519KeyPair::from_seed_unchecked(&[8; 32])
+      .unwrap()
+      .public_key()
+      .as_ref()
+      .to_vec();
+
+    let data = br#"[{"Id":0,"Categories":[],"Banners":[]}]"#.to_vec();
+    let hash = sha1sum(&data);
+    let signature = keypair.sign(&data).as_ref().to_vec();
+
+    let tampered = br#"[{"Id":1,"Categories":[],"Banners":[]}]"#.to_vec();
+    let tampered_hash = sha1sum(&tampered);
+
+    let files = HashMap::from_iter([
+      (format!("/valid/{hash}.json"), data.clone()),
+      (format!("/valid/{hash}.json.sig"), signature.clone()),
+      // Signed by the other data
+      (format!("/tampered/{tampered_hash}.json"), tampered.clone()),
+      (
+        format!("/tampered/{tampered_hash}.json.sig"),
+        signature.clone(),
+      ),
+      // Truncated signature
+      (format!("/truncated/{hash}.json"), data.clone()),
+      (
+        format!("/truncated/{hash}.json.sig"),
+        signature[..32].to_vec(),
+      ),
+      // No signature
+      (format!("/unsigned/{hash}.json"), data.clone()),
+      // Not the data of the hash
+      (format!("/mismatch/{hash}.json"), tampered.clone()),
+    ]);
+
+    let base_url = serve_files(files);
+    let download = |path: &str, hash: &str, public_key: &[u8]| {
+      let base_url = format!("{base_url}/{path}");
+      let hash = hash.to_owned();
+      let public_key = public_key.to_vec();
+      async move {
+        Metadata::download_verified(
+          &base_url,
+          &hash,
+          &[("test", public_key.as_slice())],
+          true,
+          None,
+        )
+        .await
+      }
+    };
+
+    assert_eq!(download("valid", &hash, &public_key).await.unwrap(), data);
+
+    assert!(matches!(
+      download("valid", &hash, &other_public_key).await,
+      Err(MetadataUpdateError::InvalidSignature)
+    ));
+    assert!(matches!(
+      download("tampered", &tampered_hash, &public_key).await,
+      Err(MetadataUpdateError::InvalidSignature)
+    ));
+    assert!(matches!(
+      download("truncated", &hash, &public_key).await,
+      Err(MetadataUpdateError::InvalidSignature)
+    ));
+    assert!(matches!(
+      download("unsigned", &hash, &public_key).await,
+      Err(MetadataUpdateError::MissingSignature)
+    ));
+    assert!(matches!(
+      download("mismatch", &hash, &public_key).await,
+      Err(MetadataUpdateError::DownloadedMismatch)
+    ));
+
+    // Rotation, any of the keys is accepted
+    let public_keys = [
+      ("old", other_public_key.as_slice()),
+      ("new", public_key.as_slice()),
+    ];
+    assert_eq!(
+      Metadata::download_verified(
+        &format!("{base_url}/valid"),
+        &hash,
+        &public_keys,
+        true,
+        None
+      )
+      .await
+      .unwrap(),
+      data
+    );
+
+    // Warn-only, verified by the hash
+    for path in ["unsigned", "truncated"] {
+      assert_eq!(
+        Metadata::download_verified(
+          &format!("{base_url}/{path}"),
+          &hash,
+          &public_keys,
+          false,
+          None
+        )
+        .await
+        .unwrap(),
+        data,
+        "{path}"
+      );
+    }
+    assert!(matches!(
+      Metadata::download_verified(
+        &format!("{base_url}/mismatch"),
+        &hash,
+        &public_keys,
+        false,
+        None
+      )
+      .await,
+      Err(MetadataUpdateError::DownloadedMismatch)
+    ));
+  }
+
+  #[cfg(not(feature = "disable-metadata-updater"))]
+  #[tokio::test]
+  async fn test_download_game_biz_overlay() {
+    use std::collections::HashMap;
+
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    let keypair = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let public_key = keypair.public_key().as_ref().to_vec();
+    let public_keys = [("test", public_key.as_slice())];
+
+    let hash = "0123456789abcdef0123456789abcdef01234567";
+    let data = br#"[{"game":"hk4e","server":"oversea","codename":"hk4e_global","region":"os_new","timezone":8}]"#.to_vec();
+    let signature = keypair.sign(&data).as_ref().to_vec();
+
+    let files = HashMap::from_iter([
+      (format!("/valid/{hash}.GameBiz.json"), data.clone()),
+      (format!("/valid/{hash}.GameBiz.json.sig"), signature.clone()),
+      (format!("/unsigned/{hash}.GameBiz.json"), data.clone()),
+      (format!("/tampered/{hash}.GameBiz.json"), b"[]".to_vec()),
+      (format!("/tampered/{hash}.GameBiz.json.sig"), signature),
+    ]);
+
+    let base_url = serve_files(files);
+    let download = |path: &str, enforce: bool| {
+      let base_url = format!("{base_url}/{path}");
+      async move { Metadata::download_game_biz_overlay(&base_url, hash, &public_keys, enforce).await }
+    };
+
+    assert_eq!(download("valid", true).await.unwrap(), Some(data.clone()));
+    assert_eq!(download("none", true).await.unwrap(), None);
+    assert!(matches!(
+      download("unsigned", true).await,
+      Err(MetadataUpdateError::MissingSignature)
+    ));
+    assert!(matches!(
+      download("tampered", true).await,
+      Err(MetadataUpdateError::InvalidSignature)
+    ));
+
+    // Warn-only
+    assert_eq!(download("unsigned", false).await.unwrap(), Some(data));
+    assert_eq!(
+      download("tampered", false).await.unwrap(),
+      Some(b"[]".to_vec())
+    );
+  }
+
+  #[cfg(not(feature = "disable-metadata-updater"))]
+  #[tokio::test]
+  async fn test_download_delta() {
+    use std::collections::HashMap;
+
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    let keypair = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let public_key = keypair.public_key().as_ref().to_vec();
+    let public_keys = [("test", public_key.as_slice())];
+
+    // One chunk per business
+    let current_chunks = [&b"[{\"Id\":0}"[..], b",{\"Id\":1}", b"]"];
+    let chunks = [&b"[{\"Id\":0}"[..], b",{\"Id\":1,\"Banners\":[]}", b"]"];
+
+    let current_data = current_chunks.concat();
+    let current_hash = sha1sum(&current_data);
+    let data = chunks.concat();
+    let hash = sha1sum(&data);
+    let signature = keypair.sign(&data).as_ref().to_vec();
+
+    let manifest = |chunks: &[&[u8]]| {
+      let chunks = chunks
+        .iter()
+        .map(|chunk| serde_json::json!({ "Hash": sha1sum(chunk), "Size": chunk.len() }))
+        .collect::<Vec<_>>();
+
+      serde_json::to_vec(&serde_json::json!({ "Chunks": chunks })).unwrap()
+    };
+
+    let changed_chunk = format!("/chunks/{}", sha1sum(chunks[1]));
+    let files = HashMap::from_iter([
+      // Delta only, without the full data
+      (
+        format!("/delta/{current_hash}.manifest.json"),
+        manifest(&current_chunks),
+      ),
+      (format!("/delta/{hash}.manifest.json"), manifest(&chunks)),
+      (format!("/delta{changed_chunk}"), chunks[1].to_vec()),
+      (format!("/delta/{hash}.json.sig"), signature.clone()),
+      // Corrupted chunk, with the full data
+      (
+        format!("/corrupted/{current_hash}.manifest.json"),
+        manifest(&current_chunks),
+      ),
+      (
+        format!("/corrupted/{hash}.manifest.json"),
+        manifest(&chunks),
+      ),
+      (format!("/corrupted{changed_chunk}"), b",{}".to_vec()),
+      (format!("/corrupted/{hash}.json"), data.clone()),
+      (format!("/corrupted/{hash}.json.sig"), signature.clone()),
+      // Without manifests, the full data only
+      (format!("/full/{hash}.json"), data.clone()),
+      (format!("/full/{hash}.json.sig"), signature.clone()),
+    ]);
+
+    let base_url = serve_files(files);
+    let current = Some((current_hash.as_str(), &current_data[..]));
+
+    for path in ["delta", "corrupted", "full"] {
+      let downloaded = Metadata::download_verified(
+        &format!("{base_url}/{path}"),
+        &hash,
+        &public_keys,
+        true,
+        current,
+      )
+      .await
+      .unwrap();
+
+      assert_eq!(downloaded, data, "{path}");
+    }
+
+    // Delta is unavailable without the current
+    assert!(matches!(
+      Metadata::download_verified(
+        &format!("{base_url}/delta"),
+        &hash,
+        &public_keys,
+        true,
+        None
+      )
+      .await,
+      Err(MetadataUpdateError::Reqwest { .. })
+    ));
+
+    // The current data is not of the current manifest
+    let stale = Some((current_hash.as_str(), &data[..]));
+    assert!(matches!(
+      Metadata::download_verified(
+        &format!("{base_url}/delta"),
+        &hash,
+        &public_keys,
+        true,
+        stale
+      )
+      .await,
+      Err(MetadataUpdateError::Reqwest { .. })
+    ));
+  }
+
   #[cfg(not(feature = "disable-metadata-updater"))]
   #[test]
   fn test_update_error_serialize() {
@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::LazyLock;
 
-use hg_metadata::Metadata;
+use hg_metadata::{Metadata, MetadataBanner};
 use serde::Serialize;
 use time::serde::rfc3339;
 use time::{Date, OffsetDateTime};
@@ -313,8 +313,12 @@ impl<'a> PrettizedRecord<'a> {
       )
     });
 
-    let (is_up, version) = metadata
-      .query_banner(business as _, record.gacha_type, record.time)
+    // Prefer the gacha_id, it identifies the banner exactly.
+    // Double banners run at the same time with the same gacha_type.
+    let (is_up, version) = record
+      .gacha_id
+      .and_then(|gacha_id| metadata.query_banner_by_id(business as _, gacha_id))
+      .or_else(|| metadata.query_banner(business as _, record.gacha_type, record.time))
       .map(|banner| {
         let is_up = if (record.is_rank_golden() && banner.is_up_golden(record.item_id))
           || (record.is_rank_purple() && banner.is_up_purple(record.item_id))
@@ -359,6 +363,42 @@ impl<'a> PrettizedRecord<'a> {
   }
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrettizedBanner {
+  pub gacha_type: u32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub gacha_id: Option<u32>,
+  #[serde(with = "rfc3339")]
+  pub start_time: OffsetDateTime,
+  #[serde(with = "rfc3339")]
+  pub end_time: OffsetDateTime,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub version: Option<String>,
+  pub up_golden: Vec<u32>,
+  pub up_purple: Vec<u32>,
+}
+
+impl PrettizedBanner {
+  pub fn mapping(banner: &dyn MetadataBanner) -> Self {
+    let sorted = |items: &HashSet<u32>| {
+      let mut items = items.iter().copied().collect::<Vec<_>>();
+      items.sort_unstable();
+      items
+    };
+
+    Self {
+      gacha_type: banner.gacha_type(),
+      gacha_id: banner.gacha_id(),
+      start_time: *banner.start_time(),
+      end_time: *banner.end_time(),
+      version: banner.version().map(ToOwned::to_owned),
+      up_golden: sorted(banner.up_golden()),
+      up_purple: sorted(banner.up_purple()),
+    }
+  }
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PrettizedRecords<'a> {
@@ -23,6 +23,9 @@ cfg_if! {if #[cfg(any(debug_assertions, test))] {
   pub const USER_AGENT: &str = concat!("HoYo.Gacha/v", env!("CARGO_PKG_VERSION"));
 }}
 
+/// The folder name of the database backups.
+pub const DATABASE_BACKUPS: &str = "Backups";
+
 #[deprecated = "Legacy"]
 pub const DATABASE_LEGACY: &str = "HoYo.Gacha.db";
 
@@ -0,0 +1,482 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu, ensure};
+use time::OffsetDateTime;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::serde::rfc3339;
+use tracing::{error, info, warn};
+
+use crate::database::{Database, DatabaseError, migrations};
+use crate::error::ErrorDetails;
+
+// Database backups.
+//
+// The backups are created with `VACUUM INTO` before every migration,
+// bulk import, full update or repair, and rotated to keep the latest `MAX_BACKUPS`
+// of each reason. (A burst of imports never evicts the last migration backup)
+//
+//   ${backup_folder}/${database_stem}.${created_at}.${reason}.db
+//
+// A restore is staged next to the database (`${database}.restore`) and
+// swapped in on the next startup before connecting, the app should be relaunched.
+
+#[derive(Debug, Snafu)]
+pub enum DatabaseBackupError {
+  #[snafu(display("{source}"))]
+  Database { source: DatabaseError },
+
+  #[snafu(display("Database backup I/O error: {source}"))]
+  Io { source: io::Error },
+
+  #[snafu(display("Database backup not found: {filename}"))]
+  NotFound { filename: String },
+
+  #[snafu(display("Invalid database backup: {filename}"))]
+  Invalid { filename: String },
+
+  #[snafu(display("Unsupported database backup version: {version}, expected: 1..={expected}"))]
+  UnsupportedVersion { version: u32, expected: u32 },
+}
+
+impl ErrorDetails for DatabaseBackupError {
+  fn name(&self) -> &'static str {
+    match self {
+      Self::Database { source } => source.name(),
+      _ => stringify!(DatabaseBackupError),
+    }
+  }
+
+  fn details(&self) -> Option<serde_json::Value> {
+    use serde_json::json;
+
+    match self {
+      Self::Database { source } => source.details(),
+      Self::Io { source } => Some(json!({
+        "kind": stringify!(Io),
+        "cause": source.to_string(),
+      })),
+      Self::NotFound { filename } => Some(json!({
+        "kind": stringify!(NotFound),
+        "filename": filename,
+      })),
+      Self::Invalid { filename } => Some(json!({
+        "kind": stringify!(Invalid),
+        "filename": filename,
+      })),
+      Self::UnsupportedVersion { version, expected } => Some(json!({
+        "kind": stringify!(UnsupportedVersion),
+        "version": version,
+        "expected": expected,
+      })),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub enum DatabaseBackupReason {
+  Migration,
+  Import,
+  FullUpdate,
+  /// The database before restoring a backup.
+  Restore,
+  /// The database before repairing the integrity.
+  Repair,
+}
+
+impl DatabaseBackupReason {
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Self::Migration => "Migration",
+      Self::Import => "Import",
+      Self::FullUpdate => "FullUpdate",
+      Self::Restore => "Restore",
+      Self::Repair => "Repair",
+    }
+  }
+
+  fn parse(s: &str) -> Option<Self> {
+    match s {
+      "Migration" => Some(Self::Migration),
+      "Import" => Some(Self::Import),
+      "FullUpdate" => Some(Self::FullUpdate),
+      "Restore" => Some(Self::Restore),
+      "Repair" => Some(Self::Repair),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseBackup {
+  pub filename: String,
+  pub path: PathBuf,
+  pub reason: DatabaseBackupReason,
+  #[serde(with = "rfc3339")]
+  pub created_at: OffsetDateTime,
+  pub size: u64,
+  /// `PRAGMA USER_VERSION` of the backup.
+  pub version: u32,
+}
+
+/// The maximum number of backups to keep for each reason.
+pub const MAX_BACKUPS: usize = 10;
+
+/// UTC, microseconds to avoid the same filename.
+const CREATED_AT_FORMAT: &[FormatItem<'static>] =
+  format_description!("[year][month][day]T[hour][minute][second][subsecond digits:6]Z");
+
+const SQLITE_HEADER_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+// Read the `PRAGMA USER_VERSION` from the database header, without connecting.
+// See: https://www.sqlite.org/fileformat.html#the_database_header
+fn read_user_version(path: &Path) -> io::Result<Option<u32>> {
+  let mut header = [0; 100];
+  let mut file = File::open(path)?;
+  if let Err(e) = file.read_exact(&mut header) {
+    return match e.kind() {
+      io::ErrorKind::UnexpectedEof => Ok(None),
+      _ => Err(e),
+    };
+  }
+
+  if !header.starts_with(SQLITE_HEADER_MAGIC) {
+    return Ok(None);
+  }
+
+  let version = u32::from_be_bytes([header[60], header[61], header[62], header[63]]);
+  Ok(Some(version))
+}
+
+fn database_stem(database: &Path) -> &str {
+  database
+    .file_stem()
+    .and_then(|stem| stem.to_str())
+    .unwrap_or("database")
+}
+
+fn restore_path(database: &Path) -> PathBuf {
+  let mut path = database.as_os_str().to_owned();
+  path.push(".restore");
+  PathBuf::from(path)
+}
+
+impl DatabaseBackup {
+  // Parse the backup from the path, `None` if not a backup of the database.
+  fn from_path(database: &Path, path: PathBuf) -> io::Result<Option<Self>> {
+    let Some(filename) = path.file_name().and_then(|s| s.to_str()) else {
+      return Ok(None);
+    };
+
+    let Some((created_at, reason)) = filename
+      .strip_prefix(database_stem(database))
+      .and_then(|s| s.strip_prefix('.'))
+      .and_then(|s| s.strip_suffix(".db"))
+      .and_then(|s| s.split_once('.'))
+    else {
+      return Ok(None);
+    };
+
+    let (Ok(created_at), Some(reason)) = (
+      time::PrimitiveDateTime::parse(created_at, CREATED_AT_FORMAT),
+      DatabaseBackupReason::parse(reason),
+    ) else {
+      return Ok(None);
+    };
+
+    let Some(version) = read_user_version(&path)? else {
+      return Ok(None);
+    };
+
+    Ok(Some(Self {
+      filename: filename.to_owned(),
+      size: path.metadata()?.len(),
+      path,
+      reason,
+      created_at: created_at.assume_utc(),
+      version,
+    }))
+  }
+}
+
+impl Database {
+  /// Back up the database with `VACUUM INTO`, then rotate the backups.
+  #[tracing::instrument(skip(self))]
+  pub async fn backup(
+    &self,
+    reason: DatabaseBackupReason,
+  ) -> Result<DatabaseBackup, DatabaseBackupError> {
+    let start = Instant::now();
+    fs::create_dir_all(&self.backup_folder).context(IoSnafu)?;
+
+    let created_at = OffsetDateTime::now_utc();
+    let filename = format!(
+      "{}.{}.{}.db",
+      database_stem(&self.filename),
+      created_at
+        .format(CREATED_AT_FORMAT)
+        .expect("Failed to format backup time"),
+      reason.as_str()
+    );
+
+    let path = self.backup_folder.join(&filename);
+    sqlx::query("VACUUM INTO ?;")
+      .bind(path.to_string_lossy())
+      .execute(&self.inner)
+      .await
+      .map_err(DatabaseError::from)
+      .context(DatabaseSnafu)?;
+
+    let backup = DatabaseBackup::from_path(&self.filename, path)
+      .context(IoSnafu)?
+      .context(InvalidSnafu { filename })?;
+
+    info!(
+      message = "Database backup created",
+      elapsed = ?start.elapsed(),
+      ?backup
+    );
+
+    // Rotate, failures are not fatal
+    if let Err(e) = self.rotate_backups() {
+      warn!(message = "Failed to rotate database backups", ?e);
+    }
+
+    Ok(backup)
+  }
+
+  /// Returns the backups of the database, newest first.
+  pub fn backups(&self) -> Result<Vec<DatabaseBackup>, DatabaseBackupError> {
+    let entries = match fs::read_dir(&self.backup_folder) {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e).context(IoSnafu),
+    };
+
+    let mut backups = Vec::new();
+    for entry in entries {
+      let path = entry.context(IoSnafu)?.path();
+      if let Some(backup) = DatabaseBackup::from_path(&self.filename, path).context(IoSnafu)? {
+        backups.push(backup);
+      }
+    }
+
+    backups.sort_by_key(|backup| Reverse(backup.created_at));
+    Ok(backups)
+  }
+
+  fn rotate_backups(&self) -> Result<(), DatabaseBackupError> {
+    let mut counts = HashMap::new();
+    for backup in self.backups()? {
+      let count = counts.entry(backup.reason).or_insert(0);
+      *count += 1;
+      if *count <= MAX_BACKUPS {
+        continue;
+      }
+
+      info!(
+        message = "Removing the oldest database backup",
+        filename = ?backup.filename
+      );
+      fs::remove_file(&backup.path).context(IoSnafu)?;
+    }
+
+    Ok(())
+  }
+
+  /// Validate the backup and stage it to be restored on the next startup.
+  ///
+  /// The current database is backed up too. (`DatabaseBackupReason::Restore`)
+  #[tracing::instrument(skip(self))]
+  pub async fn stage_restore(&self, filename: &str) -> Result<DatabaseBackup, DatabaseBackupError> {
+    // Only the listed backups, not any path
+    let backup = self
+      .backups()?
+      .into_iter()
+      .find(|backup| backup.filename == filename)
+      .context(NotFoundSnafu { filename })?;
+
+    let expected = migrations::version();
+    ensure!(
+      backup.version >= 1 && backup.version <= expected,
+      UnsupportedVersionSnafu {
+        version: backup.version,
+        expected
+      }
+    );
+
+    // Staged first, the backup may be rotated
+    let restore = restore_path(&self.filename);
+    fs::copy(&backup.path, &restore).context(IoSnafu)?;
+
+    if let Err(e) = self.backup(DatabaseBackupReason::Restore).await {
+      let _ = fs::remove_file(&restore);
+      return Err(e);
+    }
+
+    info!(message = "Database restore staged", ?backup);
+    Ok(backup)
+  }
+
+  // Swap in the staged restore, before connecting.
+  pub(super) fn apply_staged_restore(filename: &Path) -> io::Result<()> {
+    let restore = restore_path(filename);
+    if !restore.try_exists()? {
+      return Ok(());
+    }
+
+    // Validated when staged, but the file may be changed
+    let expected = migrations::version();
+    match read_user_version(&restore)? {
+      Some(version) if version >= 1 && version <= expected => {}
+      version => {
+        error!(
+          message = "Discard the invalid staged database restore",
+          ?restore,
+          ?version,
+          expected
+        );
+        return fs::remove_file(&restore);
+      }
+    }
+
+    // The WAL files of the current database
+    for suffix in ["-wal", "-shm"] {
+      let mut path = filename.as_os_str().to_owned();
+      path.push(suffix);
+      match fs::remove_file(PathBuf::from(path)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+      }
+    }
+
+    fs::rename(&restore, filename)?;
+    info!(
+      message = "Database restored from the staged backup",
+      ?filename
+    );
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::database::schemas::{KeyValuePair, KeyValuePairQuestioner};
+
+  #[tokio::test]
+  async fn test_backup_and_restore() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let filename = temp_dir.path().join("test_backup.db");
+    let database = Database::new_with(&filename).await.unwrap();
+    database.apply_migrations().await.unwrap();
+
+    assert!(database.backups().unwrap().is_empty());
+
+    database.create_kv_pair("key", "backup").await.unwrap();
+    let backup = database.backup(DatabaseBackupReason::Import).await.unwrap();
+
+    assert_eq!(backup.reason, DatabaseBackupReason::Import);
+    assert_eq!(backup.version, migrations::version());
+    assert!(backup.size > 0);
+    assert_eq!(database.backups().unwrap(), vec![backup.clone()]);
+
+    // Rotation
+    for _ in 0..MAX_BACKUPS {
+      database
+        .backup(DatabaseBackupReason::FullUpdate)
+        .await
+        .unwrap();
+    }
+
+    // Rotated for each reason, the import backup is kept
+    let backups = database.backups().unwrap();
+    assert_eq!(backups.len(), MAX_BACKUPS + 1);
+    assert_eq!(backups.last(), Some(&backup));
+
+    database
+      .backup(DatabaseBackupReason::FullUpdate)
+      .await
+      .unwrap();
+
+    let backups = database.backups().unwrap();
+    assert_eq!(backups.len(), MAX_BACKUPS + 1);
+    assert_eq!(backups.last(), Some(&backup));
+
+    // Restore
+    let backup = backups.last().unwrap().clone();
+    database
+      .update_kv_pair("changed", Some(OffsetDateTime::now_utc()), "key")
+      .await
+      .unwrap();
+
+    assert!(matches!(
+      database.stage_restore("not_a_backup.db").await,
+      Err(DatabaseBackupError::NotFound { .. })
+    ));
+
+    assert_eq!(
+      database.stage_restore(&backup.filename).await.unwrap(),
+      backup
+    );
+
+    // The current database is backed up
+    assert_eq!(
+      database.backups().unwrap()[0].reason,
+      DatabaseBackupReason::Restore
+    );
+
+    database.close().await;
+
+    // Next startup
+    let database = Database::new_with(&filename).await.unwrap();
+    database.apply_migrations().await.unwrap();
+    assert!(!restore_path(&filename).exists());
+    assert!(matches!(
+      database.find_kv_pair("key").await.unwrap(),
+      Some(KeyValuePair { val, .. }) if val == "backup"
+    ));
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_restore_unsupported_version() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_restore.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let backup = database
+      .backup(DatabaseBackupReason::Migration)
+      .await
+      .unwrap();
+
+    // From a newer version
+    let newer = migrations::version() + 1;
+    let backup_database = Database::new_with(&backup.path).await.unwrap();
+    backup_database
+      .execute(format!("PRAGMA USER_VERSION = {newer};"))
+      .await
+      .unwrap();
+    backup_database.close().await;
+
+    assert!(matches!(
+      database.stage_restore(&backup.filename).await,
+      Err(DatabaseBackupError::UnsupportedVersion { version, .. }) if version == newer
+    ));
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+}
@@ -0,0 +1,501 @@
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{FromRow, Row, SqliteConnection};
+use tracing::{error, info};
+
+use crate::database::backup::DatabaseBackupReason;
+use crate::database::schemas::{Account, AccountBusiness, AccountQuestioner, GachaRecord};
+use crate::database::{Database, DatabaseError};
+
+// Database integrity check and repair.
+//
+// The check reports:
+//   * The errors of `PRAGMA integrity_check`, they cannot be repaired, restore a backup instead.
+//   * The rows of `HG_ACCOUNTS` and `HG_GACHA_RECORDS` that cannot be decoded.
+//     e.g. The `item_id` is not a number, or the `properties` is invalid JSON.
+//   * The records whose account does not exist.
+//
+// The repair moves the undecodable rows to `HG_QUARANTINE` (not to the recycle bin),
+// and re-creates the missing accounts. The database is backed up before repairing.
+
+struct IntegrityTable {
+  name: &'static str,
+  scan: &'static str,
+  quarantine: &'static str,
+  delete: &'static str,
+  delete_tombstone: &'static str,
+}
+
+impl IntegrityTable {
+  const ACCOUNTS: Self = Self {
+    name: "HG_ACCOUNTS",
+    scan: "SELECT `rowid` AS `_rowid`, JSON_OBJECT('business', `business`, 'uid', `uid`) AS `_identity`, *
+      FROM `HG_ACCOUNTS`;",
+    quarantine: "INSERT INTO `HG_QUARANTINE` (`source`, `row`, `reason`)
+      SELECT 'HG_ACCOUNTS', JSON_OBJECT(
+        'business', `business`, 'uid', `uid`, 'data_folder', `data_folder`, 'properties', `properties`
+      ), ? FROM `HG_ACCOUNTS` WHERE `rowid` = ?;",
+    delete: "DELETE FROM `HG_ACCOUNTS` WHERE `rowid` = ?;",
+    delete_tombstone: "DELETE FROM `HG_ACCOUNTS_DELETED` WHERE (`business`, `uid`) IN (
+      SELECT JSON_EXTRACT(`row`, '$.business'), JSON_EXTRACT(`row`, '$.uid')
+      FROM `HG_QUARANTINE` WHERE `id` = ?
+    );",
+  };
+
+  const GACHA_RECORDS: Self = Self {
+    name: "HG_GACHA_RECORDS",
+    scan: "SELECT `rowid` AS `_rowid`, JSON_OBJECT(
+        'business', `business`, 'uid', `uid`, 'id', `id`, 'gacha_type', `gacha_type`
+      ) AS `_identity`, * FROM `HG_GACHA_RECORDS`;",
+    quarantine: "INSERT INTO `HG_QUARANTINE` (`source`, `row`, `reason`)
+      SELECT 'HG_GACHA_RECORDS', JSON_OBJECT(
+        'business', `business`, 'uid', `uid`, 'id', `id`, 'gacha_type', `gacha_type`,
+        'gacha_id', `gacha_id`, 'rank_type', `rank_type`, 'count', `count`, 'time', `time`,
+        'lang', `lang`, 'name', `name`, 'item_type', `item_type`, 'item_id', `item_id`,
+        'properties', `properties`
+      ), ? FROM `HG_GACHA_RECORDS` WHERE `rowid` = ?;",
+    delete: "DELETE FROM `HG_GACHA_RECORDS` WHERE `rowid` = ?;",
+    delete_tombstone:
+      "DELETE FROM `HG_GACHA_RECORDS_DELETED` WHERE (`business`, `uid`, `id`, `gacha_type`) IN (
+      SELECT
+        JSON_EXTRACT(`row`, '$.business'), JSON_EXTRACT(`row`, '$.uid'),
+        JSON_EXTRACT(`row`, '$.id'), JSON_EXTRACT(`row`, '$.gacha_type')
+      FROM `HG_QUARANTINE` WHERE `id` = ?
+    );",
+  };
+
+  fn from_name(name: &str) -> Option<&'static Self> {
+    match name {
+      "HG_ACCOUNTS" => Some(&Self::ACCOUNTS),
+      "HG_GACHA_RECORDS" => Some(&Self::GACHA_RECORDS),
+      _ => None,
+    }
+  }
+
+  // Decode all rows, returns the decoded and the undecodable.
+  async fn scan<T>(
+    &self,
+    conn: &mut SqliteConnection,
+  ) -> Result<(Vec<T>, Vec<UndecodableRow>), sqlx::Error>
+  where
+    T: for<'r> FromRow<'r, SqliteRow>,
+  {
+    let rows = sqlx::query(self.scan).fetch_all(conn).await?;
+
+    let mut decoded = Vec::with_capacity(rows.len());
+    let mut undecodable = Vec::new();
+    for row in rows {
+      match T::from_row(&row) {
+        Ok(value) => decoded.push(value),
+        Err(e) => undecodable.push(UndecodableRow {
+          table: self.name,
+          rowid: row.try_get("_rowid")?,
+          identity: serde_json::from_str(row.try_get("_identity")?).unwrap_or_default(),
+          reason: e.to_string(),
+        }),
+      }
+    }
+
+    Ok((decoded, undecodable))
+  }
+
+  // Move the row to the quarantine. The tombstone created by the recycle bin
+  // trigger is also removed, otherwise it cannot be decoded either.
+  async fn quarantine(
+    &self,
+    conn: &mut SqliteConnection,
+    rowid: i64,
+    reason: &str,
+  ) -> Result<u64, sqlx::Error> {
+    let ret = sqlx::query(self.quarantine)
+      .bind(reason)
+      .bind(rowid)
+      .execute(&mut *conn)
+      .await?;
+
+    // Already gone
+    if ret.rows_affected() == 0 {
+      return Ok(0);
+    }
+
+    let quarantine_id = ret.last_insert_rowid();
+    let ret = sqlx::query(self.delete)
+      .bind(rowid)
+      .execute(&mut *conn)
+      .await?;
+
+    sqlx::query(self.delete_tombstone)
+      .bind(quarantine_id)
+      .execute(&mut *conn)
+      .await?;
+
+    Ok(ret.rows_affected())
+  }
+}
+
+/// The row that cannot be decoded.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UndecodableRow {
+  pub table: &'static str,
+  pub rowid: i64,
+  /// The primary key of the row as is, e.g. `{ business, uid }`
+  pub identity: serde_json::Value,
+  pub reason: String,
+}
+
+/// The records of an account that does not exist.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedRecords {
+  pub business: AccountBusiness,
+  pub uid: u32,
+  pub records: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+  /// The errors of `PRAGMA integrity_check`, empty if ok.
+  pub corruptions: Vec<String>,
+  pub undecodable: Vec<UndecodableRow>,
+  pub orphaned: Vec<OrphanedRecords>,
+}
+
+impl IntegrityReport {
+  #[inline]
+  pub fn is_ok(&self) -> bool {
+    self.corruptions.is_empty() && self.undecodable.is_empty() && self.orphaned.is_empty()
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityRepairOptions {
+  /// Move the undecodable rows to the quarantine.
+  #[serde(default)]
+  pub quarantine: bool,
+  /// Re-create the accounts of the orphaned records, with an empty data folder.
+  #[serde(default)]
+  pub recreate_accounts: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityRepairReport {
+  pub quarantined: u64,
+  pub recreated_accounts: Vec<Account>,
+  /// The report after repaired.
+  pub report: IntegrityReport,
+}
+
+async fn find_orphaned(conn: &mut SqliteConnection) -> Result<Vec<OrphanedRecords>, sqlx::Error> {
+  let rows = sqlx::query(
+    "SELECT `business`, `uid`, COUNT(*) AS `records` FROM `HG_GACHA_RECORDS` AS `records`
+      WHERE NOT EXISTS (
+        SELECT 1 FROM `HG_ACCOUNTS` AS `accounts`
+        WHERE `accounts`.`business` = `records`.`business` AND `accounts`.`uid` = `records`.`uid`
+      )
+      GROUP BY `business`, `uid` ORDER BY `business`, `uid`;",
+  )
+  .fetch_all(conn)
+  .await?;
+
+  // The undecodable ones are reported as the undecodable rows
+  Ok(
+    rows
+      .into_iter()
+      .filter_map(|row| {
+        Some(OrphanedRecords {
+          business: row.try_get("business").ok()?,
+          uid: row.try_get("uid").ok()?,
+          records: row.try_get::<i64, _>("records").ok()? as u64,
+        })
+      })
+      .collect(),
+  )
+}
+
+async fn check(conn: &mut SqliteConnection) -> Result<IntegrityReport, sqlx::Error> {
+  let corruptions = sqlx::query_scalar::<_, String>("PRAGMA integrity_check;")
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .filter(|message| message != "ok")
+    .collect();
+
+  let (_, mut undecodable) = IntegrityTable::ACCOUNTS.scan::<Account>(&mut *conn).await?;
+
+  let (_, undecodable_records) = IntegrityTable::GACHA_RECORDS
+    .scan::<GachaRecord>(&mut *conn)
+    .await?;
+  undecodable.extend(undecodable_records);
+
+  let orphaned = find_orphaned(&mut *conn).await?;
+
+  Ok(IntegrityReport {
+    corruptions,
+    undecodable,
+    orphaned,
+  })
+}
+
+impl Database {
+  #[tracing::instrument(skip(self))]
+  pub async fn check_integrity(&self) -> Result<IntegrityReport, DatabaseError> {
+    let start = Instant::now();
+    let mut conn = self.inner.acquire().await?;
+    let report = check(&mut conn).await?;
+
+    info!(
+      message = "Database integrity checked",
+      elapsed = ?start.elapsed(),
+      ok = report.is_ok(),
+      corruptions = report.corruptions.len(),
+      undecodable = report.undecodable.len(),
+      orphaned = report.orphaned.len(),
+    );
+
+    Ok(report)
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn repair_integrity(
+    &self,
+    options: IntegrityRepairOptions,
+  ) -> Result<IntegrityRepairReport, DatabaseError> {
+    if !options.quarantine && !options.recreate_accounts {
+      return Ok(IntegrityRepairReport {
+        report: self.check_integrity().await?,
+        ..Default::default()
+      });
+    }
+
+    // Back up before repairing, failures are not fatal
+    if let Err(e) = self.backup(DatabaseBackupReason::Repair).await {
+      error!(
+        message = "Failed to back up the database before repairing",
+        ?e
+      );
+    }
+
+    let start = Instant::now();
+    let mut txn = self.inner.begin().await?;
+
+    let ret = async {
+      let mut quarantined = 0;
+      if options.quarantine {
+        let report = check(&mut txn).await?;
+        for row in report.undecodable {
+          // SAFETY: Only the known tables are scanned
+          let table = IntegrityTable::from_name(row.table).unwrap();
+          quarantined += table.quarantine(&mut txn, row.rowid, &row.reason).await?;
+        }
+      }
+
+      // After quarantined, the records of the undecodable accounts are orphaned
+      let mut recreated_accounts = Vec::new();
+      if options.recreate_accounts {
+        for orphaned in find_orphaned(&mut txn).await? {
+          let account = (&mut *txn)
+            .create_account(orphaned.business, orphaned.uid, "", None)
+            .await?;
+          recreated_accounts.push(account);
+        }
+      }
+
+      Ok::<_, DatabaseError>((quarantined, recreated_accounts))
+    }
+    .await;
+
+    let (quarantined, recreated_accounts) = match ret {
+      Ok(ret) => ret,
+      Err(e) => {
+        error!(message = "Failed to repair the database", ?e);
+        txn.rollback().await?;
+        return Err(e);
+      }
+    };
+
+    txn.commit().await?;
+
+    info!(
+      message = "Database repaired",
+      elapsed = ?start.elapsed(),
+      quarantined,
+      recreated_accounts = recreated_accounts.len(),
+    );
+
+    Ok(IntegrityRepairReport {
+      quarantined,
+      recreated_accounts,
+      report: self.check_integrity().await?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::database::schemas::{GachaRecordQuestioner, RecycleBinQuestioner};
+
+  const INSERT_RECORD: &str = "INSERT INTO `HG_GACHA_RECORDS` (
+      `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+      `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`
+    ) VALUES (0, ?, ?, 301, NULL, 3, 1, '2023-01-01 00:00:00+08:00', 'en-us', 'Cool Steel', 'Weapon', ?, ?);";
+
+  #[tokio::test]
+  async fn test_integrity() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_integrity.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let business = AccountBusiness::GenshinImpact;
+    database
+      .create_account(business, 100_000_000, "data_folder", None)
+      .await
+      .unwrap();
+
+    // Invalid properties of the account
+    database
+      .execute(
+        "INSERT INTO `HG_ACCOUNTS` (`business`, `uid`, `data_folder`, `properties`)
+          VALUES (0, 100000001, 'data_folder', '{invalid');",
+      )
+      .await
+      .unwrap();
+
+    let records = [
+      (100_000_000, "1000000000000000000", "11301", None),
+      (100_000_000, "1000000000000000001", "invalid", None), // Invalid item_id
+      (
+        100_000_000,
+        "1000000000000000002",
+        "11301",
+        Some("{invalid"),
+      ), // Invalid properties
+      (100_000_001, "1000000000000000003", "11301", None),   // Of the invalid account
+      (100_000_002, "1000000000000000004", "11301", None),   // Orphaned
+    ];
+
+    for (uid, id, item_id, properties) in records {
+      sqlx::query(INSERT_RECORD)
+        .bind(uid)
+        .bind(id)
+        .bind(item_id)
+        .bind(properties)
+        .execute(&database.inner)
+        .await
+        .unwrap();
+    }
+
+    assert!(
+      database
+        .find_gacha_records(business, 100_000_000)
+        .await
+        .is_err()
+    );
+
+    let report = database.check_integrity().await.unwrap();
+    assert!(report.corruptions.is_empty());
+    assert_eq!(
+      report
+        .undecodable
+        .iter()
+        .map(|row| (row.table, row.identity.clone()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "HG_ACCOUNTS",
+          serde_json::json!({ "business": 0, "uid": 100_000_001 })
+        ),
+        (
+          "HG_GACHA_RECORDS",
+          serde_json::json!({ "business": 0, "uid": 100_000_000, "id": "1000000000000000001", "gacha_type": 301 })
+        ),
+        (
+          "HG_GACHA_RECORDS",
+          serde_json::json!({ "business": 0, "uid": 100_000_000, "id": "1000000000000000002", "gacha_type": 301 })
+        ),
+      ]
+    );
+    assert_eq!(
+      report.orphaned,
+      vec![OrphanedRecords {
+        business,
+        uid: 100_000_002,
+        records: 1,
+      }]
+    );
+
+    // Nothing to do
+    let repair = database
+      .repair_integrity(IntegrityRepairOptions::default())
+      .await
+      .unwrap();
+    assert_eq!(repair.quarantined, 0);
+    assert_eq!(repair.report, report);
+
+    let repair = database
+      .repair_integrity(IntegrityRepairOptions {
+        quarantine: true,
+        recreate_accounts: true,
+      })
+      .await
+      .unwrap();
+    assert_eq!(repair.quarantined, 3);
+    assert_eq!(
+      repair
+        .recreated_accounts
+        .iter()
+        .map(|account| (account.uid, account.data_folder.as_str()))
+        .collect::<Vec<_>>(),
+      vec![(100_000_001, ""), (100_000_002, "")]
+    );
+    assert!(repair.report.is_ok());
+
+    assert_eq!(
+      database
+        .find_gacha_records(business, 100_000_000)
+        .await
+        .unwrap()
+        .len(),
+      1
+    );
+
+    // Not in the recycle bin
+    assert!(database.find_deleted_accounts().await.unwrap().is_empty());
+    assert!(
+      database
+        .find_deleted_gacha_records_groups()
+        .await
+        .unwrap()
+        .is_empty()
+    );
+
+    let quarantined: Vec<(String, String)> =
+      sqlx::query_as("SELECT `source`, `row` FROM `HG_QUARANTINE` ORDER BY `id` ASC;")
+        .fetch_all(&database.inner)
+        .await
+        .unwrap();
+    assert_eq!(quarantined.len(), 3);
+    assert_eq!(quarantined[0].0, "HG_ACCOUNTS");
+
+    let row: serde_json::Value = serde_json::from_str(&quarantined[1].1).unwrap();
+    assert_eq!(row["item_id"], "invalid");
+
+    let backups = database.backups().unwrap();
+    assert!(
+      backups
+        .iter()
+        .any(|backup| backup.reason == DatabaseBackupReason::Repair)
+    );
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+}
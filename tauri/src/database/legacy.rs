@@ -16,7 +16,8 @@ use tracing::{debug, error, info, warn};
 use crate::business::data_folder::{DataFolderLocator, UnityLogDataFolderLocator};
 use crate::constants;
 use crate::database::schemas::{
-  AccountBusiness, AccountQuestioner, GachaRecord, GachaRecordSaveOnConflict, JsonProperties,
+  AccountBusiness, AccountQuestioner, AuditLogQuestioner, AuditOperation, GachaRecord,
+  GachaRecordSaveOnConflict, JsonProperties, audit_source_of,
 };
 use crate::database::{Database, DatabaseError};
 use crate::error::ErrorDetails;
@@ -161,6 +162,8 @@ impl LegacyMigration {
     let start = Instant::now();
     info!(message = "Migrating legacy database...", ?legacy, ?start);
 
+    let source = audit_source_of(&legacy);
+
     let legacy = SqlitePool::connect_with(
       SqliteConnectOptions::new()
         .filename(legacy)
@@ -179,6 +182,25 @@ impl LegacyMigration {
       .migrate_records(&mut txn, metadata, &legacy)
       .await?;
 
+    // Audit log
+    let counts = JsonProperties::from_iter([
+      ("accounts".into(), migration.accounts.into()),
+      (
+        "records".into(),
+        migration.records.values().sum::<u64>().into(),
+      ),
+    ]);
+    (&mut *txn)
+      .create_audit_log(
+        AuditOperation::LegacyMigration,
+        None,
+        None,
+        Some(counts),
+        source.as_deref(),
+      )
+      .await
+      .map_err(|source| LegacyMigrationError::Database { source })?;
+
     // Commit
     txn.commit().await?;
 
@@ -236,7 +258,7 @@ impl LegacyMigration {
 
       // parse and validate uid
       let uid = if let Ok(n) = uid.parse::<u32>()
-        && let Some(valid) = Uid::validate(business.as_game(), n)
+        && let Ok(valid) = Uid::try_validate(business.as_game(), n)
       {
         valid
       } else {
@@ -390,7 +412,7 @@ impl LegacyMigration {
 
       // parse and validate uid
       let uid = if let Ok(n) = uid.parse::<u32>()
-        && let Some(valid) = Uid::validate(business.as_game(), n)
+        && let Ok(valid) = Uid::try_validate(business.as_game(), n)
       {
         valid
       } else {
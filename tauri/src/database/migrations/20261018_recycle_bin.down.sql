@@ -0,0 +1,11 @@
+-- Down of the recycle bin, the deleted rows are dropped.
+
+DROP TRIGGER IF EXISTS `HG_ACCOUNTS.soft_delete`;
+DROP TRIGGER IF EXISTS `HG_ACCOUNTS.undelete`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.soft_delete`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.undelete`;
+
+DROP TABLE IF EXISTS `HG_ACCOUNTS_DELETED`;
+DROP TABLE IF EXISTS `HG_GACHA_RECORDS_DELETED`;
+
+PRAGMA USER_VERSION = 3;
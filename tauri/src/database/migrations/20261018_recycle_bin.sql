@@ -0,0 +1,72 @@
+BEGIN TRANSACTION;
+SAVEPOINT start_migration_v4;
+
+-- Recycle bin, the deleted rows are moved here by the triggers.
+
+CREATE TABLE IF NOT EXISTS `HG_ACCOUNTS_DELETED` (
+  `business`    INTEGER  NOT NULL,
+  `uid`         INTEGER  NOT NULL,
+  `data_folder` TEXT     NOT NULL,
+  `properties`  TEXT,
+  `deleted_at`  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`business`, `uid`)
+);
+CREATE INDEX IF NOT EXISTS `HG_ACCOUNTS_DELETED.deleted_at_idx` ON `HG_ACCOUNTS_DELETED` (`deleted_at`);
+
+CREATE TABLE IF NOT EXISTS `HG_GACHA_RECORDS_DELETED` (
+  `business`   INTEGER  NOT NULL,
+  `uid`        INTEGER  NOT NULL,
+  `id`         TEXT     NOT NULL,
+  `gacha_type` INTEGER  NOT NULL,
+  `gacha_id`   INTEGER,
+  `rank_type`  INTEGER  NOT NULL,
+  `count`      INTEGER  NOT NULL,
+  `time`       TEXT     NOT NULL,
+  `lang`       TEXT     NOT NULL,
+  `name`       TEXT     NOT NULL,
+  `item_type`  TEXT     NOT NULL,
+  `item_id`    TEXT     NOT NULL,
+  `properties` TEXT     DEFAULT NULL,
+  `deleted_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`business`, `uid`, `id`, `gacha_type`)
+);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS_DELETED.business_uid_idx` ON `HG_GACHA_RECORDS_DELETED` (`business`, `uid`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS_DELETED.deleted_at_idx`   ON `HG_GACHA_RECORDS_DELETED` (`deleted_at`);
+
+CREATE TRIGGER IF NOT EXISTS `HG_ACCOUNTS.soft_delete`
+AFTER DELETE ON `HG_ACCOUNTS`
+BEGIN
+  INSERT OR REPLACE INTO `HG_ACCOUNTS_DELETED` (`business`, `uid`, `data_folder`, `properties`)
+  VALUES (OLD.`business`, OLD.`uid`, OLD.`data_folder`, OLD.`properties`);
+END;
+
+-- Created again or restored, no longer deleted
+CREATE TRIGGER IF NOT EXISTS `HG_ACCOUNTS.undelete`
+AFTER INSERT ON `HG_ACCOUNTS`
+BEGIN
+  DELETE FROM `HG_ACCOUNTS_DELETED`
+  WHERE `business` = NEW.`business` AND `uid` = NEW.`uid`;
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.soft_delete`
+AFTER DELETE ON `HG_GACHA_RECORDS`
+BEGIN
+  INSERT OR REPLACE INTO `HG_GACHA_RECORDS_DELETED`
+    (`business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+    `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`)
+  VALUES
+    (OLD.`business`, OLD.`uid`, OLD.`id`, OLD.`gacha_type`, OLD.`gacha_id`, OLD.`rank_type`,
+    OLD.`count`, OLD.`time`, OLD.`lang`, OLD.`name`, OLD.`item_type`, OLD.`item_id`, OLD.`properties`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.undelete`
+AFTER INSERT ON `HG_GACHA_RECORDS`
+BEGIN
+  DELETE FROM `HG_GACHA_RECORDS_DELETED`
+  WHERE `business` = NEW.`business` AND `uid` = NEW.`uid` AND `id` = NEW.`id` AND `gacha_type` = NEW.`gacha_type`;
+END;
+
+PRAGMA USER_VERSION = 4;
+
+RELEASE start_migration_v4;
+COMMIT TRANSACTION;
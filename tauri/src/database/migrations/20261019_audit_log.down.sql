@@ -0,0 +1,9 @@
+-- Down of the audit log, the history is dropped.
+
+DROP TRIGGER IF EXISTS `HG_ACCOUNTS.audit_create`;
+DROP TRIGGER IF EXISTS `HG_ACCOUNTS.audit_update_data_folder`;
+DROP TRIGGER IF EXISTS `HG_ACCOUNTS.audit_delete`;
+
+DROP TABLE IF EXISTS `HG_AUDIT_LOG`;
+
+PRAGMA USER_VERSION = 4;
@@ -0,0 +1,44 @@
+BEGIN TRANSACTION;
+SAVEPOINT start_migration_v5;
+
+CREATE TABLE IF NOT EXISTS `HG_AUDIT_LOG` (
+  `id`         INTEGER  NOT NULL PRIMARY KEY AUTOINCREMENT,
+  `operation`  TEXT     NOT NULL,
+  `business`   INTEGER,
+  `uid`        INTEGER,
+  `counts`     TEXT,
+  `source`     TEXT,
+  `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS `HG_AUDIT_LOG.business_uid_idx` ON `HG_AUDIT_LOG` (`business`, `uid`);
+CREATE INDEX IF NOT EXISTS `HG_AUDIT_LOG.created_at_idx`   ON `HG_AUDIT_LOG` (`created_at`);
+
+-- Accounts, the frequent `properties` changes are not logged.
+-- The data folder is a full local path, not kept as the source.
+
+CREATE TRIGGER IF NOT EXISTS `HG_ACCOUNTS.audit_create`
+AFTER INSERT ON `HG_ACCOUNTS`
+BEGIN
+  INSERT INTO `HG_AUDIT_LOG` (`operation`, `business`, `uid`)
+  VALUES ('CreateAccount', NEW.`business`, NEW.`uid`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ACCOUNTS.audit_update_data_folder`
+AFTER UPDATE OF `data_folder` ON `HG_ACCOUNTS`
+WHEN OLD.`data_folder` IS NOT NEW.`data_folder`
+BEGIN
+  INSERT INTO `HG_AUDIT_LOG` (`operation`, `business`, `uid`)
+  VALUES ('UpdateAccount', NEW.`business`, NEW.`uid`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ACCOUNTS.audit_delete`
+AFTER DELETE ON `HG_ACCOUNTS`
+BEGIN
+  INSERT INTO `HG_AUDIT_LOG` (`operation`, `business`, `uid`)
+  VALUES ('DeleteAccount', OLD.`business`, OLD.`uid`);
+END;
+
+PRAGMA USER_VERSION = 5;
+
+RELEASE start_migration_v5;
+COMMIT TRANSACTION;
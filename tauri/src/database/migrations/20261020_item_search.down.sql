@@ -0,0 +1,12 @@
+-- Down of the item search.
+
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.item_names_insert`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.item_names_update`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_insert`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_delete`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_update`;
+
+DROP TABLE IF EXISTS `HG_ITEM_NAMES_FTS`;
+DROP TABLE IF EXISTS `HG_ITEM_NAMES`;
+
+PRAGMA USER_VERSION = 5;
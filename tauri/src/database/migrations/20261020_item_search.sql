@@ -0,0 +1,84 @@
+BEGIN TRANSACTION;
+SAVEPOINT start_migration_v6;
+
+-- Item search, the known names of the items and the full-text index of them.
+-- The trigram tokenizer matches the infix, also the names without the word boundaries. (e.g. CJK)
+--   * The `name` and `properties.i18n` of the records, by the triggers.
+--   * The localized names from the metadata, by the application.
+-- The names are kept after the records are deleted, they describe the items.
+-- The invalid JSON `properties` are skipped, instead of failing the writes.
+-- (They are reported by the integrity check, see: database/integrity.rs)
+
+CREATE TABLE IF NOT EXISTS `HG_ITEM_NAMES` (
+  `id`       INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT, -- Stable rowid for the index
+  `business` INTEGER NOT NULL,
+  `item_id`  TEXT    NOT NULL,
+  `lang`     TEXT    NOT NULL,
+  `name`     TEXT    NOT NULL,
+  UNIQUE (`business`, `item_id`, `lang`, `name`)
+);
+CREATE INDEX IF NOT EXISTS `HG_ITEM_NAMES.business_item_id_idx` ON `HG_ITEM_NAMES` (`business`, `item_id`);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS `HG_ITEM_NAMES_FTS` USING fts5 (
+  `name`,
+  content = 'HG_ITEM_NAMES',
+  content_rowid = 'id',
+  tokenize = 'trigram remove_diacritics 1'
+);
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_insert`
+AFTER INSERT ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`rowid`, `name`) VALUES (NEW.`id`, NEW.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_delete`
+AFTER DELETE ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`HG_ITEM_NAMES_FTS`, `rowid`, `name`) VALUES ('delete', OLD.`id`, OLD.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_update`
+AFTER UPDATE ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`HG_ITEM_NAMES_FTS`, `rowid`, `name`) VALUES ('delete', OLD.`id`, OLD.`name`);
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`rowid`, `name`) VALUES (NEW.`id`, NEW.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.item_names_insert`
+AFTER INSERT ON `HG_GACHA_RECORDS`
+BEGIN
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  VALUES (NEW.`business`, NEW.`item_id`, NEW.`lang`, NEW.`name`);
+
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  SELECT NEW.`business`, NEW.`item_id`, `i18n`.`key`, JSON_EXTRACT(`i18n`.`value`, '$.item_name')
+  FROM JSON_EACH(IIF(JSON_VALID(NEW.`properties`), NEW.`properties`, NULL), '$.i18n') AS `i18n`
+  WHERE JSON_EXTRACT(`i18n`.`value`, '$.item_name') IS NOT NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.item_names_update`
+AFTER UPDATE OF `item_id`, `lang`, `name`, `properties` ON `HG_GACHA_RECORDS`
+BEGIN
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  VALUES (NEW.`business`, NEW.`item_id`, NEW.`lang`, NEW.`name`);
+
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  SELECT NEW.`business`, NEW.`item_id`, `i18n`.`key`, JSON_EXTRACT(`i18n`.`value`, '$.item_name')
+  FROM JSON_EACH(IIF(JSON_VALID(NEW.`properties`), NEW.`properties`, NULL), '$.i18n') AS `i18n`
+  WHERE JSON_EXTRACT(`i18n`.`value`, '$.item_name') IS NOT NULL;
+END;
+
+-- Existing records
+INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+SELECT DISTINCT `business`, `item_id`, `lang`, `name` FROM `HG_GACHA_RECORDS`;
+
+INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+SELECT DISTINCT `records`.`business`, `records`.`item_id`, `i18n`.`key`, JSON_EXTRACT(`i18n`.`value`, '$.item_name')
+FROM `HG_GACHA_RECORDS` AS `records`, JSON_EACH(`records`.`properties`, '$.i18n') AS `i18n`
+WHERE JSON_VALID(`records`.`properties`) AND JSON_EXTRACT(`i18n`.`value`, '$.item_name') IS NOT NULL;
+
+PRAGMA USER_VERSION = 6;
+
+RELEASE start_migration_v6;
+COMMIT TRANSACTION;
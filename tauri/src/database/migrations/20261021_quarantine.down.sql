@@ -0,0 +1,5 @@
+-- Down of the quarantine, the quarantined rows are dropped.
+
+DROP TABLE IF EXISTS `HG_QUARANTINE`;
+
+PRAGMA USER_VERSION = 6;
@@ -0,0 +1,19 @@
+BEGIN TRANSACTION;
+SAVEPOINT start_migration_v7;
+
+-- Quarantine, the rows that cannot be decoded are moved here by the repair.
+-- (See: database/integrity.rs)
+
+CREATE TABLE IF NOT EXISTS `HG_QUARANTINE` (
+  `id`             INTEGER  NOT NULL PRIMARY KEY AUTOINCREMENT,
+  `source`         TEXT     NOT NULL, -- Table name
+  `row`            TEXT     NOT NULL, -- JSON object of the row as is
+  `reason`         TEXT     NOT NULL,
+  `quarantined_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS `HG_QUARANTINE.source_idx` ON `HG_QUARANTINE` (`source`);
+
+PRAGMA USER_VERSION = 7;
+
+RELEASE start_migration_v7;
+COMMIT TRANSACTION;
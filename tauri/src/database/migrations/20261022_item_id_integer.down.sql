@@ -0,0 +1,157 @@
+-- Down of the item id integer, the `item_id` is TEXT again.
+-- The unknown `item_id` 0 is blank again, the resolved are kept.
+
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.soft_delete`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.undelete`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.item_names_insert`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.item_names_update`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_insert`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_delete`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_update`;
+
+CREATE TABLE IF NOT EXISTS `HG_GACHA_RECORDS_OLD` (
+  `business`   INTEGER NOT NULL,
+  `uid`        INTEGER NOT NULL,
+  `id`         TEXT    NOT NULL,
+  `gacha_type` INTEGER NOT NULL,
+  `gacha_id`   INTEGER,
+  `rank_type`  INTEGER NOT NULL,
+  `count`      INTEGER NOT NULL,
+  `time`       TEXT    NOT NULL,
+  `lang`       TEXT    NOT NULL,
+  `name`       TEXT    NOT NULL,
+  `item_type`  TEXT    NOT NULL,
+  `item_id`    TEXT    NOT NULL,
+  `properties` TEXT    DEFAULT NULL,
+  PRIMARY KEY (`business`, `uid`, `id`, `gacha_type`)
+);
+
+INSERT INTO `HG_GACHA_RECORDS_OLD`
+  (`business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`)
+SELECT
+  `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, IIF(`item_id` = 0, '', CAST(`item_id` AS TEXT)), `properties`
+FROM `HG_GACHA_RECORDS`;
+
+DROP TABLE `HG_GACHA_RECORDS`;
+ALTER TABLE `HG_GACHA_RECORDS_OLD` RENAME TO `HG_GACHA_RECORDS`;
+
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.id_idx`                      ON `HG_GACHA_RECORDS` (`id`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.gacha_type_idx`              ON `HG_GACHA_RECORDS` (`gacha_type`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.rank_type_idx`               ON `HG_GACHA_RECORDS` (`rank_type`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.business_uid_idx`            ON `HG_GACHA_RECORDS` (`business`, `uid`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.business_uid_gacha_type_idx` ON `HG_GACHA_RECORDS` (`business`, `uid`, `gacha_type`);
+
+CREATE TABLE IF NOT EXISTS `HG_GACHA_RECORDS_DELETED_OLD` (
+  `business`   INTEGER  NOT NULL,
+  `uid`        INTEGER  NOT NULL,
+  `id`         TEXT     NOT NULL,
+  `gacha_type` INTEGER  NOT NULL,
+  `gacha_id`   INTEGER,
+  `rank_type`  INTEGER  NOT NULL,
+  `count`      INTEGER  NOT NULL,
+  `time`       TEXT     NOT NULL,
+  `lang`       TEXT     NOT NULL,
+  `name`       TEXT     NOT NULL,
+  `item_type`  TEXT     NOT NULL,
+  `item_id`    TEXT     NOT NULL,
+  `properties` TEXT     DEFAULT NULL,
+  `deleted_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`business`, `uid`, `id`, `gacha_type`)
+);
+
+INSERT INTO `HG_GACHA_RECORDS_DELETED_OLD`
+  (`business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`, `deleted_at`)
+SELECT
+  `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, IIF(`item_id` = 0, '', CAST(`item_id` AS TEXT)), `properties`, `deleted_at`
+FROM `HG_GACHA_RECORDS_DELETED`;
+
+DROP TABLE `HG_GACHA_RECORDS_DELETED`;
+ALTER TABLE `HG_GACHA_RECORDS_DELETED_OLD` RENAME TO `HG_GACHA_RECORDS_DELETED`;
+
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS_DELETED.business_uid_idx` ON `HG_GACHA_RECORDS_DELETED` (`business`, `uid`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS_DELETED.deleted_at_idx`   ON `HG_GACHA_RECORDS_DELETED` (`deleted_at`);
+
+CREATE TABLE IF NOT EXISTS `HG_ITEM_NAMES_OLD` (
+  `id`       INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT, -- Stable rowid for the index
+  `business` INTEGER NOT NULL,
+  `item_id`  TEXT    NOT NULL,
+  `lang`     TEXT    NOT NULL,
+  `name`     TEXT    NOT NULL,
+  UNIQUE (`business`, `item_id`, `lang`, `name`)
+);
+
+INSERT INTO `HG_ITEM_NAMES_OLD` (`id`, `business`, `item_id`, `lang`, `name`)
+SELECT `id`, `business`, CAST(`item_id` AS TEXT), `lang`, `name`
+FROM `HG_ITEM_NAMES` ORDER BY `id` ASC;
+
+DROP TABLE `HG_ITEM_NAMES`;
+ALTER TABLE `HG_ITEM_NAMES_OLD` RENAME TO `HG_ITEM_NAMES`;
+
+CREATE INDEX IF NOT EXISTS `HG_ITEM_NAMES.business_item_id_idx` ON `HG_ITEM_NAMES` (`business`, `item_id`);
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_insert`
+AFTER INSERT ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`rowid`, `name`) VALUES (NEW.`id`, NEW.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_delete`
+AFTER DELETE ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`HG_ITEM_NAMES_FTS`, `rowid`, `name`) VALUES ('delete', OLD.`id`, OLD.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_update`
+AFTER UPDATE ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`HG_ITEM_NAMES_FTS`, `rowid`, `name`) VALUES ('delete', OLD.`id`, OLD.`name`);
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`rowid`, `name`) VALUES (NEW.`id`, NEW.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.soft_delete`
+AFTER DELETE ON `HG_GACHA_RECORDS`
+BEGIN
+  INSERT OR REPLACE INTO `HG_GACHA_RECORDS_DELETED`
+    (`business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+    `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`)
+  VALUES
+    (OLD.`business`, OLD.`uid`, OLD.`id`, OLD.`gacha_type`, OLD.`gacha_id`, OLD.`rank_type`,
+    OLD.`count`, OLD.`time`, OLD.`lang`, OLD.`name`, OLD.`item_type`, OLD.`item_id`, OLD.`properties`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.undelete`
+AFTER INSERT ON `HG_GACHA_RECORDS`
+BEGIN
+  DELETE FROM `HG_GACHA_RECORDS_DELETED`
+  WHERE `business` = NEW.`business` AND `uid` = NEW.`uid` AND `id` = NEW.`id` AND `gacha_type` = NEW.`gacha_type`;
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.item_names_insert`
+AFTER INSERT ON `HG_GACHA_RECORDS`
+BEGIN
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  VALUES (NEW.`business`, NEW.`item_id`, NEW.`lang`, NEW.`name`);
+
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  SELECT NEW.`business`, NEW.`item_id`, `i18n`.`key`, JSON_EXTRACT(`i18n`.`value`, '$.item_name')
+  FROM JSON_EACH(IIF(JSON_VALID(NEW.`properties`), NEW.`properties`, NULL), '$.i18n') AS `i18n`
+  WHERE JSON_EXTRACT(`i18n`.`value`, '$.item_name') IS NOT NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.item_names_update`
+AFTER UPDATE OF `item_id`, `lang`, `name`, `properties` ON `HG_GACHA_RECORDS`
+BEGIN
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  VALUES (NEW.`business`, NEW.`item_id`, NEW.`lang`, NEW.`name`);
+
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  SELECT NEW.`business`, NEW.`item_id`, `i18n`.`key`, JSON_EXTRACT(`i18n`.`value`, '$.item_name')
+  FROM JSON_EACH(IIF(JSON_VALID(NEW.`properties`), NEW.`properties`, NULL), '$.i18n') AS `i18n`
+  WHERE JSON_EXTRACT(`i18n`.`value`, '$.item_name') IS NOT NULL;
+END;
+
+PRAGMA USER_VERSION = 7;
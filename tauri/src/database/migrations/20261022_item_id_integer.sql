@@ -0,0 +1,200 @@
+BEGIN TRANSACTION;
+SAVEPOINT start_migration_v8;
+
+-- Store the `item_id` as INTEGER, it was TEXT.
+--   * The blank `item_id` (Genshin Impact legacy records) is resolved by the known
+--     item names of the same business and lang, only if it is unique.
+--   * The unresolved and the non-numeric are kept with the unknown `item_id` 0.
+--     They are resolved later against the metadata.
+--     (See: schemas/item_name.rs `resolve_unknown_item_ids`)
+
+UPDATE `HG_GACHA_RECORDS` AS `records` SET `item_id` = COALESCE((
+  SELECT IIF(COUNT(DISTINCT `names`.`item_id`) = 1, MIN(`names`.`item_id`), NULL)
+  FROM `HG_ITEM_NAMES` AS `names`
+  WHERE `names`.`business` = `records`.`business`
+    AND `names`.`lang` = `records`.`lang`
+    AND `names`.`name` = `records`.`name`
+    AND `names`.`item_id` <> '' AND `names`.`item_id` NOT GLOB '*[^0-9]*'
+), `item_id`)
+WHERE `item_id` = '';
+
+UPDATE `HG_GACHA_RECORDS_DELETED` AS `records` SET `item_id` = COALESCE((
+  SELECT IIF(COUNT(DISTINCT `names`.`item_id`) = 1, MIN(`names`.`item_id`), NULL)
+  FROM `HG_ITEM_NAMES` AS `names`
+  WHERE `names`.`business` = `records`.`business`
+    AND `names`.`lang` = `records`.`lang`
+    AND `names`.`name` = `records`.`name`
+    AND `names`.`item_id` <> '' AND `names`.`item_id` NOT GLOB '*[^0-9]*'
+), `item_id`)
+WHERE `item_id` = '';
+
+-- Rebuild the tables, the triggers and indexes are dropped with them.
+
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.soft_delete`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.undelete`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.item_names_insert`;
+DROP TRIGGER IF EXISTS `HG_GACHA_RECORDS.item_names_update`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_insert`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_delete`;
+DROP TRIGGER IF EXISTS `HG_ITEM_NAMES.fts_update`;
+
+CREATE TABLE IF NOT EXISTS `HG_GACHA_RECORDS_NEW` (
+  `business`   INTEGER NOT NULL,
+  `uid`        INTEGER NOT NULL,
+  `id`         TEXT    NOT NULL,
+  `gacha_type` INTEGER NOT NULL,
+  `gacha_id`   INTEGER,
+  `rank_type`  INTEGER NOT NULL,
+  `count`      INTEGER NOT NULL,
+  `time`       TEXT    NOT NULL,
+  `lang`       TEXT    NOT NULL,
+  `name`       TEXT    NOT NULL,
+  `item_type`  TEXT    NOT NULL,
+  `item_id`    INTEGER NOT NULL,
+  `properties` TEXT    DEFAULT NULL,
+  PRIMARY KEY (`business`, `uid`, `id`, `gacha_type`)
+);
+
+INSERT INTO `HG_GACHA_RECORDS_NEW`
+  (`business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`)
+SELECT
+  `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, IIF(`item_id` <> '' AND `item_id` NOT GLOB '*[^0-9]*', CAST(`item_id` AS INTEGER), 0), `properties`
+FROM `HG_GACHA_RECORDS`;
+
+DROP TABLE `HG_GACHA_RECORDS`;
+ALTER TABLE `HG_GACHA_RECORDS_NEW` RENAME TO `HG_GACHA_RECORDS`;
+
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.id_idx`                      ON `HG_GACHA_RECORDS` (`id`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.gacha_type_idx`              ON `HG_GACHA_RECORDS` (`gacha_type`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.rank_type_idx`               ON `HG_GACHA_RECORDS` (`rank_type`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.business_uid_idx`            ON `HG_GACHA_RECORDS` (`business`, `uid`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.business_uid_gacha_type_idx` ON `HG_GACHA_RECORDS` (`business`, `uid`, `gacha_type`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS.business_item_id_idx`        ON `HG_GACHA_RECORDS` (`business`, `item_id`);
+
+CREATE TABLE IF NOT EXISTS `HG_GACHA_RECORDS_DELETED_NEW` (
+  `business`   INTEGER  NOT NULL,
+  `uid`        INTEGER  NOT NULL,
+  `id`         TEXT     NOT NULL,
+  `gacha_type` INTEGER  NOT NULL,
+  `gacha_id`   INTEGER,
+  `rank_type`  INTEGER  NOT NULL,
+  `count`      INTEGER  NOT NULL,
+  `time`       TEXT     NOT NULL,
+  `lang`       TEXT     NOT NULL,
+  `name`       TEXT     NOT NULL,
+  `item_type`  TEXT     NOT NULL,
+  `item_id`    INTEGER  NOT NULL,
+  `properties` TEXT     DEFAULT NULL,
+  `deleted_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`business`, `uid`, `id`, `gacha_type`)
+);
+
+INSERT INTO `HG_GACHA_RECORDS_DELETED_NEW`
+  (`business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`, `deleted_at`)
+SELECT
+  `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+  `count`, `time`, `lang`, `name`, `item_type`, IIF(`item_id` <> '' AND `item_id` NOT GLOB '*[^0-9]*', CAST(`item_id` AS INTEGER), 0), `properties`, `deleted_at`
+FROM `HG_GACHA_RECORDS_DELETED`;
+
+DROP TABLE `HG_GACHA_RECORDS_DELETED`;
+ALTER TABLE `HG_GACHA_RECORDS_DELETED_NEW` RENAME TO `HG_GACHA_RECORDS_DELETED`;
+
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS_DELETED.business_uid_idx` ON `HG_GACHA_RECORDS_DELETED` (`business`, `uid`);
+CREATE INDEX IF NOT EXISTS `HG_GACHA_RECORDS_DELETED.deleted_at_idx`   ON `HG_GACHA_RECORDS_DELETED` (`deleted_at`);
+
+-- The names of the blank are not the names of an item, the rowid is kept for the index.
+CREATE TABLE IF NOT EXISTS `HG_ITEM_NAMES_NEW` (
+  `id`       INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT, -- Stable rowid for the index
+  `business` INTEGER NOT NULL,
+  `item_id`  INTEGER NOT NULL,
+  `lang`     TEXT    NOT NULL,
+  `name`     TEXT    NOT NULL,
+  UNIQUE (`business`, `item_id`, `lang`, `name`)
+);
+
+INSERT OR IGNORE INTO `HG_ITEM_NAMES_NEW` (`id`, `business`, `item_id`, `lang`, `name`)
+SELECT `id`, `business`, CAST(`item_id` AS INTEGER), `lang`, `name`
+FROM `HG_ITEM_NAMES` WHERE `item_id` <> '' AND `item_id` NOT GLOB '*[^0-9]*'
+ORDER BY `id` ASC;
+
+DROP TABLE `HG_ITEM_NAMES`;
+ALTER TABLE `HG_ITEM_NAMES_NEW` RENAME TO `HG_ITEM_NAMES`;
+
+CREATE INDEX IF NOT EXISTS `HG_ITEM_NAMES.business_item_id_idx` ON `HG_ITEM_NAMES` (`business`, `item_id`);
+
+INSERT INTO `HG_ITEM_NAMES_FTS` (`HG_ITEM_NAMES_FTS`) VALUES ('rebuild');
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_insert`
+AFTER INSERT ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`rowid`, `name`) VALUES (NEW.`id`, NEW.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_delete`
+AFTER DELETE ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`HG_ITEM_NAMES_FTS`, `rowid`, `name`) VALUES ('delete', OLD.`id`, OLD.`name`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_ITEM_NAMES.fts_update`
+AFTER UPDATE ON `HG_ITEM_NAMES`
+BEGIN
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`HG_ITEM_NAMES_FTS`, `rowid`, `name`) VALUES ('delete', OLD.`id`, OLD.`name`);
+  INSERT INTO `HG_ITEM_NAMES_FTS` (`rowid`, `name`) VALUES (NEW.`id`, NEW.`name`);
+END;
+
+-- The triggers of the records, same as before.
+-- (See: 20261018_recycle_bin.sql, 20261020_item_search.sql)
+-- Except the unknown `item_id` 0, it is not the id of an item and has no names.
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.soft_delete`
+AFTER DELETE ON `HG_GACHA_RECORDS`
+BEGIN
+  INSERT OR REPLACE INTO `HG_GACHA_RECORDS_DELETED`
+    (`business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+    `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`)
+  VALUES
+    (OLD.`business`, OLD.`uid`, OLD.`id`, OLD.`gacha_type`, OLD.`gacha_id`, OLD.`rank_type`,
+    OLD.`count`, OLD.`time`, OLD.`lang`, OLD.`name`, OLD.`item_type`, OLD.`item_id`, OLD.`properties`);
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.undelete`
+AFTER INSERT ON `HG_GACHA_RECORDS`
+BEGIN
+  DELETE FROM `HG_GACHA_RECORDS_DELETED`
+  WHERE `business` = NEW.`business` AND `uid` = NEW.`uid` AND `id` = NEW.`id` AND `gacha_type` = NEW.`gacha_type`;
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.item_names_insert`
+AFTER INSERT ON `HG_GACHA_RECORDS`
+WHEN NEW.`item_id` <> 0
+BEGIN
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  VALUES (NEW.`business`, NEW.`item_id`, NEW.`lang`, NEW.`name`);
+
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  SELECT NEW.`business`, NEW.`item_id`, `i18n`.`key`, JSON_EXTRACT(`i18n`.`value`, '$.item_name')
+  FROM JSON_EACH(IIF(JSON_VALID(NEW.`properties`), NEW.`properties`, NULL), '$.i18n') AS `i18n`
+  WHERE JSON_EXTRACT(`i18n`.`value`, '$.item_name') IS NOT NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS `HG_GACHA_RECORDS.item_names_update`
+AFTER UPDATE OF `item_id`, `lang`, `name`, `properties` ON `HG_GACHA_RECORDS`
+WHEN NEW.`item_id` <> 0
+BEGIN
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  VALUES (NEW.`business`, NEW.`item_id`, NEW.`lang`, NEW.`name`);
+
+  INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`)
+  SELECT NEW.`business`, NEW.`item_id`, `i18n`.`key`, JSON_EXTRACT(`i18n`.`value`, '$.item_name')
+  FROM JSON_EACH(IIF(JSON_VALID(NEW.`properties`), NEW.`properties`, NULL), '$.i18n') AS `i18n`
+  WHERE JSON_EXTRACT(`i18n`.`value`, '$.item_name') IS NOT NULL;
+END;
+
+PRAGMA USER_VERSION = 8;
+
+RELEASE start_migration_v8;
+COMMIT TRANSACTION;
@@ -1,17 +1,49 @@
+use std::collections::HashMap;
+
+use snafu::{ResultExt, Snafu};
 use sqlx::{Executor, Row};
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 
+use crate::business::metadata::sha1sum;
+use crate::database::backup::DatabaseBackupReason;
 use crate::database::{Database, DatabaseError};
+use crate::error::ErrorDetails;
+
+// Migrations.
+//
+// Each migration is the `up` script and the optional `down` script:
+//   * The `up` script is in its own transaction and sets the `PRAGMA USER_VERSION`.
+//   * The `down` script is without the transaction, it is wrapped by the runner.
+//     Sets the `PRAGMA USER_VERSION` back to the previous version.
+//
+// The applied migrations are recorded in the `HG_MIGRATIONS` table, with the checksum
+// of the `up` script and the `down` script itself. So that an older application
+// can roll back the migrations that it does not know. (See: `downgrade`)
+//
+// NOTE: The applied migrations must not be modified, the checksum is verified.
 
 macro_rules! define_migrations {
-  ($($name:ident: $sql:literal,)*) => {
+  (@down $down:literal) => {
+    Some(include_str!($down))
+  };
+
+  (@down) => {
+    None
+  };
+
+  ($($name:ident: $sql:literal $(=> $down:literal)?,)*) => {
     struct Migration {
       name: &'static str,
       sql: &'static str,
+      down: Option<&'static str>,
     }
 
     impl Migration {
-      $(const $name: Self = Self { name: stringify!($name), sql: include_str!($sql) };)*
+      $(const $name: Self = Self {
+        name: stringify!($name),
+        sql: include_str!($sql),
+        down: define_migrations!(@down $($down)?),
+      };)*
 
       const fn values() -> &'static [Self] {
         &[$(Self::$name,)*]
@@ -24,30 +56,664 @@ define_migrations! {
   INITIALIZE              : "20240725_initialize.sql",
   GACHA_RECORDS_PK        : "20250719_gacha_records_pk.sql",
   GACHA_RECORDS_PROPERTIES: "20251102_gacha_records_properties.sql",
+  RECYCLE_BIN             : "20261018_recycle_bin.sql"         => "20261018_recycle_bin.down.sql",
+  AUDIT_LOG               : "20261019_audit_log.sql"           => "20261019_audit_log.down.sql",
+  ITEM_SEARCH             : "20261020_item_search.sql"         => "20261020_item_search.down.sql",
+  QUARANTINE              : "20261021_quarantine.sql"          => "20261021_quarantine.down.sql",
+  ITEM_ID_INTEGER         : "20261022_item_id_integer.sql"     => "20261022_item_id_integer.down.sql",
+}
+
+impl Migration {
+  /// The `up` script without its own outer transaction,
+  /// to be run in the same transaction with the record of it.
+  fn sql_without_transaction(&self) -> &'static str {
+    let sql = self.sql.trim();
+    sql
+      .strip_prefix("BEGIN TRANSACTION;")
+      .and_then(|sql| sql.strip_suffix("COMMIT TRANSACTION;"))
+      .unwrap_or(sql)
+  }
+
+  /// The SHA-1 checksum of the `up` script.
+  /// Line endings are normalized, the checkout may convert them. (See: .gitattributes)
+  fn checksum(&self) -> String {
+    sha1sum(self.sql.replace("\r\n", "\n").as_bytes())
+  }
+}
+
+/// The expected version of the database. (`PRAGMA USER_VERSION`)
+#[inline]
+pub const fn version() -> u32 {
+  Migration::values().len() as u32
 }
 
+#[derive(Debug, Snafu)]
+pub enum MigrationError {
+  #[snafu(display("{source}"))]
+  Database { source: DatabaseError },
+
+  #[snafu(display(
+    "Migration v{version} ({name}) has been modified, checksum: {actual}, expected: {expected}"
+  ))]
+  ChecksumMismatch {
+    version: u32,
+    name: String,
+    expected: String,
+    actual: String,
+  },
+
+  #[snafu(display("Migration v{version} ({name}) has been applied, but is not recorded"))]
+  Unrecorded { version: u32, name: &'static str },
+
+  #[snafu(display("Migration v{version} ({name}) has been recorded, but is not applied"))]
+  PartiallyApplied { version: u32, name: String },
+}
+
+impl From<sqlx::Error> for MigrationError {
+  fn from(value: sqlx::Error) -> Self {
+    Self::Database {
+      source: DatabaseError::from(value),
+    }
+  }
+}
+
+impl ErrorDetails for MigrationError {
+  fn name(&self) -> &'static str {
+    match self {
+      Self::Database { source } => source.name(),
+      _ => stringify!(MigrationError),
+    }
+  }
+
+  fn details(&self) -> Option<serde_json::Value> {
+    use serde_json::json;
+
+    match self {
+      Self::Database { source } => source.details(),
+      Self::ChecksumMismatch {
+        version,
+        name,
+        expected,
+        actual,
+      } => Some(json!({
+        "kind": stringify!(ChecksumMismatch),
+        "version": version,
+        "name": name,
+        "expected": expected,
+        "actual": actual,
+      })),
+      Self::Unrecorded { version, name } => Some(json!({
+        "kind": stringify!(Unrecorded),
+        "version": version,
+        "name": name,
+      })),
+      Self::PartiallyApplied { version, name } => Some(json!({
+        "kind": stringify!(PartiallyApplied),
+        "version": version,
+        "name": name,
+      })),
+    }
+  }
+}
+
+const CREATE_MIGRATIONS_TABLE: &str = "CREATE TABLE IF NOT EXISTS `HG_MIGRATIONS` (
+  `version`    INTEGER  NOT NULL PRIMARY KEY,
+  `name`       TEXT     NOT NULL,
+  `checksum`   TEXT     NOT NULL,
+  `down`       TEXT,
+  `applied_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);";
+
+const INSERT_MIGRATION: &str = "INSERT OR REPLACE INTO `HG_MIGRATIONS` (`version`, `name`, `checksum`, `down`) VALUES (?, ?, ?, ?);";
+
 #[tracing::instrument(skip(database))]
-pub async fn apply_migrations(database: &Database) -> Result<(), DatabaseError> {
-  info!("Applying database migrations...");
+pub async fn apply_migrations(database: &Database) -> Result<(), MigrationError> {
+  apply_migrations_with(database, Migration::values()).await
+}
+
+async fn user_version(database: &Database) -> Result<u32, MigrationError> {
+  Ok(
+    database
+      .inner
+      .fetch_one("PRAGMA USER_VERSION;")
+      .await?
+      .get(0),
+  )
+}
 
-  let migrations = Migration::values();
-  let expected_version = migrations.len();
+async fn apply_migrations_with(
+  database: &Database,
+  migrations: &[Migration],
+) -> Result<(), MigrationError> {
+  info!("Applying database migrations...");
 
-  let version: u32 = database
-    .inner
-    .fetch_one("PRAGMA USER_VERSION;")
-    .await?
-    .get(0);
+  let expected_version = migrations.len() as u32;
+  let version = user_version(database).await?;
 
   info!(
     "Current version: {}, expected version: {}",
     version, expected_version
   );
 
-  for migration in migrations.iter().skip(version as _) {
+  let recorded: bool = sqlx::query_scalar(
+    "SELECT COUNT(*) > 0 FROM `sqlite_master` WHERE `type` = 'table' AND `name` = 'HG_MIGRATIONS';",
+  )
+  .fetch_one(&database.inner)
+  .await?;
+
+  database
+    .execute(CREATE_MIGRATIONS_TABLE)
+    .await
+    .context(DatabaseSnafu)?;
+
+  // Applied before the migrations are recorded
+  if !recorded && version > 0 {
+    info!("Recording the applied migrations...");
+    for (index, migration) in migrations.iter().enumerate().take(version as _) {
+      record_migration(&database.inner, index as u32 + 1, migration).await?;
+    }
+  }
+
+  verify_migrations(database, migrations, version).await?;
+
+  if version > expected_version {
+    return downgrade(database, version, expected_version).await;
+  }
+
+  // Back up the existing database before migrating, failures are not fatal
+  if version > 0
+    && version < expected_version
+    && let Err(e) = database.backup(DatabaseBackupReason::Migration).await
+  {
+    error!(
+      message = "Failed to back up the database before migrating",
+      ?e
+    );
+  }
+
+  // The script and the record of it are committed together
+  for (index, migration) in migrations.iter().enumerate().skip(version as _) {
     debug!(message = "Applying migration...", ?migration.name);
-    database.execute(migration.sql).await?;
+    let mut txn = database.inner.begin().await?;
+    (&mut *txn)
+      .execute(migration.sql_without_transaction())
+      .await?;
+    record_migration(&mut *txn, index as u32 + 1, migration).await?;
+    txn.commit().await?;
+  }
+
+  Ok(())
+}
+
+async fn record_migration<'c, E>(
+  executor: E,
+  version: u32,
+  migration: &Migration,
+) -> Result<(), MigrationError>
+where
+  E: Executor<'c, Database = sqlx::Sqlite>,
+{
+  sqlx::query(INSERT_MIGRATION)
+    .bind(version)
+    .bind(migration.name)
+    .bind(migration.checksum())
+    .bind(migration.down)
+    .execute(executor)
+    .await?;
+
+  Ok(())
+}
+
+// Verify the recorded migrations match the applied version and the known scripts.
+#[tracing::instrument(skip(database, migrations))]
+async fn verify_migrations(
+  database: &Database,
+  migrations: &[Migration],
+  version: u32,
+) -> Result<(), MigrationError> {
+  let mut recorded: HashMap<u32, (String, String)> = sqlx::query_as::<_, (u32, String, String)>(
+    "SELECT `version`, `name`, `checksum` FROM `HG_MIGRATIONS`;",
+  )
+  .fetch_all(&database.inner)
+  .await?
+  .into_iter()
+  .map(|(version, name, checksum)| (version, (name, checksum)))
+  .collect();
+
+  // The newer migrations are unknown, only the recorded ones can be verified
+  for (index, migration) in migrations.iter().enumerate().take(version as _) {
+    let version = index as u32 + 1;
+    let Some((_, checksum)) = recorded.remove(&version) else {
+      return UnrecordedSnafu {
+        version,
+        name: migration.name,
+      }
+      .fail();
+    };
+
+    let expected = migration.checksum();
+    if checksum != expected {
+      return ChecksumMismatchSnafu {
+        version,
+        name: migration.name,
+        expected,
+        actual: checksum,
+      }
+      .fail();
+    }
+  }
+
+  // Recorded, but the `up` script did not complete
+  if let Some((version, (name, _))) = recorded
+    .into_iter()
+    .filter(|(recorded, _)| *recorded > version)
+    .min_by_key(|(recorded, _)| *recorded)
+  {
+    return PartiallyAppliedSnafu { version, name }.fail();
+  }
+
+  Ok(())
+}
+
+// Roll back the newer migrations with the recorded `down` scripts.
+// When any of them is missing, the database is kept as is.
+#[tracing::instrument(skip(database))]
+async fn downgrade(
+  database: &Database,
+  version: u32,
+  expected_version: u32,
+) -> Result<(), MigrationError> {
+  let downs: Vec<(u32, String, Option<String>)> = sqlx::query_as(
+    "SELECT `version`, `name`, `down` FROM `HG_MIGRATIONS` WHERE `version` > ? ORDER BY `version` DESC;",
+  )
+  .bind(expected_version)
+  .fetch_all(&database.inner)
+  .await?;
+
+  if downs.len() != (version - expected_version) as usize
+    || downs.iter().any(|(_, _, down)| down.is_none())
+  {
+    warn!(
+      message = "The database is newer and cannot be rolled back, keep it as is",
+      version, expected_version
+    );
+    return Ok(());
+  }
+
+  // Back up before rolling back, failures are not fatal
+  if let Err(e) = database.backup(DatabaseBackupReason::Migration).await {
+    error!(
+      message = "Failed to back up the database before rolling back",
+      ?e
+    );
+  }
+
+  for (version, name, down) in downs {
+    info!(message = "Rolling back migration...", version, ?name);
+
+    let mut txn = database.inner.begin().await?;
+    (&mut *txn)
+      .execute(down.as_deref().unwrap_or_default()) // SAFETY: Checked
+      .await?;
+    sqlx::query("DELETE FROM `HG_MIGRATIONS` WHERE `version` = ?;")
+      .bind(version)
+      .execute(&mut *txn)
+      .await?;
+    txn.commit().await?;
   }
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  async fn migrations_of(database: &Database) -> Vec<(u32, String)> {
+    sqlx::query_as("SELECT `version`, `name` FROM `HG_MIGRATIONS` ORDER BY `version` ASC;")
+      .fetch_all(&database.inner)
+      .await
+      .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_migrations() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_migrations.db"))
+      .await
+      .unwrap();
+
+    // Run in the transaction with the record
+    let migrations = Migration::values();
+    for migration in migrations {
+      assert_ne!(migration.sql_without_transaction(), migration.sql.trim());
+    }
+
+    database.apply_migrations().await.unwrap();
+    assert_eq!(user_version(&database).await.unwrap(), version());
+    assert_eq!(
+      migrations_of(&database).await,
+      migrations
+        .iter()
+        .enumerate()
+        .map(|(index, migration)| (index as u32 + 1, migration.name.to_owned()))
+        .collect::<Vec<_>>()
+    );
+
+    // Applied before the migrations are recorded
+    database
+      .execute("DROP TABLE `HG_MIGRATIONS`;")
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+    assert_eq!(migrations_of(&database).await.len(), migrations.len());
+
+    // Modified
+    database
+      .execute("UPDATE `HG_MIGRATIONS` SET `checksum` = 'modified' WHERE `version` = 2;")
+      .await
+      .unwrap();
+    let err = database.apply_migrations().await.unwrap_err();
+    assert!(matches!(
+      err,
+      MigrationError::ChecksumMismatch { version: 2, .. }
+    ));
+    assert_eq!(err.name(), "MigrationError");
+    assert_eq!(
+      err.details().unwrap()["kind"],
+      serde_json::json!("ChecksumMismatch")
+    );
+    record_migration(&database.inner, 2, &migrations[1])
+      .await
+      .unwrap();
+
+    // Applied, but not recorded
+    database
+      .execute("DELETE FROM `HG_MIGRATIONS` WHERE `version` = 3;")
+      .await
+      .unwrap();
+    assert!(matches!(
+      database.apply_migrations().await,
+      Err(MigrationError::Unrecorded { version: 3, .. })
+    ));
+    record_migration(&database.inner, 3, &migrations[2])
+      .await
+      .unwrap();
+
+    // Recorded, but not applied
+    database.execute("PRAGMA USER_VERSION = 5;").await.unwrap();
+    assert!(matches!(
+      database.apply_migrations().await,
+      Err(MigrationError::PartiallyApplied { version: 6, .. })
+    ));
+    database
+      .execute(format!("PRAGMA USER_VERSION = {};", version()))
+      .await
+      .unwrap();
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_migrations_failed_read_only() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut database = Database::new_with(temp_dir.path().join("test_migrations_read_only.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    database
+      .execute("UPDATE `HG_MIGRATIONS` SET `checksum` = 'modified' WHERE `version` = 1;")
+      .await
+      .unwrap();
+    assert!(database.apply_migrations().await.is_err());
+
+    // Writes are blocked, the backups are not
+    database.reconnect_read_only().await.unwrap();
+    assert!(
+      database
+        .execute("DELETE FROM `HG_MIGRATIONS`;")
+        .await
+        .is_err()
+    );
+    assert_eq!(
+      migrations_of(&database).await.len(),
+      Migration::values().len()
+    );
+
+    let backup = database
+      .backup(DatabaseBackupReason::Migration)
+      .await
+      .unwrap();
+    assert_eq!(backup.version, version());
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_migrations_downgrade() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_migrations_downgrade.db"))
+      .await
+      .unwrap();
+
+    let migrations = Migration::values();
+    database.apply_migrations().await.unwrap();
+
+    let tables = || async {
+      sqlx::query_scalar::<_, String>(
+        "SELECT `name` FROM `sqlite_master` WHERE `name` NOT LIKE 'sqlite_%' ORDER BY `name`;",
+      )
+      .fetch_all(&database.inner)
+      .await
+      .unwrap()
+    };
+    let newest = tables().await;
+
+    // The older application, without the migrations after `GACHA_RECORDS_PROPERTIES`
+    apply_migrations_with(&database, &migrations[..3])
+      .await
+      .unwrap();
+    assert_eq!(user_version(&database).await.unwrap(), 3);
+    assert_eq!(migrations_of(&database).await.len(), 3);
+    assert!(!tables().await.contains(&"HG_AUDIT_LOG".to_owned()));
+
+    // And the newer again
+    database.apply_migrations().await.unwrap();
+    assert_eq!(user_version(&database).await.unwrap(), version());
+    assert_eq!(tables().await, newest);
+
+    // Without the down script, kept as is
+    database
+      .execute("UPDATE `HG_MIGRATIONS` SET `down` = NULL WHERE `version` = 5;")
+      .await
+      .unwrap();
+    apply_migrations_with(&database, &migrations[..3])
+      .await
+      .unwrap();
+    assert_eq!(user_version(&database).await.unwrap(), version());
+    assert_eq!(tables().await, newest);
+
+    // Backed up before rolling back
+    let backups = database.backups().unwrap();
+    assert!(
+      backups
+        .iter()
+        .any(|backup| backup.reason == DatabaseBackupReason::Migration)
+    );
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_migrations_item_id_integer() {
+    use crate::business::metadata::Metadata;
+    use crate::database::schemas::{
+      AccountBusiness, GachaRecordQuestioner, RecycleBinQuestioner, resolve_unknown_item_ids,
+    };
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_migrations_item_id_integer.db"))
+      .await
+      .unwrap();
+
+    // The fixture of the shipped schema, before the `RECYCLE_BIN`
+    let migrations = Migration::values();
+    apply_migrations_with(&database, &migrations[..3])
+      .await
+      .unwrap();
+    assert_eq!(user_version(&database).await.unwrap(), 3);
+
+    let records = [
+      ("1000000000000000000", "Cool Steel", 3, "11301"),
+      ("1000000000000000001", "Cool Steel", 3, ""), // Resolved by the name
+      ("1000000000000000002", "Skyward Harp", 5, ""), // Resolved by the metadata
+      ("1000000000000000003", "Invalid", 3, "invalid"), // Unresolved
+      ("1000000000000000004", "Harbinger of Dawn", 3, "11302"), // Deleted
+      ("1000000000000000005", "Skyward Harp", 5, ""), // Deleted, resolved by the metadata
+    ];
+
+    for (id, name, rank_type, item_id) in records {
+      sqlx::query(
+        "INSERT INTO `HG_GACHA_RECORDS` (
+          `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+          `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`
+        ) VALUES (0, 100000000, ?, 301, NULL, ?, 1, '2023-01-01 00:00:00+08:00', 'en-us', ?, 'Weapon', ?, NULL);",
+      )
+      .bind(id)
+      .bind(rank_type)
+      .bind(name)
+      .bind(item_id)
+      .execute(&database.inner)
+      .await
+      .unwrap();
+    }
+
+    // Deleted into the recycle bin
+    apply_migrations_with(&database, &migrations[..4])
+      .await
+      .unwrap();
+    database
+      .execute(
+        "DELETE FROM `HG_GACHA_RECORDS` WHERE `id` IN ('1000000000000000004', '1000000000000000005');",
+      )
+      .await
+      .unwrap();
+
+    database.apply_migrations().await.unwrap();
+    assert_eq!(user_version(&database).await.unwrap(), version());
+
+    let item_ids = || async {
+      sqlx::query_as::<_, (String, String, u32)>(
+        "SELECT `id`, TYPEOF(`item_id`), `item_id` FROM `HG_GACHA_RECORDS`
+          UNION ALL SELECT `id`, TYPEOF(`item_id`), `item_id` FROM `HG_GACHA_RECORDS_DELETED`
+          ORDER BY `id` ASC;",
+      )
+      .fetch_all(&database.inner)
+      .await
+      .unwrap()
+    };
+
+    // Visible with the unknown item id, not quarantined
+    assert_eq!(
+      item_ids().await,
+      vec![
+        ("1000000000000000000".into(), "integer".into(), 11301),
+        ("1000000000000000001".into(), "integer".into(), 11301),
+        ("1000000000000000002".into(), "integer".into(), 0),
+        ("1000000000000000003".into(), "integer".into(), 0),
+        ("1000000000000000004".into(), "integer".into(), 11302),
+        ("1000000000000000005".into(), "integer".into(), 0),
+      ]
+    );
+
+    let quarantined: u32 = sqlx::query_scalar("SELECT COUNT(*) FROM `HG_QUARANTINE`;")
+      .fetch_one(&database.inner)
+      .await
+      .unwrap();
+    assert_eq!(quarantined, 0);
+
+    let unknown_names: u32 =
+      sqlx::query_scalar("SELECT COUNT(*) FROM `HG_ITEM_NAMES` WHERE `item_id` = 0;")
+        .fetch_one(&database.inner)
+        .await
+        .unwrap();
+    assert_eq!(unknown_names, 0);
+
+    // Resolved by the metadata
+    let metadata = Metadata::embedded();
+    let skyward_harp = metadata
+      .locale(AccountBusiness::GenshinImpact as _, "en-us")
+      .unwrap()
+      .entry_from_name_first("Skyward Harp")
+      .unwrap()
+      .item_id;
+
+    assert_eq!(
+      resolve_unknown_item_ids(&database, metadata).await.unwrap(),
+      2
+    );
+
+    let business = AccountBusiness::GenshinImpact;
+    assert_eq!(
+      database
+        .find_gacha_records(business, 100_000_000)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|record| (record.id, record.item_id))
+        .collect::<Vec<_>>(),
+      vec![
+        ("1000000000000000000".into(), 11301),
+        ("1000000000000000001".into(), 11301),
+        ("1000000000000000002".into(), skyward_harp),
+        ("1000000000000000003".into(), 0),
+      ]
+    );
+    assert_eq!(
+      database
+        .find_deleted_gacha_records(business, 100_000_000)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|deleted| (deleted.record.id, deleted.record.item_id))
+        .collect::<Vec<_>>(),
+      vec![
+        ("1000000000000000004".into(), 11302),
+        ("1000000000000000005".into(), skyward_harp),
+      ]
+    );
+
+    // Unresolved, kept unknown
+    assert_eq!(
+      resolve_unknown_item_ids(&database, metadata).await.unwrap(),
+      0
+    );
+
+    let indexes: Vec<String> = sqlx::query_scalar(
+      "SELECT `name` FROM `sqlite_master` WHERE `type` = 'index' AND `tbl_name` = 'HG_GACHA_RECORDS';",
+    )
+    .fetch_all(&database.inner)
+    .await
+    .unwrap();
+    assert!(indexes.contains(&"HG_GACHA_RECORDS.business_item_id_idx".to_owned()));
+
+    // Rolled back, the item id is TEXT again
+    apply_migrations_with(&database, &migrations[..7])
+      .await
+      .unwrap();
+    let item_id: String = sqlx::query_scalar(
+      "SELECT TYPEOF(`item_id`) FROM `HG_GACHA_RECORDS` WHERE `id` = '1000000000000000000';",
+    )
+    .fetch_one(&database.inner)
+    .await
+    .unwrap();
+    assert_eq!(item_id, "text");
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+}
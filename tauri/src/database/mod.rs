@@ -6,11 +6,13 @@ use std::{env, fmt};
 use snafu::{ResultExt, Snafu};
 use sqlx::sqlite::{SqliteConnectOptions, SqliteQueryResult};
 use sqlx::{Executor, SqlitePool};
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 
 use crate::constants;
 use crate::error::ErrorDetails;
 
+pub mod backup;
+pub mod integrity;
 pub mod legacy;
 pub mod migrations;
 pub mod schemas;
@@ -44,6 +46,7 @@ impl ErrorDetails for DatabaseError {
 pub struct Database {
   pub(crate) inner: SqlitePool,
   pub filename: PathBuf,
+  pub backup_folder: PathBuf,
 }
 
 impl Deref for Database {
@@ -65,34 +68,81 @@ impl Database {
       constants::EXE_WORKING_DIR.join(constants::DATABASE)
     };
 
-    Self::new_with(filename).await
+    // Backup storage folder
+    //   In debug mode  : Next to the database
+    //   In release mode: App local data folder
+    if cfg!(debug_assertions) {
+      Self::new_with(filename).await
+    } else {
+      let backup_folder = constants::APP_LOCAL_DATA_DIR.join(constants::DATABASE_BACKUPS);
+      Self::new_with_backup_folder(filename, backup_folder).await
+    }
   }
 
-  #[tracing::instrument]
+  #[inline]
   pub async fn new_with<P: AsRef<Path> + fmt::Debug>(filename: P) -> Result<Self, DatabaseError> {
+    let backup_folder = Self::default_backup_folder(filename.as_ref());
+    Self::new_with_backup_folder(filename, backup_folder).await
+  }
+
+  fn default_backup_folder(filename: &Path) -> PathBuf {
+    filename
+      .parent()
+      .unwrap_or(Path::new("."))
+      .join(constants::DATABASE_BACKUPS)
+  }
+
+  #[tracing::instrument]
+  pub async fn new_with_backup_folder<P: AsRef<Path> + fmt::Debug>(
+    filename: P,
+    backup_folder: PathBuf,
+  ) -> Result<Self, DatabaseError> {
+    // Not fatal, the current database is still usable
+    if let Err(e) = Self::apply_staged_restore(filename.as_ref()) {
+      error!(message = "Failed to apply the staged database restore", ?e);
+    }
+
     info!("Connecting to database...");
 
-    let sqlite = SqlitePool::connect_with(
+    let sqlite = Self::connect(filename.as_ref(), false).await?;
+
+    Ok(Self {
+      inner: sqlite,
+      filename: filename.as_ref().to_path_buf(),
+      backup_folder,
+    })
+  }
+
+  async fn connect(filename: &Path, read_only: bool) -> Result<SqlitePool, DatabaseError> {
+    SqlitePool::connect_with(
       SqliteConnectOptions::new()
-        .filename(&filename)
-        .create_if_missing(true)
-        .read_only(false)
+        .filename(filename)
+        .create_if_missing(!read_only)
+        .read_only(read_only)
         .immutable(false)
         .shared_cache(false),
     )
     .await
-    .context(DatabaseSnafu)?;
+    .context(DatabaseSnafu)
+  }
 
-    Ok(Self {
-      inner: sqlite,
-      filename: filename.as_ref().to_path_buf(),
-    })
+  /// Reconnect in read-only mode, the writes fail with `SQLITE_READONLY`.
+  /// Used when the migrations failed, the schema is unknown and must not be written.
+  /// The backups can still be created and restored.
+  #[tracing::instrument(skip(self))]
+  pub async fn reconnect_read_only(&mut self) -> Result<(), DatabaseError> {
+    info!("Reconnecting to database in read-only mode...");
+
+    let sqlite = Self::connect(&self.filename, true).await?;
+    self.inner.close().await;
+    self.inner = sqlite;
+    Ok(())
   }
 
   /// Initialize database and apply migrations
   #[inline]
   #[tracing::instrument(skip(self))]
-  pub async fn apply_migrations(&self) -> Result<(), DatabaseError> {
+  pub async fn apply_migrations(&self) -> Result<(), migrations::MigrationError> {
     migrations::apply_migrations(self).await
   }
 
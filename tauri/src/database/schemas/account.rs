@@ -3,6 +3,7 @@ use sqlx::encode::IsNull;
 use sqlx::error::BoxDynError;
 use sqlx::sqlite::{SqliteArgumentValue, SqliteRow, SqliteTypeInfo, SqliteValueRef};
 use sqlx::{Decode, Encode, FromRow, Row, Sqlite, Type};
+use time::UtcOffset;
 
 use crate::database::schemas::JsonProperties;
 
@@ -106,6 +107,23 @@ pub struct Account {
   pub properties: Option<JsonProperties>,
 }
 
+impl Account {
+  // Known `properties` keys
+  pub const KEY_TIMEZONE_OVERRIDE: &str = "timezone_override";
+
+  /// Returns the timezone override of this account. (Whole hours)
+  /// It takes precedence over the fixed timezone of the `GameBiz` server.
+  pub fn timezone_override(&self) -> Option<UtcOffset> {
+    let hours = self
+      .properties
+      .as_ref()?
+      .get(Self::KEY_TIMEZONE_OVERRIDE)?
+      .as_i64()?;
+
+    UtcOffset::from_hms(i8::try_from(hours).ok()?, 0, 0).ok()
+  }
+}
+
 impl<'r> FromRow<'r, SqliteRow> for Account {
   fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
     Ok(Self {
@@ -0,0 +1,308 @@
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::{SqliteArgumentValue, SqliteRow, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, FromRow, Row, Sqlite, Type};
+use time::OffsetDateTime;
+use time::serde::rfc3339;
+
+use tracing::info;
+
+use crate::database::schemas::{AccountBusiness, JsonProperties, retention_days};
+use crate::database::{Database, DatabaseError};
+
+// Audit log, the history of the data changes.
+//
+// | operation       | business, uid | counts                | source           | written by                    |
+// |-----------------|---------------|-----------------------|------------------|-------------------------------|
+// | Fetch           | Some          | GachaRecordSaveReport | SHA-1 of the url | GachaRecordSaver              |
+// | FullUpdate      | Some          | GachaRecordSaveReport | SHA-1 of the url | GachaRecordSaver              |
+// | Import          | Some or None  | GachaRecordSaveReport | File name        | GachaRecordSaver              |
+// | LegacyMigration | None          | accounts, records     | Legacy file name | LegacyMigration               |
+// | DeleteRecords   | Some          | deleted               | None             | database_delete_gacha_records |
+// | CreateAccount   | Some          | None                  | None             | Trigger                       |
+// | UpdateAccount   | Some          | None                  | None             | Trigger (data_folder only)    |
+// | DeleteAccount   | Some          | None                  | None             | Trigger                       |
+//
+// The logs are kept until expired. (See: `purge_expired_audit_logs`)
+//
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum AuditOperation {
+  Fetch,
+  FullUpdate,
+  Import,
+  LegacyMigration,
+  DeleteRecords,
+  CreateAccount,
+  UpdateAccount,
+  DeleteAccount,
+}
+
+impl AuditOperation {
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Self::Fetch => "Fetch",
+      Self::FullUpdate => "FullUpdate",
+      Self::Import => "Import",
+      Self::LegacyMigration => "LegacyMigration",
+      Self::DeleteRecords => "DeleteRecords",
+      Self::CreateAccount => "CreateAccount",
+      Self::UpdateAccount => "UpdateAccount",
+      Self::DeleteAccount => "DeleteAccount",
+    }
+  }
+}
+
+impl TryFrom<&str> for AuditOperation {
+  type Error = String;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    match value {
+      "Fetch" => Ok(Self::Fetch),
+      "FullUpdate" => Ok(Self::FullUpdate),
+      "Import" => Ok(Self::Import),
+      "LegacyMigration" => Ok(Self::LegacyMigration),
+      "DeleteRecords" => Ok(Self::DeleteRecords),
+      "CreateAccount" => Ok(Self::CreateAccount),
+      "UpdateAccount" => Ok(Self::UpdateAccount),
+      "DeleteAccount" => Ok(Self::DeleteAccount),
+      other => Err(format!("Unknown AuditOperation value: {other}")),
+    }
+  }
+}
+
+impl Type<Sqlite> for AuditOperation {
+  fn type_info() -> SqliteTypeInfo {
+    str::type_info()
+  }
+
+  fn compatible(ty: &SqliteTypeInfo) -> bool {
+    str::compatible(ty)
+  }
+}
+
+impl<'r> Encode<'r, Sqlite> for AuditOperation {
+  fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'r>>) -> Result<IsNull, BoxDynError> {
+    self.as_str().encode_by_ref(buf)
+  }
+}
+
+impl Decode<'_, Sqlite> for AuditOperation {
+  fn decode(value: SqliteValueRef) -> Result<Self, BoxDynError> {
+    let s = <&str>::decode(value)?;
+    Self::try_from(s).map_err(Into::into)
+  }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLog {
+  pub id: i64,
+  pub operation: AuditOperation,
+  pub business: Option<AccountBusiness>,
+  pub uid: Option<u32>,
+  pub counts: Option<JsonProperties>,
+  pub source: Option<String>,
+  #[serde(with = "rfc3339")]
+  pub created_at: OffsetDateTime,
+}
+
+impl<'r> FromRow<'r, SqliteRow> for AuditLog {
+  fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+    Ok(Self {
+      id: row.try_get("id")?,
+      operation: row.try_get("operation")?,
+      business: row.try_get("business")?,
+      uid: row.try_get("uid")?,
+      counts: row.try_get("counts")?,
+      source: row.try_get("source")?,
+      created_at: row.try_get("created_at")?,
+    })
+  }
+}
+
+impl_questioner_with_handlers! {
+  #[audit_log_handlers]
+  AuditLog of AuditLogQuestioner,
+
+  "INSERT INTO `HG_AUDIT_LOG` (`operation`, `business`, `uid`, `counts`, `source`) VALUES (?, ?, ?, ?, ?);"
+    = create_audit_log {
+        operation: AuditOperation,
+        business: Option<AccountBusiness>,
+        uid: Option<u32>,
+        counts: Option<JsonProperties>,
+        source: Option<&str>
+      }: execute -> u64,
+
+  // Newest first, the `business` and `uid` are optional filters.
+  #[database_find_audit_logs]
+  "SELECT * FROM `HG_AUDIT_LOG`
+    WHERE (?1 IS NULL OR `business` = ?1) AND (?2 IS NULL OR `uid` = ?2)
+    ORDER BY `id` DESC LIMIT ?3 OFFSET ?4;"
+    = find_audit_logs {
+        business: Option<AccountBusiness>,
+        uid: Option<u32>,
+        limit: u32,
+        offset: u32
+      }: fetch_all -> Vec<AuditLog>,
+
+  "DELETE FROM `HG_AUDIT_LOG` WHERE `created_at` < DATETIME('now', '-' || ? || ' days');"
+    = purge_expired_audit_logs { retention_days: u32 }: execute -> u64,
+}
+
+/// The source of the file, only the file name.
+/// The full path may contain the user name.
+pub fn audit_source_of(path: &Path) -> Option<String> {
+  path
+    .file_name()
+    .map(|file_name| file_name.to_string_lossy().into_owned())
+}
+
+/// The retention period of the audit log in days. (`HG_KVS`)
+pub const KEY_AUDIT_LOG_RETENTION_DAYS: &str = "HG_AUDIT_LOG_RETENTION_DAYS";
+
+/// The default retention period of the audit log in days.
+pub const DEFAULT_AUDIT_LOG_RETENTION_DAYS: u32 = 365;
+
+/// Purge the logs created longer than the retention period.
+#[tracing::instrument(skip_all)]
+pub async fn purge_expired_audit_logs(database: &Database) -> Result<u64, DatabaseError> {
+  let retention_days = retention_days(
+    database,
+    KEY_AUDIT_LOG_RETENTION_DAYS,
+    DEFAULT_AUDIT_LOG_RETENTION_DAYS,
+  )
+  .await?;
+
+  let start = Instant::now();
+  let purged = database.purge_expired_audit_logs(retention_days).await?;
+
+  info!(
+    message = "Purged expired audit logs",
+    elapsed = ?start.elapsed(),
+    retention_days,
+    purged,
+  );
+
+  Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::database::Database;
+  use crate::database::schemas::AccountQuestioner;
+
+  #[tokio::test]
+  async fn test_audit_log() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_audit_log.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let business = AccountBusiness::GenshinImpact;
+    database
+      .create_account(business, 100_000_000, "data_folder", None)
+      .await
+      .unwrap();
+    database
+      .create_account(
+        AccountBusiness::HonkaiStarRail,
+        100_000_000,
+        "data_folder",
+        None,
+      )
+      .await
+      .unwrap();
+
+    // Properties are not logged
+    database
+      .update_account_properties(None, business, 100_000_000)
+      .await
+      .unwrap();
+    database
+      .update_account_data_folder("moved", business, 100_000_000)
+      .await
+      .unwrap();
+
+    let counts = JsonProperties::from_iter([("inserted".to_owned(), 10.into())]);
+    database
+      .create_audit_log(
+        AuditOperation::Import,
+        Some(business),
+        Some(100_000_000),
+        Some(counts.clone()),
+        Some("records.json"),
+      )
+      .await
+      .unwrap();
+
+    database
+      .delete_account(business, 100_000_000)
+      .await
+      .unwrap();
+
+    let logs = database
+      .find_audit_logs(Some(business), Some(100_000_000), 10, 0)
+      .await
+      .unwrap();
+
+    assert_eq!(
+      logs
+        .iter()
+        .map(|log| (log.operation, log.source.as_deref()))
+        .collect::<Vec<_>>(),
+      vec![
+        (AuditOperation::DeleteAccount, None),
+        (AuditOperation::Import, Some("records.json")),
+        (AuditOperation::UpdateAccount, None),
+        (AuditOperation::CreateAccount, None),
+      ]
+    );
+    assert_eq!(logs[1].counts, Some(counts));
+
+    // Paginated
+    let page = database
+      .find_audit_logs(Some(business), Some(100_000_000), 2, 2)
+      .await
+      .unwrap();
+    assert_eq!(page, logs[2..]);
+
+    // Without filters
+    let all = database.find_audit_logs(None, None, 10, 0).await.unwrap();
+    assert_eq!(all.len(), 5);
+
+    // Only the file name
+    assert_eq!(
+      audit_source_of(Path::new("/home/user/records.json")).as_deref(),
+      Some("records.json")
+    );
+
+    // Expired
+    assert_eq!(purge_expired_audit_logs(&database).await.unwrap(), 0);
+
+    sqlx::query(
+      "UPDATE `HG_AUDIT_LOG` SET `created_at` = DATETIME('now', '-366 days') WHERE `operation` = 'Import';",
+    )
+    .execute(&database.inner)
+    .await
+    .unwrap();
+    assert_eq!(purge_expired_audit_logs(&database).await.unwrap(), 1);
+    assert_eq!(
+      database
+        .find_audit_logs(None, None, 10, 0)
+        .await
+        .unwrap()
+        .len(),
+      4
+    );
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+}
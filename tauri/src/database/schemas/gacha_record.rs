@@ -1,15 +1,20 @@
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
 use serde::{Deserialize, Serialize};
+use snafu::Snafu;
 use sqlx::query::Query;
-use sqlx::sqlite::{SqliteArguments, SqliteRow};
-use sqlx::{FromRow, Row, Sqlite};
+use sqlx::sqlite::{SqliteArguments, SqliteConnection, SqliteRow};
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite};
 use time::serde::rfc3339;
-use time::{OffsetDateTime, PrimitiveDateTime};
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
 use tracing::{error, info};
 
-use crate::database::schemas::{AccountBusiness, JsonProperties};
+use crate::database::schemas::{
+  AccountBusiness, AuditLogQuestioner, AuditOperation, JsonProperties,
+};
 use crate::database::{Database, DatabaseError};
+use crate::error::ErrorDetails;
 
 // Known Gacha record data structures.
 //
@@ -35,6 +40,12 @@ use crate::database::{Database, DatabaseError};
 // * `Null`  : This field does not exist.
 // * `Some`  : Have values and are different.
 // * `Empty` : Is the empty string.
+// * `item_id` : Stored as INTEGER, the `Empty` is resolved by the metadata before saving.
+//               The `0` is unknown, the records before it was INTEGER. (See: `resolve_unknown_item_ids`)
+// * `properties.i18n` : Optional for all, the item names fetched with alternate languages.
+// * `properties.time_offset_source` : Optional for all, where the offset of `time` came from.
+//                                     Missing for the fixed timezone of the `GameBiz` server,
+//                                     and the records saved before it was introduced.
 //
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -65,6 +76,40 @@ impl GachaRecord {
   pub const KEY_SCHEDULE_ID: &str = "schedule_id";
   pub const KEY_IS_UP: &str = "is_up";
   pub const IS_UP_ZERO: &str = "0";
+  pub const KEY_I18N: &str = "i18n";
+  pub const KEY_I18N_ITEM_NAME: &str = "item_name";
+  pub const KEY_I18N_ITEM_TYPE: &str = "item_type";
+  pub const KEY_TIME_OFFSET_SOURCE: &str = "time_offset_source";
+
+  /// Returns the `item_name` and `item_type` of the given `lang`,
+  /// either the record's own or the one fetched with alternate languages.
+  /// See: `properties.i18n`
+  pub fn i18n(&self, lang: &str) -> Option<(&str, &str)> {
+    if self.lang == lang {
+      return Some((&self.item_name, &self.item_type));
+    }
+
+    let i18n = self.properties.as_ref()?.get(Self::KEY_I18N)?.get(lang)?;
+    let item_name = i18n.get(Self::KEY_I18N_ITEM_NAME)?.as_str()?;
+    let item_type = i18n.get(Self::KEY_I18N_ITEM_TYPE)?.as_str()?;
+    Some((item_name, item_type))
+  }
+
+  /// Record where the offset of `time` came from into the `properties`.
+  /// The default is not recorded, otherwise every record would carry the properties.
+  pub fn set_time_offset_source(&mut self, source: GachaRecordTimeOffsetSource) {
+    if source != GachaRecordTimeOffsetSource::default() {
+      self
+        .properties
+        .get_or_insert_default()
+        .insert(Self::KEY_TIME_OFFSET_SOURCE.into(), source.as_str().into());
+    } else if let Some(properties) = self.properties.as_mut() {
+      properties.remove(Self::KEY_TIME_OFFSET_SOURCE);
+      if properties.is_empty() {
+        self.properties = None;
+      }
+    }
+  }
 
   /// HACK: 'Genshin Impact: Miliastra Wonderland' only
   #[inline]
@@ -122,6 +167,31 @@ impl GachaRecord {
   }
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum GachaRecordTimeOffsetSource {
+  /// The fixed timezone of the `GameBiz` server.
+  #[default]
+  GameBiz,
+  /// The timezone override of the account.
+  Account,
+  /// The timezone provided by the imported file.
+  Imported,
+  /// Re-based to a corrected offset.
+  Rebased,
+}
+
+impl GachaRecordTimeOffsetSource {
+  #[inline]
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Self::GameBiz => "GameBiz",
+      Self::Account => "Account",
+      Self::Imported => "Imported",
+      Self::Rebased => "Rebased",
+    }
+  }
+}
+
 impl<'r> FromRow<'r, SqliteRow> for GachaRecord {
   fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
     Ok(Self {
@@ -136,10 +206,7 @@ impl<'r> FromRow<'r, SqliteRow> for GachaRecord {
       time: row.try_get("time")?,
       item_name: row.try_get("name")?, // database: name
       item_type: row.try_get("item_type")?,
-      item_id: row
-        .try_get::<String, _>("item_id")? // database: string
-        .parse::<u32>()
-        .map_err(|e| sqlx::Error::Decode(e.into()))?,
+      item_id: row.try_get("item_id")?,
       properties: row.try_get("properties")?,
     })
   }
@@ -163,10 +230,18 @@ impl_questioner_with_handlers! {
         limit: u32
       }: fetch_all -> Vec<GachaRecord>,
 
-  #[database_delete_gacha_records]
+  // See: `delete_gacha_records_with_audit`
   "DELETE FROM `HG_GACHA_RECORDS` WHERE `business` = ? AND `uid` = ?;"
     = delete_gacha_records { business: AccountBusiness, uid: u32 }: execute -> u64,
 
+  "SELECT * FROM `HG_GACHA_RECORDS` WHERE `business` = ? AND `uid` = ? AND `gacha_type` = ? AND `id` >= ? ORDER BY `id` ASC;"
+    = find_gacha_records_with_newer_than_end_id {
+        business: AccountBusiness,
+        uid: u32,
+        gacha_type: u32,
+        end_id: &str
+      }: fetch_all -> Vec<GachaRecord>,
+
   "DELETE FROM `HG_GACHA_RECORDS` WHERE `business` = ? AND `uid` = ? AND `gacha_type` = ? AND `id` >= ?;"
     = delete_gacha_records_with_newer_than_end_id {
         business: AccountBusiness,
@@ -174,6 +249,230 @@ impl_questioner_with_handlers! {
         gacha_type: u32,
         end_id: &str
       }: execute -> u64,
+
+  handlers {
+    #[tauri::command]
+    pub async fn database_delete_gacha_records(
+      database: crate::bootstrap::TauriDatabaseState<'_>,
+      business: AccountBusiness,
+      uid: u32,
+    ) -> Result<u64, crate::error::AppError<DatabaseError>> {
+      Ok(delete_gacha_records_with_audit(&database, business, uid).await?)
+    }
+
+    #[tauri::command]
+    pub async fn database_query_gacha_records(
+      database: crate::bootstrap::TauriDatabaseState<'_>,
+      query: GachaRecordQuery,
+    ) -> Result<GachaRecordPage, crate::error::AppError<DatabaseError>> {
+      Ok(query.fetch(&database).await?)
+    }
+  }
+}
+
+/// Delete the records of the account, with the audit log.
+#[tracing::instrument(skip(database))]
+pub async fn delete_gacha_records_with_audit(
+  database: &Database,
+  business: AccountBusiness,
+  uid: u32,
+) -> Result<u64, DatabaseError> {
+  let mut txn = database.inner.begin().await?;
+  let deleted = (&mut *txn).delete_gacha_records(business, uid).await?;
+
+  let counts = JsonProperties::from_iter([("deleted".into(), deleted.into())]);
+  (&mut *txn)
+    .create_audit_log(
+      AuditOperation::DeleteRecords,
+      Some(business),
+      Some(uid),
+      Some(counts),
+      None,
+    )
+    .await?;
+
+  txn.commit().await?;
+  Ok(deleted)
+}
+
+// Filtered and paginated query
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum GachaRecordQueryOrder {
+  #[default]
+  Asc,
+  Desc,
+}
+
+impl GachaRecordQueryOrder {
+  const fn as_sql(&self) -> &'static str {
+    match self {
+      Self::Asc => "ASC",
+      Self::Desc => "DESC",
+    }
+  }
+}
+
+/// The position of a record in the query results.
+/// The `id` is only unique within the `gacha_type`. (See: Primary key)
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordCursor {
+  pub id: String,
+  pub gacha_type: u32,
+}
+
+impl From<&GachaRecord> for GachaRecordCursor {
+  fn from(record: &GachaRecord) -> Self {
+    Self {
+      id: record.id.clone(),
+      gacha_type: record.gacha_type,
+    }
+  }
+}
+
+/// Query the records of the account with the optional filters,
+/// ordered by `id` and paginated with the cursor of the last record.
+///
+/// The `None` or empty filters match all records.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordQuery {
+  pub business: AccountBusiness,
+  pub uid: u32,
+  #[serde(default)]
+  pub gacha_types: Option<Vec<u32>>,
+  #[serde(default)]
+  pub rank_types: Option<Vec<u32>>,
+  #[serde(default)]
+  pub item_id: Option<u32>,
+  #[serde(default)]
+  pub item_name: Option<String>,
+  /// Inclusive
+  #[serde(default, with = "rfc3339::option")]
+  pub time_from: Option<OffsetDateTime>,
+  /// Exclusive
+  #[serde(default, with = "rfc3339::option")]
+  pub time_to: Option<OffsetDateTime>,
+  /// See: `properties.is_up`
+  #[serde(default)]
+  pub is_up: Option<bool>,
+  /// The records after this cursor, in the order.
+  #[serde(default)]
+  pub after: Option<GachaRecordCursor>,
+  #[serde(default)]
+  pub order: GachaRecordQueryOrder,
+  #[serde(default)]
+  pub limit: Option<u32>,
+}
+
+/// A page of the query results.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordPage {
+  pub records: Vec<GachaRecord>,
+  /// The cursor of the next page, `None` if this is the last page.
+  pub next: Option<GachaRecordCursor>,
+}
+
+impl GachaRecordQuery {
+  fn push_in(builder: &mut QueryBuilder<'_, Sqlite>, column: &str, values: &[u32]) {
+    if values.is_empty() {
+      return;
+    }
+
+    builder.push(format!(" AND `{column}` IN ("));
+    let mut separated = builder.separated(", ");
+    for value in values {
+      separated.push_bind(*value);
+    }
+    separated.push_unseparated(")");
+  }
+
+  fn build(&self) -> QueryBuilder<'_, Sqlite> {
+    let mut builder = QueryBuilder::new("SELECT * FROM `HG_GACHA_RECORDS` WHERE `business` = ");
+    builder.push_bind(self.business);
+    builder.push(" AND `uid` = ").push_bind(self.uid);
+
+    if let Some(gacha_types) = &self.gacha_types {
+      Self::push_in(&mut builder, "gacha_type", gacha_types);
+    }
+
+    if let Some(rank_types) = &self.rank_types {
+      Self::push_in(&mut builder, "rank_type", rank_types);
+    }
+
+    if let Some(item_id) = self.item_id {
+      builder.push(" AND `item_id` = ").push_bind(item_id);
+    }
+
+    if let Some(item_name) = &self.item_name {
+      builder.push(" AND `name` = ").push_bind(item_name.as_str());
+    }
+
+    // Compare the instants, the offsets of the records may differ
+    if let Some(time_from) = self.time_from {
+      builder
+        .push(" AND JULIANDAY(`time`) >= JULIANDAY(")
+        .push_bind(time_from)
+        .push(")");
+    }
+
+    if let Some(time_to) = self.time_to {
+      builder
+        .push(" AND JULIANDAY(`time`) < JULIANDAY(")
+        .push_bind(time_to)
+        .push(")");
+    }
+
+    if let Some(is_up) = self.is_up {
+      // The "0" is not saved, missing means not up. (See: Fetcher)
+      builder.push(format!(
+        " AND COALESCE(JSON_EXTRACT(`properties`, '$.{}'), '{}') {} '{}'",
+        GachaRecord::KEY_IS_UP,
+        GachaRecord::IS_UP_ZERO,
+        if is_up { "!=" } else { "=" },
+        GachaRecord::IS_UP_ZERO,
+      ));
+    }
+
+    if let Some(after) = &self.after {
+      builder
+        .push(match self.order {
+          GachaRecordQueryOrder::Asc => " AND (`id`, `gacha_type`) > (",
+          GachaRecordQueryOrder::Desc => " AND (`id`, `gacha_type`) < (",
+        })
+        .push_bind(after.id.as_str())
+        .push(", ")
+        .push_bind(after.gacha_type)
+        .push(")");
+    }
+
+    let order = self.order.as_sql();
+    builder.push(format!(" ORDER BY `id` {order}, `gacha_type` {order}"));
+
+    if let Some(limit) = self.limit {
+      builder.push(" LIMIT ").push_bind(limit);
+    }
+
+    builder
+  }
+
+  #[tracing::instrument(skip(database))]
+  pub async fn fetch(&self, database: &Database) -> Result<GachaRecordPage, DatabaseError> {
+    let records = self
+      .build()
+      .build_query_as::<GachaRecord>()
+      .fetch_all(&database.inner)
+      .await?;
+
+    let next = match self.limit {
+      Some(limit) if records.len() >= limit as usize => records.last().map(GachaRecordCursor::from),
+      _ => None,
+    };
+
+    Ok(GachaRecordPage { records, next })
+  }
 }
 
 // Create and save transaction
@@ -186,29 +485,22 @@ pub enum GachaRecordSaveOnConflict {
 }
 
 impl GachaRecordSaveOnConflict {
-  pub const fn as_sql(&self) -> &'static str {
+  const INSERT_INTO: &str = "INSERT INTO `HG_GACHA_RECORDS` (
+    `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+    `count`, `time`, `lang`, `name`, `item_type`, `item_id`,
+    `properties`
+  ) VALUES";
+
+  const ROW_VALUES: &str = "(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+
+  /// The number of bind variables of a row.
+  const ROW_VARIABLES: usize = 13;
+
+  const fn on_conflict_sql(&self) -> &'static str {
     match self {
-      Self::Nothing => {
-        "INSERT INTO `HG_GACHA_RECORDS` (
-          `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
-          `count`, `time`, `lang`, `name`, `item_type`, `item_id`,
-          `properties`
-        ) VALUES (
-          ?, ?, ?, ?, ?, ?,
-          ?, ?, ?, ?, ?, ?,
-          ?
-        ) ON CONFLICT (`business`, `uid`, `id`, `gacha_type`) DO NOTHING;"
-      }
+      Self::Nothing => "ON CONFLICT (`business`, `uid`, `id`, `gacha_type`) DO NOTHING",
       Self::Update => {
-        "INSERT INTO `HG_GACHA_RECORDS` (
-          `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
-          `count`, `time`, `lang`, `name`, `item_type`, `item_id`,
-          `properties`
-        ) VALUES (
-          ?, ?, ?, ?, ?, ?,
-          ?, ?, ?, ?, ?, ?,
-          ?
-        ) ON CONFLICT (`business`, `uid`, `id`, `gacha_type`) DO UPDATE SET
+        "ON CONFLICT (`business`, `uid`, `id`, `gacha_type`) DO UPDATE SET
           `gacha_id`   = excluded.`gacha_id`,
           `rank_type`  = excluded.`rank_type`,
           `count`      = excluded.`count`,
@@ -217,14 +509,50 @@ impl GachaRecordSaveOnConflict {
           `name`       = excluded.`name`,
           `item_type`  = excluded.`item_type`,
           `item_id`    = excluded.`item_id`,
-          `properties` = excluded.`properties`;"
+          `properties` = excluded.`properties`"
       }
     }
   }
 
+  /// Generate the SQL for saving multiple rows with the specified conflict resolution strategy.
+  pub fn as_batch_sql(&self, rows: usize) -> String {
+    let values = vec![Self::ROW_VALUES; rows].join(", ");
+    format!("{} {values} {};", Self::INSERT_INTO, self.on_conflict_sql())
+  }
+
+  pub fn as_sql(&self) -> &'static str {
+    use std::sync::LazyLock;
+
+    static NOTHING: LazyLock<String> =
+      LazyLock::new(|| GachaRecordSaveOnConflict::Nothing.as_batch_sql(1));
+    static UPDATE: LazyLock<String> =
+      LazyLock::new(|| GachaRecordSaveOnConflict::Update.as_batch_sql(1));
+
+    match self {
+      Self::Nothing => &NOTHING,
+      Self::Update => &UPDATE,
+    }
+  }
+
   /// Generate a query for saving a GachaRecord with the specified conflict resolution strategy.
   pub fn as_query<'r>(&self, bind: &'r GachaRecord) -> Query<'r, Sqlite, SqliteArguments<'r>> {
-    sqlx::query(self.as_sql())
+    Self::bind(sqlx::query(self.as_sql()), bind)
+  }
+
+  /// Generate a query for saving multiple GachaRecords. See: `as_batch_sql`
+  pub fn as_batch_query<'r>(
+    &self,
+    sql: &'r str,
+    binds: impl IntoIterator<Item = &'r GachaRecord>,
+  ) -> Query<'r, Sqlite, SqliteArguments<'r>> {
+    binds.into_iter().fold(sqlx::query(sql), Self::bind)
+  }
+
+  fn bind<'r>(
+    query: Query<'r, Sqlite, SqliteArguments<'r>>,
+    bind: &'r GachaRecord,
+  ) -> Query<'r, Sqlite, SqliteArguments<'r>> {
+    query
       .bind(bind.business)
       .bind(bind.uid)
       .bind(&bind.id)
@@ -236,11 +564,176 @@ impl GachaRecordSaveOnConflict {
       .bind(&bind.lang)
       .bind(&bind.item_name) // database: name
       .bind(&bind.item_type)
-      .bind(bind.item_id) // database: string
+      .bind(bind.item_id)
       .bind(&bind.properties)
   }
 }
 
+/// The identity of a record.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordKey {
+  pub business: AccountBusiness,
+  pub uid: u32,
+  pub id: String,
+  pub gacha_type: u32,
+}
+
+impl From<&GachaRecord> for GachaRecordKey {
+  fn from(value: &GachaRecord) -> Self {
+    Self {
+      business: value.business,
+      uid: value.uid,
+      id: value.id.clone(),
+      gacha_type: value.gacha_type,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordFieldDiff {
+  /// The serialized field name. e.g.: `itemName`
+  pub field: String,
+  pub old: serde_json::Value,
+  pub new: serde_json::Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordDiff {
+  #[serde(flatten)]
+  pub key: GachaRecordKey,
+  pub fields: Vec<GachaRecordFieldDiff>,
+}
+
+impl GachaRecordDiff {
+  /// Compare the fields of the records with the same key.
+  /// Returns `None` if nothing is different.
+  ///
+  /// The `properties.time_offset_source` is not compared, it only tells
+  /// where the offset came from and differs between fetch and import.
+  pub fn new(old: &GachaRecord, new: &GachaRecord) -> Option<Self> {
+    use serde_json::{Map, Value};
+
+    let (Ok(Value::Object(mut old_fields)), Ok(Value::Object(mut new_fields))) =
+      (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+      unreachable!("GachaRecord is always serialized as an object")
+    };
+
+    fn remove_time_offset_source(fields: &mut Map<String, Value>) {
+      if let Some(Value::Object(properties)) = fields.get_mut("properties") {
+        properties.remove(GachaRecord::KEY_TIME_OFFSET_SOURCE);
+        if properties.is_empty() {
+          fields.insert("properties".into(), Value::Null);
+        }
+      }
+    }
+
+    remove_time_offset_source(&mut old_fields);
+    remove_time_offset_source(&mut new_fields);
+
+    let fields = old_fields
+      .into_iter()
+      .filter_map(|(field, old)| {
+        let new = new_fields.remove(&field).unwrap_or_default();
+        (old != new).then_some(GachaRecordFieldDiff { field, old, new })
+      })
+      .collect::<Vec<_>>();
+
+    (!fields.is_empty()).then(|| Self {
+      key: GachaRecordKey::from(new),
+      fields,
+    })
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum GachaRecordRejectReason {
+  /// The same record appears more than once, the first is saved.
+  Duplicate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordRejected {
+  #[serde(flatten)]
+  pub key: GachaRecordKey,
+  pub reason: GachaRecordRejectReason,
+}
+
+/// The report of the saved records.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordSaveReport {
+  pub inserted: Vec<GachaRecordKey>,
+  /// Different from the existing and updated. (`GachaRecordSaveOnConflict::Update`)
+  pub updated: Vec<GachaRecordDiff>,
+  /// Same as the existing.
+  pub unchanged: Vec<GachaRecordKey>,
+  /// Different from the existing and kept the existing. (`GachaRecordSaveOnConflict::Nothing`)
+  pub conflicting: Vec<GachaRecordDiff>,
+  pub rejected: Vec<GachaRecordRejected>,
+  /// Deleted before saving and not saved again. (Fetch: `GachaRecordSaveToDatabase::FullUpdate`)
+  pub deleted: Vec<GachaRecordKey>,
+}
+
+impl GachaRecordSaveReport {
+  /// The number of inserted or updated records.
+  #[inline]
+  pub fn changes(&self) -> u64 {
+    (self.inserted.len() + self.updated.len()) as u64
+  }
+
+  /// The counts of the audit log.
+  pub fn counts(&self) -> JsonProperties {
+    JsonProperties::from_iter([
+      ("inserted".into(), self.inserted.len().into()),
+      ("updated".into(), self.updated.len().into()),
+      ("unchanged".into(), self.unchanged.len().into()),
+      ("conflicting".into(), self.conflicting.len().into()),
+      ("rejected".into(), self.rejected.len().into()),
+      ("deleted".into(), self.deleted.len().into()),
+    ])
+  }
+
+  /// Reconcile the records deleted before saving the `saved`.
+  ///
+  /// The deleted and inserted again are unchanged or updated,
+  /// the others are deleted.
+  pub fn reconcile_deleted(&mut self, deleted: Vec<GachaRecord>, saved: &[GachaRecord]) {
+    // The first wins for the duplicates, the others are rejected by the saver.
+    let mut first_saved = HashMap::with_capacity(saved.len());
+    for record in saved {
+      first_saved
+        .entry(GachaRecordKey::from(record))
+        .or_insert(record);
+    }
+
+    let mut inserted = self.inserted.iter().cloned().collect::<HashSet<_>>();
+
+    for record in deleted {
+      let key = GachaRecordKey::from(&record);
+      if !inserted.remove(&key) {
+        self.deleted.push(key);
+        continue;
+      }
+
+      match first_saved
+        .get(&key)
+        .and_then(|saved| GachaRecordDiff::new(&record, saved))
+      {
+        Some(diff) => self.updated.push(diff),
+        None => self.unchanged.push(key),
+      }
+    }
+
+    // Keep the order of the others
+    self.inserted.retain(|key| inserted.contains(key));
+  }
+}
+
 pub struct GachaRecordSaver<'a, P>
 where
   P: Fn(u64),
@@ -248,12 +741,20 @@ where
   records: &'a [GachaRecord],
   on_conflict: GachaRecordSaveOnConflict,
   progress_reporter: Option<P>,
+  audit: Option<(AuditOperation, Option<String>)>,
+  full_update: bool,
 }
 
 impl<'a, P> GachaRecordSaver<'a, P>
 where
   P: Fn(u64),
 {
+  /// The maximum number of bind variables. (Bundled SQLite >= 3.32.0)
+  const MAX_VARIABLES: usize = 32766;
+
+  /// The maximum number of rows of a batch.
+  const MAX_BATCH_ROWS: usize = Self::MAX_VARIABLES / GachaRecordSaveOnConflict::ROW_VARIABLES;
+
   pub fn new(
     records: &'a [GachaRecord],
     on_conflict: GachaRecordSaveOnConflict,
@@ -263,15 +764,93 @@ where
       records,
       on_conflict,
       progress_reporter,
+      audit: None,
+      full_update: false,
+    }
+  }
+
+  /// Write the audit log of the operation in the same transaction.
+  pub fn with_audit(mut self, operation: AuditOperation, source: Option<String>) -> Self {
+    self.audit = Some((operation, source));
+    self
+  }
+
+  /// Delete the existing records newer than the oldest of each gacha type
+  /// in the same transaction before saving, and report the deleted.
+  pub fn with_full_update(mut self) -> Self {
+    self.full_update = true;
+    self
+  }
+
+  // Delete the records to be recreated by the full update, returns the deleted.
+  async fn delete_for_full_update(
+    txn: &mut SqliteConnection,
+    records: &[GachaRecord],
+  ) -> Result<Vec<GachaRecord>, DatabaseError> {
+    // The records are in the order of the gacha log, the last is the oldest.
+    let mut oldest_end_ids = HashMap::new();
+    for record in records {
+      oldest_end_ids.insert(
+        (record.business, record.uid, record.gacha_type),
+        record.id.as_str(),
+      );
+    }
+
+    let mut deleted = Vec::new();
+    for ((business, uid, gacha_type), end_id) in oldest_end_ids {
+      deleted.extend(
+        (&mut *txn)
+          .find_gacha_records_with_newer_than_end_id(business, uid, gacha_type, end_id)
+          .await?,
+      );
+
+      (&mut *txn)
+        .delete_gacha_records_with_newer_than_end_id(business, uid, gacha_type, end_id)
+        .await?;
     }
+
+    Ok(deleted)
+  }
+
+  // Find the existing records of the batch.
+  async fn find_existing(
+    txn: &mut SqliteConnection,
+    batch: &[&GachaRecord],
+  ) -> Result<HashMap<GachaRecordKey, GachaRecord>, sqlx::Error> {
+    let values = vec!["(?, ?, ?, ?)"; batch.len()].join(", ");
+    let sql = format!(
+      "SELECT * FROM `HG_GACHA_RECORDS`
+        WHERE (`business`, `uid`, `id`, `gacha_type`) IN (VALUES {values});"
+    );
+
+    let query = batch.iter().fold(
+      sqlx::query_as::<Sqlite, GachaRecord>(&sql),
+      |query, record| {
+        query
+          .bind(record.business)
+          .bind(record.uid)
+          .bind(&record.id)
+          .bind(record.gacha_type)
+      },
+    );
+
+    let existing: Vec<GachaRecord> = query.fetch_all(txn).await?;
+    Ok(
+      existing
+        .into_iter()
+        .map(|record| (GachaRecordKey::from(&record), record))
+        .collect(),
+    )
   }
 
   #[tracing::instrument(skip_all)]
-  pub async fn save(self, database: &Database) -> Result<u64, DatabaseError> {
+  pub async fn save(self, database: &Database) -> Result<GachaRecordSaveReport, DatabaseError> {
     let Self {
       records,
       on_conflict,
       progress_reporter,
+      audit,
+      full_update,
     } = self;
 
     let start = Instant::now();
@@ -283,28 +862,138 @@ where
       ?start
     );
 
-    let mut txn = database.inner.begin().await?;
-    let mut changes: u64 = 0;
+    let mut report = GachaRecordSaveReport::default();
     let mut completes: u64 = 0;
+    let mut report_progress = |n: usize| {
+      for _ in 0..n {
+        completes += 1;
+        if let Some(reporter) = &progress_reporter {
+          reporter(completes);
+        }
+      }
+    };
 
+    // Reject the duplicates, the first is saved
+    let mut keys = HashSet::with_capacity(len);
+    let mut unique = Vec::with_capacity(len);
     for record in records {
-      match on_conflict.as_query(record).execute(&mut *txn).await {
+      if keys.insert((record.business, record.uid, &record.id, record.gacha_type)) {
+        unique.push(record);
+      } else {
+        report.rejected.push(GachaRecordRejected {
+          key: GachaRecordKey::from(record),
+          reason: GachaRecordRejectReason::Duplicate,
+        });
+      }
+    }
+
+    report_progress(report.rejected.len());
+
+    let mut txn = database.inner.begin().await?;
+
+    let deleted = if full_update {
+      match Self::delete_for_full_update(&mut txn, records).await {
+        Ok(deleted) => deleted,
         Err(e) => {
-          // Log error and rollback transaction
-          error!(message = "Failed to save GachaRecord", ?record, ?e);
+          error!(message = "Failed to delete GachaRecord for full update", ?e);
+          txn.rollback().await?;
+          return Err(e);
+        }
+      }
+    } else {
+      Vec::new()
+    };
+
+    // Prepared statements are cached by the SQL,
+    // all full batches share the same one.
+    let mut sql = String::new();
+    for batch in unique.chunks(Self::MAX_BATCH_ROWS) {
+      let existing = match Self::find_existing(&mut txn, batch).await {
+        Ok(existing) => existing,
+        Err(e) => {
+          error!(message = "Failed to find existing GachaRecord", ?e);
           txn.rollback().await?;
           return Err(e.into());
         }
-        Ok(ret) => {
-          // Count affected rows and report progress
-          changes += ret.rows_affected();
-          completes += 1;
+      };
 
-          if let Some(reporter) = &progress_reporter {
-            reporter(completes);
+      // Only the inserted and updated are written
+      let mut writes = Vec::with_capacity(batch.len());
+      for record in batch {
+        let key = GachaRecordKey::from(*record);
+        let Some(old) = existing.get(&key) else {
+          report.inserted.push(key);
+          writes.push(*record);
+          continue;
+        };
+
+        match (GachaRecordDiff::new(old, record), on_conflict) {
+          (None, _) => report.unchanged.push(key),
+          (Some(diff), GachaRecordSaveOnConflict::Nothing) => report.conflicting.push(diff),
+          (Some(diff), GachaRecordSaveOnConflict::Update) => {
+            report.updated.push(diff);
+            writes.push(*record);
           }
         }
       }
+
+      if !writes.is_empty() {
+        if sql.is_empty() || writes.len() != Self::MAX_BATCH_ROWS {
+          sql = on_conflict.as_batch_sql(writes.len());
+        }
+
+        let ret = on_conflict
+          .as_batch_query(&sql, writes.iter().copied())
+          .execute(&mut *txn)
+          .await;
+
+        if let Err(e) = ret {
+          // Log error and rollback transaction
+          error!(
+            message = "Failed to save GachaRecord batch",
+            first = ?writes.first(),
+            rows = writes.len(),
+            ?e
+          );
+          txn.rollback().await?;
+          return Err(e.into());
+        }
+      }
+
+      // Report progress of each record
+      report_progress(batch.len());
+    }
+
+    if full_update {
+      report.reconcile_deleted(deleted, records);
+    }
+
+    if let Some((operation, source)) = audit {
+      // The account of the records, if only one
+      let account = records
+        .first()
+        .map(|record| (record.business, record.uid))
+        .filter(|account| {
+          records
+            .iter()
+            .all(|record| (record.business, record.uid) == *account)
+        });
+
+      let ret = (&mut *txn)
+        .create_audit_log(
+          operation,
+          account.map(|(business, _)| business),
+          account.map(|(_, uid)| uid),
+          Some(report.counts()),
+          source.as_deref(),
+        )
+        .await;
+
+      if let Err(e) = ret {
+        error!(message = "Failed to write GachaRecord audit log", ?e);
+        txn.rollback().await?;
+        return Err(e);
+      }
     }
 
     // Commit transaction
@@ -314,9 +1003,794 @@ where
       message = "GachaRecord saver transaction committed",
       elapsed = ?start.elapsed(),
       records = len,
+      inserted = report.inserted.len(),
+      updated = report.updated.len(),
+      unchanged = report.unchanged.len(),
+      conflicting = report.conflicting.len(),
+      rejected = report.rejected.len(),
+      deleted = report.deleted.len(),
+    );
+
+    Ok(report)
+  }
+}
+
+// Re-base transaction
+
+#[derive(Debug, Snafu)]
+pub enum GachaRecordRebaseError {
+  #[snafu(display("{source}"))]
+  Database { source: DatabaseError },
+
+  #[snafu(display("Invalid time offset: {offset} hours"))]
+  InvalidOffset { offset: i8 },
+}
+
+impl ErrorDetails for GachaRecordRebaseError {
+  fn name(&self) -> &'static str {
+    match self {
+      Self::Database { source } => source.name(),
+      _ => stringify!(GachaRecordRebaseError),
+    }
+  }
+
+  fn details(&self) -> Option<serde_json::Value> {
+    use serde_json::json;
+
+    match self {
+      Self::Database { source } => source.details(),
+      Self::InvalidOffset { offset } => Some(json!({
+        "kind": stringify!(InvalidOffset),
+        "offset": offset,
+      })),
+    }
+  }
+}
+
+impl From<DatabaseError> for GachaRecordRebaseError {
+  fn from(source: DatabaseError) -> Self {
+    Self::Database { source }
+  }
+}
+
+/// Re-base the `time` of all records of the account to the corrected offset.
+///
+/// The local date time is kept, only the offset is replaced.
+/// e.g. `2023-01-01T00:00:00+08:00` -> `2023-01-01T00:00:00+09:00`
+pub struct GachaRecordTimeRebaser {
+  business: AccountBusiness,
+  uid: u32,
+  offset: UtcOffset,
+}
+
+impl GachaRecordTimeRebaser {
+  pub fn new(business: AccountBusiness, uid: u32, offset: UtcOffset) -> Self {
+    Self {
+      business,
+      uid,
+      offset,
+    }
+  }
+
+  /// The offset of whole hours, e.g. the timezone override of the account.
+  pub fn from_hours(
+    business: AccountBusiness,
+    uid: u32,
+    offset: i8,
+  ) -> Result<Self, GachaRecordRebaseError> {
+    let offset = UtcOffset::from_hms(offset, 0, 0)
+      .map_err(|_| GachaRecordRebaseError::InvalidOffset { offset })?;
+
+    Ok(Self::new(business, uid, offset))
+  }
+
+  #[tracing::instrument(skip_all, fields(business = ?self.business, uid = self.uid, offset = %self.offset))]
+  pub async fn rebase(self, database: &Database) -> Result<u64, DatabaseError> {
+    let Self {
+      business,
+      uid,
+      offset,
+    } = self;
+
+    let start = Instant::now();
+    info!("Committing GachaRecord time rebaser transaction");
+
+    let mut txn = database.inner.begin().await?;
+    let records: Vec<GachaRecord> =
+      sqlx::query_as("SELECT * FROM `HG_GACHA_RECORDS` WHERE `business` = ? AND `uid` = ?;")
+        .bind(business)
+        .bind(uid)
+        .fetch_all(&mut *txn)
+        .await?;
+
+    let mut changes: u64 = 0;
+    for mut record in records {
+      // Already the expected offset
+      if record.time.offset() == offset {
+        continue;
+      }
+
+      record.time = record.time_to_primitive().assume_offset(offset);
+      record.set_time_offset_source(GachaRecordTimeOffsetSource::Rebased);
+
+      let ret = sqlx::query(
+        "UPDATE `HG_GACHA_RECORDS` SET `time` = ?, `properties` = ?
+          WHERE `business` = ? AND `uid` = ? AND `id` = ? AND `gacha_type` = ?;",
+      )
+      .bind(record.time)
+      .bind(&record.properties)
+      .bind(record.business)
+      .bind(record.uid)
+      .bind(&record.id)
+      .bind(record.gacha_type)
+      .execute(&mut *txn)
+      .await;
+
+      match ret {
+        Err(e) => {
+          // Log error and rollback transaction
+          error!(message = "Failed to rebase GachaRecord", ?record, ?e);
+          txn.rollback().await?;
+          return Err(e.into());
+        }
+        Ok(ret) => changes += ret.rows_affected(),
+      }
+    }
+
+    // Commit transaction
+    txn.commit().await?;
+
+    info!(
+      message = "GachaRecord time rebaser transaction committed",
+      elapsed = ?start.elapsed(),
       changes,
     );
 
     Ok(changes)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use time::macros::{datetime, offset};
+
+  use super::*;
+
+  fn new_record(index: u64) -> GachaRecord {
+    GachaRecord {
+      business: AccountBusiness::GenshinImpact,
+      uid: 100_000_000,
+      id: (1_000_000_000_000_000_000 + index).to_string(),
+      gacha_type: 301,
+      gacha_id: None,
+      rank_type: 3,
+      count: 1,
+      lang: "en-us".into(),
+      time: datetime!(2023-01-01 00:00:00 +8),
+      item_name: "Cool Steel".into(),
+      item_type: "Weapon".into(),
+      item_id: 11301,
+      properties: None,
+    }
+  }
+
+  fn keys_of(range: std::ops::Range<u64>) -> Vec<GachaRecordKey> {
+    range
+      .map(new_record)
+      .map(|record| GachaRecordKey::from(&record))
+      .collect()
+  }
+
+  #[test]
+  fn test_record_diff() {
+    let old = new_record(0);
+    assert_eq!(GachaRecordDiff::new(&old, &old.clone()), None);
+
+    let mut new = old.clone();
+    new.item_name = "Harbinger of Dawn".into();
+    new.time = datetime!(2023-01-01 00:00:00 +9);
+
+    let diff = GachaRecordDiff::new(&old, &new).unwrap();
+    assert_eq!(diff.key, GachaRecordKey::from(&old));
+    assert_eq!(
+      diff.fields,
+      vec![
+        GachaRecordFieldDiff {
+          field: "itemName".into(),
+          old: "Cool Steel".into(),
+          new: "Harbinger of Dawn".into(),
+        },
+        GachaRecordFieldDiff {
+          field: "time".into(),
+          old: "2023-01-01T00:00:00+08:00".into(),
+          new: "2023-01-01T00:00:00+09:00".into(),
+        },
+      ]
+    );
+
+    // Flattened key
+    let value = serde_json::to_value(&diff).unwrap();
+    assert_eq!(value["id"], "1000000000000000000");
+    assert_eq!(value["gachaType"], 301);
+    assert_eq!(value["fields"][0]["field"], "itemName");
+
+    // The offset source is not a difference
+    let mut new = old.clone();
+    new.set_time_offset_source(GachaRecordTimeOffsetSource::Imported);
+    assert_eq!(GachaRecordDiff::new(&old, &new), None);
+
+    new.item_name = "Harbinger of Dawn".into();
+    let diff = GachaRecordDiff::new(&old, &new).unwrap();
+    assert_eq!(diff.fields.len(), 1);
+    assert_eq!(diff.fields[0].field, "itemName");
+  }
+
+  #[tokio::test]
+  async fn test_saver_report() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_saver_report.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    // More than a batch
+    let len = GachaRecordSaver::<fn(u64)>::MAX_BATCH_ROWS as u64 + 10;
+    let records = (0..len).map(new_record).collect::<Vec<_>>();
+
+    let progress = std::sync::Mutex::new(Vec::new());
+    let report = GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Some(|completes| progress.lock().unwrap().push(completes)),
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    assert_eq!(report.inserted, keys_of(0..len));
+    assert_eq!(report.changes(), len);
+
+    // Reported for each record
+    assert_eq!(
+      progress.into_inner().unwrap(),
+      (1..=len).collect::<Vec<_>>()
+    );
+
+    // Half existing, one different and a duplicate
+    let mut records = (len - 5..len + 5).map(new_record).collect::<Vec<_>>();
+    records[0].item_name = "Harbinger of Dawn".into();
+    records.push(new_record(len));
+
+    let report = GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    assert_eq!(report.inserted, keys_of(len..len + 5));
+    assert!(report.updated.is_empty());
+    assert_eq!(report.unchanged, keys_of(len - 4..len));
+    assert_eq!(report.conflicting.len(), 1);
+    assert_eq!(report.conflicting[0].key, GachaRecordKey::from(&records[0]));
+    assert_eq!(
+      report.rejected,
+      vec![GachaRecordRejected {
+        key: GachaRecordKey::from(&new_record(len)),
+        reason: GachaRecordRejectReason::Duplicate,
+      }]
+    );
+
+    let records = (len + 3..len + 8)
+      .map(new_record)
+      .map(|mut record| {
+        record.item_name = "Harbinger of Dawn".into();
+        record
+      })
+      .collect::<Vec<_>>();
+
+    let report = GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Update,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    assert_eq!(report.inserted, keys_of(len + 5..len + 8));
+    assert_eq!(
+      report
+        .updated
+        .iter()
+        .map(|diff| diff.key.clone())
+        .collect::<Vec<_>>(),
+      keys_of(len + 3..len + 5)
+    );
+    assert_eq!(report.updated[0].fields.len(), 1);
+    assert_eq!(report.changes(), 5);
+
+    let saved = database
+      .find_gacha_records(AccountBusiness::GenshinImpact, 100_000_000)
+      .await
+      .unwrap();
+    assert_eq!(saved.len() as u64, len + 8);
+    assert_eq!(saved[len as usize - 5].item_name, "Cool Steel");
+    assert_eq!(saved[len as usize + 3].item_name, "Harbinger of Dawn");
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  #[test]
+  fn test_report_reconcile_deleted() {
+    let deleted = (0..3).map(new_record).collect::<Vec<_>>();
+    let mut saved = (1..5).map(new_record).collect::<Vec<_>>();
+    saved[1].item_name = "Harbinger of Dawn".into();
+
+    let mut report = GachaRecordSaveReport {
+      inserted: keys_of(1..5),
+      ..Default::default()
+    };
+
+    report.reconcile_deleted(deleted, &saved);
+
+    assert_eq!(report.deleted, keys_of(0..1));
+    assert_eq!(report.unchanged, keys_of(1..2));
+    assert_eq!(report.updated.len(), 1);
+    assert_eq!(report.updated[0].key, GachaRecordKey::from(&saved[1]));
+
+    assert_eq!(report.inserted, keys_of(3..5));
+    assert_eq!(report.changes(), 3);
+
+    // The first wins for the duplicates
+    let deleted = vec![new_record(0)];
+    let mut saved = vec![new_record(0), new_record(0)];
+    saved[0].item_name = "Harbinger of Dawn".into();
+
+    let mut report = GachaRecordSaveReport {
+      inserted: keys_of(0..1),
+      ..Default::default()
+    };
+
+    report.reconcile_deleted(deleted, &saved);
+    assert!(report.inserted.is_empty());
+    assert!(report.unchanged.is_empty());
+    assert_eq!(report.updated.len(), 1);
+    assert_eq!(report.updated[0].fields[0].new, "Harbinger of Dawn");
+  }
+
+  #[tokio::test]
+  async fn test_saver_full_update() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_saver_full_update.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let records = (0..5).map(new_record).collect::<Vec<_>>();
+    GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    // The newest first, the oldest end id is 3
+    let mut records = [6, 5, 3].map(new_record);
+    records[2].item_name = "Harbinger of Dawn".into();
+
+    let report = GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .with_audit(AuditOperation::FullUpdate, None)
+    .with_full_update()
+    .save(&database)
+    .await
+    .unwrap();
+
+    assert_eq!(report.inserted, vec![keys_of(6..7), keys_of(5..6)].concat());
+    assert_eq!(report.updated.len(), 1);
+    assert_eq!(report.updated[0].key, GachaRecordKey::from(&records[2]));
+    assert_eq!(report.deleted, keys_of(4..5));
+
+    let saved = database
+      .find_gacha_records(AccountBusiness::GenshinImpact, 100_000_000)
+      .await
+      .unwrap();
+    assert_eq!(
+      saved.iter().map(|record| &record.id).collect::<Vec<_>>(),
+      [0, 1, 2, 3, 5, 6]
+        .map(|index| new_record(index).id)
+        .iter()
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(saved[3].item_name, "Harbinger of Dawn");
+
+    // Written in the same transaction
+    let logs = database
+      .find_audit_logs(
+        Some(AccountBusiness::GenshinImpact),
+        Some(100_000_000),
+        10,
+        0,
+      )
+      .await
+      .unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].operation, AuditOperation::FullUpdate);
+    assert_eq!(logs[0].counts, Some(report.counts()));
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_saver_duplicates() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_saver_duplicates.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let deleted = vec![new_record(0)];
+    GachaRecordSaver::new(
+      &deleted[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    // Deleted and saved again, with the duplicate ids in one batch
+    database
+      .delete_gacha_records(AccountBusiness::GenshinImpact, 100_000_000)
+      .await
+      .unwrap();
+
+    let mut records = vec![new_record(0), new_record(0), new_record(1)];
+    records[0].item_name = "Harbinger of Dawn".into();
+
+    let mut report = GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Update,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    assert_eq!(report.inserted, keys_of(0..2));
+    assert_eq!(
+      report.rejected,
+      vec![GachaRecordRejected {
+        key: GachaRecordKey::from(&records[1]),
+        reason: GachaRecordRejectReason::Duplicate,
+      }]
+    );
+
+    // The saver and the reconciliation keep the same duplicate
+    report.reconcile_deleted(deleted, &records);
+    assert_eq!(report.inserted, keys_of(1..2));
+    assert_eq!(report.updated.len(), 1);
+    assert_eq!(report.updated[0].fields[0].new, "Harbinger of Dawn");
+
+    let saved = database
+      .find_gacha_records(AccountBusiness::GenshinImpact, 100_000_000)
+      .await
+      .unwrap();
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[0].item_name, "Harbinger of Dawn");
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  // Benchmark of the batched against the previous per-record path.
+  // cargo test --release bench_saver -- --ignored --nocapture
+  #[tokio::test]
+  #[ignore = "benchmark"]
+  async fn bench_saver() {
+    const RECORDS: u64 = 50_000;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let records = (0..RECORDS).map(new_record).collect::<Vec<_>>();
+
+    for on_conflict in [
+      GachaRecordSaveOnConflict::Nothing,
+      GachaRecordSaveOnConflict::Update,
+    ] {
+      let database = Database::new_with(temp_dir.path().join(format!("{on_conflict:?}.db")))
+        .await
+        .unwrap();
+      database.apply_migrations().await.unwrap();
+
+      // Per record, the previous path
+      let start = Instant::now();
+      let mut txn = database.inner.begin().await.unwrap();
+      for record in &records {
+        on_conflict
+          .as_query(record)
+          .execute(&mut *txn)
+          .await
+          .unwrap();
+      }
+      txn.commit().await.unwrap();
+      let per_record = start.elapsed();
+
+      database
+        .delete_gacha_records(AccountBusiness::GenshinImpact, 100_000_000)
+        .await
+        .unwrap();
+
+      let start = Instant::now();
+      GachaRecordSaver::new(&records[..], on_conflict, Option::<fn(u64)>::None)
+        .save(&database)
+        .await
+        .unwrap();
+      let batched = start.elapsed();
+
+      eprintln!(
+        "{on_conflict:?}: {RECORDS} records, per record: {per_record:?}, batched: {batched:?}"
+      );
+      database.close().await;
+    }
+
+    temp_dir.close().unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_query() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_query.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let mut records = (0..10)
+      .map(|index| {
+        let mut record = new_record(index);
+        record.gacha_type = if index % 2 == 0 { 301 } else { 302 };
+        record.rank_type = 3 + (index % 3) as u32;
+        record.time += time::Duration::hours(index as i64);
+        record
+      })
+      .collect::<Vec<_>>();
+
+    // Same instant as 2023-01-01 04:00:00 +8
+    records[4].time = datetime!(2022-12-31 20:00:00 UTC);
+    records[5].properties = Some(JsonProperties::from_iter([(
+      GachaRecord::KEY_IS_UP.into(),
+      "1".into(),
+    )]));
+    records[7].item_name = "Harbinger of Dawn".into();
+    records[7].item_id = 11401;
+
+    // Same id in another gacha type
+    let mut same_id = records[0].clone();
+    same_id.gacha_type = 302;
+    records.push(same_id);
+
+    GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    let query = GachaRecordQuery {
+      business: AccountBusiness::GenshinImpact,
+      uid: 100_000_000,
+      gacha_types: None,
+      rank_types: None,
+      item_id: None,
+      item_name: None,
+      time_from: None,
+      time_to: None,
+      is_up: None,
+      after: None,
+      order: GachaRecordQueryOrder::default(),
+      limit: None,
+    };
+    let keys = |page: &GachaRecordPage| {
+      page
+        .records
+        .iter()
+        .map(|record| (record.id.parse::<u64>().unwrap() % 1_000, record.gacha_type))
+        .collect::<Vec<_>>()
+    };
+
+    let page = query.clone().fetch(&database).await.unwrap();
+    assert_eq!(page.records.len(), 11);
+    assert_eq!(page.next, None);
+
+    let page = GachaRecordQuery {
+      gacha_types: Some(vec![301]),
+      ..query.clone()
+    }
+    .fetch(&database)
+    .await
+    .unwrap();
+    assert_eq!(
+      keys(&page),
+      vec![(0, 301), (2, 301), (4, 301), (6, 301), (8, 301)]
+    );
+
+    let page = GachaRecordQuery {
+      rank_types: Some(vec![5]),
+      ..query.clone()
+    }
+    .fetch(&database)
+    .await
+    .unwrap();
+    assert_eq!(keys(&page), vec![(2, 301), (5, 302), (8, 301)]);
+
+    let page = GachaRecordQuery {
+      time_from: Some(datetime!(2023-01-01 02:00:00 +8)),
+      time_to: Some(datetime!(2023-01-01 05:00:00 +8)),
+      ..query.clone()
+    }
+    .fetch(&database)
+    .await
+    .unwrap();
+    assert_eq!(keys(&page), vec![(2, 301), (3, 302), (4, 301)]);
+
+    let page = GachaRecordQuery {
+      is_up: Some(true),
+      ..query.clone()
+    }
+    .fetch(&database)
+    .await
+    .unwrap();
+    assert_eq!(keys(&page), vec![(5, 302)]);
+
+    let page = GachaRecordQuery {
+      is_up: Some(false),
+      ..query.clone()
+    }
+    .fetch(&database)
+    .await
+    .unwrap();
+    assert_eq!(page.records.len(), 10);
+
+    let page = GachaRecordQuery {
+      item_id: Some(11401),
+      ..query.clone()
+    }
+    .fetch(&database)
+    .await
+    .unwrap();
+    assert_eq!(keys(&page), vec![(7, 302)]);
+
+    let page = GachaRecordQuery {
+      item_name: Some("Harbinger of Dawn".into()),
+      ..query.clone()
+    }
+    .fetch(&database)
+    .await
+    .unwrap();
+    assert_eq!(keys(&page), vec![(7, 302)]);
+
+    // Keyset pagination
+    for order in [GachaRecordQueryOrder::Asc, GachaRecordQueryOrder::Desc] {
+      let mut paginated = Vec::new();
+      let mut pages = 0;
+      let mut query = GachaRecordQuery {
+        order,
+        limit: Some(4),
+        ..query.clone()
+      };
+      loop {
+        let page = query.clone().fetch(&database).await.unwrap();
+        paginated.extend(keys(&page));
+        pages += 1;
+
+        match page.next {
+          Some(next) => query.after = Some(next),
+          None => break,
+        }
+      }
+
+      let mut expected = (0..10)
+        .map(|index| (index, if index % 2 == 0 { 301 } else { 302 }))
+        .collect::<Vec<_>>();
+      expected.insert(1, (0, 302));
+      if order == GachaRecordQueryOrder::Desc {
+        expected.reverse();
+      }
+
+      assert_eq!(paginated, expected);
+      assert_eq!(pages, 3);
+    }
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_time_rebaser() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_time_rebaser.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let mut record = GachaRecord {
+      business: AccountBusiness::GenshinImpact,
+      uid: 100_000_000,
+      id: "1000000000000000000".into(),
+      gacha_type: 301,
+      gacha_id: None,
+      rank_type: 5,
+      count: 1,
+      lang: "en-us".into(),
+      time: datetime!(2023-01-01 00:00:00 +8),
+      item_name: "Kamisato Ayaka".into(),
+      item_type: "Character".into(),
+      item_id: 10000002,
+      properties: None,
+    };
+
+    record.set_time_offset_source(GachaRecordTimeOffsetSource::Imported);
+    assert_eq!(
+      record.properties.as_ref().unwrap()[GachaRecord::KEY_TIME_OFFSET_SOURCE],
+      "Imported"
+    );
+
+    // The default is not recorded
+    record.set_time_offset_source(GachaRecordTimeOffsetSource::GameBiz);
+    assert_eq!(record.properties, None);
+
+    GachaRecordSaver::new(
+      &[record],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    let changes =
+      GachaRecordTimeRebaser::new(AccountBusiness::GenshinImpact, 100_000_000, offset!(+9))
+        .rebase(&database)
+        .await
+        .unwrap();
+    assert_eq!(changes, 1);
+
+    let records = database
+      .find_gacha_records(AccountBusiness::GenshinImpact, 100_000_000)
+      .await
+      .unwrap();
+    assert_eq!(records[0].time, datetime!(2023-01-01 00:00:00 +9));
+    assert_eq!(
+      records[0].properties.as_ref().unwrap()[GachaRecord::KEY_TIME_OFFSET_SOURCE],
+      "Rebased"
+    );
+
+    // Already the expected offset
+    let changes =
+      GachaRecordTimeRebaser::new(AccountBusiness::GenshinImpact, 100_000_000, offset!(+9))
+        .rebase(&database)
+        .await
+        .unwrap();
+    assert_eq!(changes, 0);
+
+    // Invalid offset
+    assert!(matches!(
+      GachaRecordTimeRebaser::from_hours(AccountBusiness::GenshinImpact, 100_000_000, 26),
+      Err(GachaRecordRebaseError::InvalidOffset { offset: 26 })
+    ));
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+}
@@ -0,0 +1,507 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Instant;
+
+use hg_metadata::Metadata;
+use hg_metadata::resolve::{EntryNameQuery, resolve_entry_from_name};
+use serde::Serialize;
+use sqlx::{QueryBuilder, Sqlite};
+use time::OffsetDateTime;
+use tracing::{debug, error, info, warn};
+
+use crate::business::metadata::sha1sum;
+use crate::database::schemas::{AccountBusiness, GachaRecord, KeyValuePairQuestioner};
+use crate::database::{Database, DatabaseError};
+
+// Item search.
+//
+// `HG_ITEM_NAMES` holds the known names of the items, `HG_ITEM_NAMES_FTS` is the
+// full-text index of it with the trigram tokenizer. Both are kept in sync by the triggers.
+// (See: migrations/20261020_item_search.sql)
+//
+// | source                        | written by                   |
+// |-------------------------------|------------------------------|
+// | `name` of the records         | Trigger                      |
+// | `properties.i18n` of records  | Trigger                      |
+// | Localized names of metadata   | `sync_item_names`            |
+//
+// The names are kept after the records are deleted, they describe the items.
+// The unknown `item_id` 0 of the records has no names. (See: `resolve_unknown_item_ids`)
+
+crate::database::schemas::impl_questioner! {
+  ItemName of ItemNameQuestioner,
+
+  "INSERT OR IGNORE INTO `HG_ITEM_NAMES` (`business`, `item_id`, `lang`, `name`) VALUES (?, ?, ?, ?);"
+    = create_item_name {
+        business: AccountBusiness,
+        item_id: u32,
+        lang: &str,
+        name: &str
+      }: execute -> u64,
+
+  "SELECT DISTINCT `business`, `item_id` FROM `HG_GACHA_RECORDS`;"
+    = find_record_items {}: fetch_all -> Vec<(AccountBusiness, u32)>,
+
+  "SELECT `business`, `lang`, `name`, `rank_type`, `item_type` FROM `HG_GACHA_RECORDS` WHERE `item_id` = 0
+    UNION SELECT `business`, `lang`, `name`, `rank_type`, `item_type` FROM `HG_GACHA_RECORDS_DELETED` WHERE `item_id` = 0;"
+    = find_unknown_items {}: fetch_all -> Vec<(AccountBusiness, String, String, u32, String)>,
+
+  "UPDATE `HG_GACHA_RECORDS` SET `item_id` = ?
+    WHERE `item_id` = 0 AND `business` = ? AND `lang` = ? AND `name` = ? AND `rank_type` = ? AND `item_type` = ?;"
+    = update_unknown_item_id {
+        item_id: u32,
+        business: AccountBusiness,
+        lang: &str,
+        name: &str,
+        rank_type: u32,
+        item_type: &str
+      }: execute -> u64,
+
+  "UPDATE `HG_GACHA_RECORDS_DELETED` SET `item_id` = ?
+    WHERE `item_id` = 0 AND `business` = ? AND `lang` = ? AND `name` = ? AND `rank_type` = ? AND `item_type` = ?;"
+    = update_deleted_unknown_item_id {
+        item_id: u32,
+        business: AccountBusiness,
+        lang: &str,
+        name: &str,
+        rank_type: u32,
+        item_type: &str
+      }: execute -> u64,
+}
+
+/// The search query of the user input, each word is matched as an infix of the names.
+/// The trigram index only matches the words of 3 characters or more,
+/// the shorter are matched by `LIKE` instead. (e.g. The single CJK character)
+/// e.g. `Kamisato 绫华` -> MATCH `"Kamisato"` AND LIKE `%绫华%`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemSearchQuery {
+  /// The FTS5 query of the words of 3 characters or more.
+  fts: Option<String>,
+  /// The `LIKE` patterns of the shorter words.
+  likes: Vec<String>,
+}
+
+impl ItemSearchQuery {
+  const TRIGRAM: usize = 3;
+
+  /// Returns `None` if there are no words.
+  pub fn new(input: &str) -> Option<Self> {
+    let (words, shorts): (Vec<_>, Vec<_>) = input
+      .split_whitespace()
+      .partition(|word| word.chars().count() >= Self::TRIGRAM);
+
+    if words.is_empty() && shorts.is_empty() {
+      return None;
+    }
+
+    let fts = (!words.is_empty()).then(|| {
+      words
+        .iter()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+    });
+
+    let likes = shorts
+      .iter()
+      .map(|word| {
+        let word = word
+          .replace('\\', "\\\\")
+          .replace('%', "\\%")
+          .replace('_', "\\_");
+        format!("%{word}%")
+      })
+      .collect();
+
+    Some(Self { fts, likes })
+  }
+
+  fn build(&self) -> QueryBuilder<'_, Sqlite> {
+    let mut builder = QueryBuilder::new(
+      "SELECT * FROM `HG_GACHA_RECORDS` WHERE (`business`, `item_id`) IN (
+        SELECT `business`, `item_id` FROM `HG_ITEM_NAMES` WHERE `id` IN (
+          SELECT `rowid` FROM `HG_ITEM_NAMES_FTS` WHERE 1",
+    );
+
+    if let Some(fts) = &self.fts {
+      builder
+        .push(" AND `HG_ITEM_NAMES_FTS` MATCH ")
+        .push_bind(fts.as_str());
+    }
+
+    for like in &self.likes {
+      builder
+        .push(" AND `name` LIKE ")
+        .push_bind(like.as_str())
+        .push(" ESCAPE '\\'");
+    }
+
+    builder.push(")) ORDER BY `business` ASC, `uid` ASC, `id` ASC, `gacha_type` ASC");
+    builder
+  }
+
+  #[tracing::instrument(skip(database))]
+  pub async fn fetch(&self, database: &Database) -> Result<Vec<GachaRecord>, DatabaseError> {
+    Ok(
+      self
+        .build()
+        .build_query_as::<GachaRecord>()
+        .fetch_all(&database.inner)
+        .await?,
+    )
+  }
+}
+
+/// The matched records of an account.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GachaRecordSearchResult {
+  pub business: AccountBusiness,
+  pub uid: u32,
+  pub records: Vec<GachaRecord>,
+}
+
+/// The digest of the last synced names. (See: `sync_item_names`)
+pub const KEY_ITEM_NAMES_SYNCED: &str = "HG_ITEM_NAMES_SYNCED";
+
+/// Write the localized names of the metadata for the items of the records.
+/// After the metadata is updated or the records are saved, not on every search.
+///
+/// Only written when the names differ from the last sync,
+/// either the metadata or the items of the records have changed.
+#[tracing::instrument(skip_all)]
+pub async fn sync_item_names(
+  database: &Database,
+  metadata: &dyn Metadata,
+) -> Result<u64, DatabaseError> {
+  let start = Instant::now();
+  let items = database.find_record_items().await?;
+
+  let mut names = Vec::new();
+  for (business, item_id) in items {
+    let Some(locales) = metadata.locales(business as _) else {
+      continue;
+    };
+
+    for locale in locales {
+      if let Some(entry) = locale.entry_from_id(item_id) {
+        names.push((business, item_id, entry.locale, entry.item_name));
+      }
+    }
+  }
+
+  // The locales are not ordered
+  names.sort_unstable();
+
+  let mut digest = String::new();
+  for (business, item_id, lang, name) in &names {
+    let _ = writeln!(digest, "{}\t{item_id}\t{lang}\t{name}", *business as u8);
+  }
+  let digest = sha1sum(digest.as_bytes());
+
+  if database
+    .find_kv_pair(KEY_ITEM_NAMES_SYNCED)
+    .await?
+    .is_some_and(|synced| synced.val == digest)
+  {
+    debug!(message = "Item names are up to date", elapsed = ?start.elapsed());
+    return Ok(0);
+  }
+
+  let mut txn = database.inner.begin().await?;
+  let mut changes = 0;
+
+  for (business, item_id, lang, name) in names {
+    match (&mut *txn)
+      .create_item_name(business, item_id, lang, name)
+      .await
+    {
+      Ok(ret) => changes += ret,
+      Err(e) => {
+        error!(message = "Failed to sync item name", ?business, item_id, ?e);
+        txn.rollback().await?;
+        return Err(e);
+      }
+    }
+  }
+
+  if let Err(e) = (&mut *txn)
+    .upsert_kv_pair(
+      KEY_ITEM_NAMES_SYNCED,
+      &digest,
+      Some(OffsetDateTime::now_utc()),
+    )
+    .await
+  {
+    error!(message = "Failed to record the synced item names", ?e);
+    txn.rollback().await?;
+    return Err(e);
+  }
+
+  txn.commit().await?;
+
+  info!(
+    message = "Item names synced",
+    elapsed = ?start.elapsed(),
+    changes,
+  );
+
+  Ok(changes)
+}
+
+/// Resolve the unknown `item_id` 0 of the records and the deleted by the item names
+/// against the metadata. The unresolved are kept unknown, try again with the newer metadata.
+#[tracing::instrument(skip_all)]
+pub async fn resolve_unknown_item_ids(
+  database: &Database,
+  metadata: &dyn Metadata,
+) -> Result<u64, DatabaseError> {
+  let start = Instant::now();
+  let items = database.find_unknown_items().await?;
+  if items.is_empty() {
+    return Ok(0);
+  }
+
+  let mut txn = database.inner.begin().await?;
+  let mut changes = 0;
+
+  for (business, lang, name, rank_type, item_type) in items {
+    let query = EntryNameQuery {
+      lang: &lang,
+      item_name: &name,
+      rank_type: u8::try_from(rank_type).ok(),
+      item_type: Some(item_type.as_str()).filter(|s| !s.is_empty()),
+    };
+
+    let item_id = match resolve_entry_from_name(metadata, business as _, query) {
+      Ok(resolved) => resolved.entry.item_id,
+      Err(err) => {
+        warn!(
+          message = "Unknown item id is unresolved",
+          ?business,
+          ?lang,
+          ?name,
+          ?err
+        );
+        continue;
+      }
+    };
+
+    changes += (&mut *txn)
+      .update_unknown_item_id(item_id, business, &lang, &name, rank_type, &item_type)
+      .await?;
+    changes += (&mut *txn)
+      .update_deleted_unknown_item_id(item_id, business, &lang, &name, rank_type, &item_type)
+      .await?;
+  }
+
+  txn.commit().await?;
+
+  info!(
+    message = "Unknown item ids resolved",
+    elapsed = ?start.elapsed(),
+    changes,
+  );
+
+  Ok(changes)
+}
+
+/// Search the records of all accounts by the item names in any lang,
+/// grouped by the account.
+#[tracing::instrument(skip(database))]
+pub async fn search_gacha_records(
+  database: &Database,
+  input: &str,
+) -> Result<Vec<GachaRecordSearchResult>, DatabaseError> {
+  let Some(query) = ItemSearchQuery::new(input) else {
+    return Ok(Vec::new());
+  };
+
+  let records = query.fetch(database).await?;
+
+  let mut groups = BTreeMap::<(u8, u32), GachaRecordSearchResult>::new();
+  for record in records {
+    groups
+      .entry((record.business as u8, record.uid))
+      .or_insert_with(|| GachaRecordSearchResult {
+        business: record.business,
+        uid: record.uid,
+        records: Vec::new(),
+      })
+      .records
+      .push(record);
+  }
+
+  Ok(groups.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use time::macros::datetime;
+
+  use super::*;
+  use crate::database::schemas::{
+    GachaRecordQuestioner, GachaRecordSaveOnConflict, GachaRecordSaver, JsonProperties,
+  };
+
+  #[test]
+  fn test_search_query() {
+    assert_eq!(ItemSearchQuery::new(""), None);
+    assert_eq!(ItemSearchQuery::new("  "), None);
+    assert_eq!(
+      ItemSearchQuery::new(" Kamisato  Aya "),
+      Some(ItemSearchQuery {
+        fts: Some(r#""Kamisato" "Aya""#.into()),
+        likes: vec![],
+      })
+    );
+    assert_eq!(
+      ItemSearchQuery::new(r#"a"b OR"#),
+      Some(ItemSearchQuery {
+        fts: Some(r#""a""b""#.into()),
+        likes: vec!["%OR%".into()],
+      })
+    );
+    assert_eq!(
+      ItemSearchQuery::new("绫华 5_%"),
+      Some(ItemSearchQuery {
+        fts: None,
+        likes: vec!["%绫华%".into(), r"%5\_\%%".into()],
+      })
+    );
+  }
+
+  async fn find_item_names(database: &Database, item_id: u32) -> Vec<(String, String)> {
+    sqlx::query_as(
+      "SELECT `lang`, `name` FROM `HG_ITEM_NAMES` WHERE `business` = ? AND `item_id` = ? ORDER BY `id` ASC;",
+    )
+    .bind(AccountBusiness::GenshinImpact)
+    .bind(item_id)
+    .fetch_all(&database.inner)
+    .await
+    .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_item_names() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_item_names.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let record = |uid: u32, id: &str, lang: &str, item_name: &str, item_id: u32| GachaRecord {
+      business: AccountBusiness::GenshinImpact,
+      uid,
+      id: id.into(),
+      gacha_type: 301,
+      gacha_id: None,
+      rank_type: 3,
+      count: 1,
+      lang: lang.into(),
+      time: datetime!(2023-01-01 00:00:00 +8),
+      item_name: item_name.into(),
+      item_type: "Weapon".into(),
+      item_id,
+      properties: None,
+    };
+
+    let mut cool_steel = record(
+      100_000_000,
+      "1000000000000000000",
+      "en-us",
+      "Cool Steel",
+      11301,
+    );
+    cool_steel.properties = Some(JsonProperties::from_iter([(
+      GachaRecord::KEY_I18N.into(),
+      serde_json::json!({
+        "zh-cn": {
+          "item_name": "冷刃",
+          "item_type": "武器",
+        }
+      }),
+    )]));
+
+    let records = vec![
+      cool_steel,
+      record(
+        100_000_000,
+        "1000000000000000001",
+        "en-us",
+        "Harbinger of Dawn",
+        11302,
+      ),
+      record(100_000_001, "1000000000000000002", "zh-cn", "冷刃", 11301),
+    ];
+
+    GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    // Written by the triggers
+    assert_eq!(
+      find_item_names(&database, 11301).await,
+      vec![
+        ("en-us".into(), "Cool Steel".into()),
+        ("zh-cn".into(), "冷刃".into()),
+      ]
+    );
+
+    let search = |input: &'static str| {
+      let database = &database;
+      async move {
+        ItemSearchQuery::new(input)
+          .unwrap()
+          .fetch(database)
+          .await
+          .unwrap()
+          .into_iter()
+          .map(|record| (record.uid, record.item_id))
+          .collect::<Vec<_>>()
+      }
+    };
+
+    // In any lang, with infix and diacritics
+    assert_eq!(
+      search("cool").await,
+      vec![(100_000_000, 11301), (100_000_001, 11301)]
+    );
+    assert_eq!(
+      search("冷").await,
+      vec![(100_000_000, 11301), (100_000_001, 11301)]
+    );
+    assert_eq!(search("harb DAWN").await, vec![(100_000_000, 11302)]);
+    assert_eq!(search("Hárbinger").await, vec![(100_000_000, 11302)]);
+    assert_eq!(search("Ayaka").await, vec![]);
+
+    // Like the metadata
+    database
+      .create_item_name(AccountBusiness::GenshinImpact, 11302, "ja-jp", "黎明の神剣")
+      .await
+      .unwrap();
+    assert_eq!(search("黎明").await, vec![(100_000_000, 11302)]);
+
+    // Infix of the names without the word boundaries
+    assert_eq!(search("明の神").await, vec![(100_000_000, 11302)]);
+    assert_eq!(search("神剣").await, vec![(100_000_000, 11302)]);
+    assert_eq!(search("binger").await, vec![(100_000_000, 11302)]);
+
+    // Kept after deleted
+    database
+      .delete_gacha_records(AccountBusiness::GenshinImpact, 100_000_000)
+      .await
+      .unwrap();
+    assert_eq!(search("cool").await, vec![(100_000_001, 11301)]);
+    assert_eq!(search("dawn").await, vec![]);
+    assert_eq!(find_item_names(&database, 11302).await.len(), 2);
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+}
@@ -11,9 +11,9 @@ macro_rules! impl_questioner {
   };
 
   (@inner, execute, $sql:expr, $($arg_n:ident $(=> $transform:expr)?),* ; $result:ty, $db:expr) => {{
-    let mut query = sqlx::query($sql);
+    let query = sqlx::query($sql);
     $(
-      query = impl_questioner!(@bind_param query, $arg_n $(=> $transform)?);
+      let query = impl_questioner!(@bind_param query, $arg_n $(=> $transform)?);
     )*
     query
       .execute($db)
@@ -23,9 +23,9 @@ macro_rules! impl_questioner {
   }};
 
   (@inner, $operation:ident, $sql:expr, $($arg_n:ident $(=> $transform:expr)?),* ; $result:ty, $db:expr) => {{
-    let mut query = sqlx::query_as($sql);
+    let query = sqlx::query_as($sql);
     $(
-      query = impl_questioner!(@bind_param query, $arg_n $(=> $transform)?);
+      let query = impl_questioner!(@bind_param query, $arg_n $(=> $transform)?);
     )*
     query
       .$operation($db)
@@ -95,6 +95,12 @@ macro_rules! impl_questioner_with_handlers {
         $($arg_n:ident: $arg_t:ty $(=> $transform:expr)?),*
       }: $operation:ident -> $result:ty,
     )*
+    $(
+      // Other handlers that are not a single statement
+      handlers {
+        $($item:item)*
+      }
+    )?
   ) => {
     crate::database::schemas::impl_questioner! {
       $entity of $questioner,
@@ -116,6 +122,8 @@ macro_rules! impl_questioner_with_handlers {
           } -> $result
         );
       )*
+
+      $($($item)*)?
     }
   };
 
@@ -146,11 +154,17 @@ macro_rules! impl_questioner_with_handlers {
 }
 
 mod account;
+mod audit_log;
 mod gacha_record;
+mod item_name;
 mod key_value_pair;
+mod recycle_bin;
 mod shared;
 
 pub use account::*;
+pub use audit_log::*;
 pub use gacha_record::*;
+pub use item_name::*;
 pub use key_value_pair::*;
+pub use recycle_bin::*;
 pub use shared::*;
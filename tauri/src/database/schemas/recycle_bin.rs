@@ -0,0 +1,354 @@
+use std::time::Instant;
+
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{FromRow, Row};
+use time::OffsetDateTime;
+use time::serde::rfc3339;
+use tracing::{error, info, warn};
+
+use crate::database::schemas::{
+  Account, AccountBusiness, GachaRecord, KeyValuePair, KeyValuePairQuestioner,
+};
+use crate::database::{Database, DatabaseError};
+
+// Recycle bin.
+//
+// The rows deleted from `HG_ACCOUNTS` and `HG_GACHA_RECORDS` are moved to
+// `HG_ACCOUNTS_DELETED` and `HG_GACHA_RECORDS_DELETED` by the triggers,
+// with the `deleted_at` time. (See: migrations/20261018_recycle_bin.sql)
+//
+// They are kept until restored, purged or expired. (See: `purge_expired`)
+// Inserting the same row again removes it from the recycle bin.
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedAccount {
+  #[serde(flatten)]
+  pub account: Account,
+  #[serde(with = "rfc3339")]
+  pub deleted_at: OffsetDateTime,
+}
+
+impl<'r> FromRow<'r, SqliteRow> for DeletedAccount {
+  fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+    Ok(Self {
+      account: Account::from_row(row)?,
+      deleted_at: row.try_get("deleted_at")?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedGachaRecord {
+  #[serde(flatten)]
+  pub record: GachaRecord,
+  #[serde(with = "rfc3339")]
+  pub deleted_at: OffsetDateTime,
+}
+
+impl<'r> FromRow<'r, SqliteRow> for DeletedGachaRecord {
+  fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+    Ok(Self {
+      record: GachaRecord::from_row(row)?,
+      deleted_at: row.try_get("deleted_at")?,
+    })
+  }
+}
+
+/// The deleted gacha records of an account.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedGachaRecords {
+  pub business: AccountBusiness,
+  pub uid: u32,
+  pub records: u32,
+  /// The latest deleted time.
+  #[serde(with = "rfc3339")]
+  pub deleted_at: OffsetDateTime,
+}
+
+impl<'r> FromRow<'r, SqliteRow> for DeletedGachaRecords {
+  fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+    Ok(Self {
+      business: row.try_get("business")?,
+      uid: row.try_get("uid")?,
+      records: row.try_get("records")?,
+      deleted_at: row.try_get("deleted_at")?,
+    })
+  }
+}
+
+impl_questioner_with_handlers! {
+  #[recycle_bin_handlers]
+  DeletedAccount of RecycleBinQuestioner,
+
+  #[database_find_deleted_accounts]
+  "SELECT * FROM `HG_ACCOUNTS_DELETED` ORDER BY `deleted_at` DESC;"
+    = find_deleted_accounts {}: fetch_all -> Vec<DeletedAccount>,
+
+  #[database_find_deleted_gacha_records_groups]
+  "SELECT `business`, `uid`, COUNT(*) AS `records`, MAX(`deleted_at`) AS `deleted_at`
+    FROM `HG_GACHA_RECORDS_DELETED` GROUP BY `business`, `uid` ORDER BY `deleted_at` DESC;"
+    = find_deleted_gacha_records_groups {}: fetch_all -> Vec<DeletedGachaRecords>,
+
+  #[database_find_deleted_gacha_records]
+  "SELECT * FROM `HG_GACHA_RECORDS_DELETED` WHERE `business` = ? AND `uid` = ? ORDER BY `id` ASC;"
+    = find_deleted_gacha_records {
+        business: AccountBusiness,
+        uid: u32
+      }: fetch_all -> Vec<DeletedGachaRecord>,
+
+  // The existing account is kept, returns `None`.
+  #[database_restore_account]
+  "INSERT OR IGNORE INTO `HG_ACCOUNTS` (`business`, `uid`, `data_folder`, `properties`)
+    SELECT `business`, `uid`, `data_folder`, `properties` FROM `HG_ACCOUNTS_DELETED`
+    WHERE `business` = ? AND `uid` = ? RETURNING *;"
+    = restore_account { business: AccountBusiness, uid: u32 }: fetch_optional -> Option<Account>,
+
+  // The existing records are kept and stay in the recycle bin.
+  #[database_restore_gacha_records]
+  "INSERT OR IGNORE INTO `HG_GACHA_RECORDS` (
+      `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+      `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`
+    )
+    SELECT
+      `business`, `uid`, `id`, `gacha_type`, `gacha_id`, `rank_type`,
+      `count`, `time`, `lang`, `name`, `item_type`, `item_id`, `properties`
+    FROM `HG_GACHA_RECORDS_DELETED` WHERE `business` = ? AND `uid` = ?;"
+    = restore_gacha_records { business: AccountBusiness, uid: u32 }: execute -> u64,
+
+  #[database_purge_deleted_account]
+  "DELETE FROM `HG_ACCOUNTS_DELETED` WHERE `business` = ? AND `uid` = ? RETURNING *;"
+    = purge_deleted_account {
+        business: AccountBusiness,
+        uid: u32
+      }: fetch_optional -> Option<DeletedAccount>,
+
+  #[database_purge_deleted_gacha_records]
+  "DELETE FROM `HG_GACHA_RECORDS_DELETED` WHERE `business` = ? AND `uid` = ?;"
+    = purge_deleted_gacha_records { business: AccountBusiness, uid: u32 }: execute -> u64,
+
+  "DELETE FROM `HG_ACCOUNTS_DELETED` WHERE `deleted_at` < DATETIME('now', '-' || ? || ' days');"
+    = purge_expired_accounts { retention_days: u32 }: execute -> u64,
+
+  "DELETE FROM `HG_GACHA_RECORDS_DELETED` WHERE `deleted_at` < DATETIME('now', '-' || ? || ' days');"
+    = purge_expired_gacha_records { retention_days: u32 }: execute -> u64,
+}
+
+/// The retention period of the recycle bin in days. (`HG_KVS`)
+pub const KEY_RECYCLE_BIN_RETENTION_DAYS: &str = "HG_RECYCLE_BIN_RETENTION_DAYS";
+
+/// The default retention period of the recycle bin in days.
+pub const DEFAULT_RECYCLE_BIN_RETENTION_DAYS: u32 = 30;
+
+/// The retention period in days of the `key` in `HG_KVS`, or the `default`.
+pub(crate) async fn retention_days(
+  database: &Database,
+  key: &str,
+  default: u32,
+) -> Result<u32, DatabaseError> {
+  let Some(KeyValuePair { val, .. }) = database.find_kv_pair(key).await? else {
+    return Ok(default);
+  };
+
+  Ok(val.trim().parse().unwrap_or_else(|_| {
+    warn!(
+      message = "Invalid retention days, use default",
+      ?key,
+      ?val,
+      default
+    );
+    default
+  }))
+}
+
+/// Purge the rows deleted longer than the retention period from the recycle bin.
+#[tracing::instrument(skip_all)]
+pub async fn purge_expired(database: &Database) -> Result<u64, DatabaseError> {
+  let retention_days = retention_days(
+    database,
+    KEY_RECYCLE_BIN_RETENTION_DAYS,
+    DEFAULT_RECYCLE_BIN_RETENTION_DAYS,
+  )
+  .await?;
+
+  let start = Instant::now();
+  let mut txn = database.inner.begin().await?;
+
+  let ret = async {
+    let accounts = (&mut *txn).purge_expired_accounts(retention_days).await?;
+    let records = (&mut *txn)
+      .purge_expired_gacha_records(retention_days)
+      .await?;
+    Ok::<_, DatabaseError>(accounts + records)
+  }
+  .await;
+
+  let purged = match ret {
+    Ok(purged) => purged,
+    Err(e) => {
+      error!(message = "Failed to purge expired recycle bin", ?e);
+      txn.rollback().await?;
+      return Err(e);
+    }
+  };
+
+  txn.commit().await?;
+
+  info!(
+    message = "Purged expired recycle bin",
+    elapsed = ?start.elapsed(),
+    retention_days,
+    purged,
+  );
+
+  Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+  use time::macros::datetime;
+
+  use super::*;
+  use crate::database::schemas::{
+    AccountQuestioner, GachaRecordQuestioner, GachaRecordSaveOnConflict, GachaRecordSaver,
+  };
+
+  #[tokio::test]
+  async fn test_recycle_bin() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database = Database::new_with(temp_dir.path().join("test_recycle_bin.db"))
+      .await
+      .unwrap();
+    database.apply_migrations().await.unwrap();
+
+    let business = AccountBusiness::GenshinImpact;
+    let uid = 100_000_000;
+    let account = database
+      .create_account(business, uid, "data_folder", None)
+      .await
+      .unwrap();
+
+    let records = (0..3)
+      .map(|index| GachaRecord {
+        business,
+        uid,
+        id: (1_000_000_000_000_000_000u64 + index).to_string(),
+        gacha_type: 301,
+        gacha_id: None,
+        rank_type: 3,
+        count: 1,
+        lang: "en-us".into(),
+        time: datetime!(2023-01-01 00:00:00 +8),
+        item_name: "Cool Steel".into(),
+        item_type: "Weapon".into(),
+        item_id: 11301,
+        properties: None,
+      })
+      .collect::<Vec<_>>();
+
+    GachaRecordSaver::new(
+      &records[..],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    // Delete
+    database.delete_account(business, uid).await.unwrap();
+    database.delete_gacha_records(business, uid).await.unwrap();
+
+    let deleted_accounts = database.find_deleted_accounts().await.unwrap();
+    assert_eq!(deleted_accounts.len(), 1);
+    assert_eq!(deleted_accounts[0].account, account);
+
+    let groups = database.find_deleted_gacha_records_groups().await.unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!((groups[0].business, groups[0].uid), (business, uid));
+    assert_eq!(groups[0].records, 3);
+
+    let deleted_records = database
+      .find_deleted_gacha_records(business, uid)
+      .await
+      .unwrap();
+    assert_eq!(
+      deleted_records
+        .into_iter()
+        .map(|deleted| deleted.record)
+        .collect::<Vec<_>>(),
+      records
+    );
+
+    // Saved again, no longer deleted
+    GachaRecordSaver::new(
+      &records[..1],
+      GachaRecordSaveOnConflict::Nothing,
+      Option::<fn(u64)>::None,
+    )
+    .save(&database)
+    .await
+    .unwrap();
+
+    assert_eq!(
+      database
+        .find_deleted_gacha_records(business, uid)
+        .await
+        .unwrap()
+        .len(),
+      2
+    );
+
+    // Restore
+    assert_eq!(
+      database.restore_account(business, uid).await.unwrap(),
+      Some(account.clone())
+    );
+    assert_eq!(
+      database.restore_gacha_records(business, uid).await.unwrap(),
+      2
+    );
+    assert!(database.find_deleted_accounts().await.unwrap().is_empty());
+    assert!(
+      database
+        .find_deleted_gacha_records_groups()
+        .await
+        .unwrap()
+        .is_empty()
+    );
+    assert_eq!(
+      database.find_gacha_records(business, uid).await.unwrap(),
+      records
+    );
+
+    // Purge
+    database.delete_account(business, uid).await.unwrap();
+    assert_eq!(
+      database
+        .purge_deleted_account(business, uid)
+        .await
+        .unwrap()
+        .map(|deleted| deleted.account),
+      Some(account)
+    );
+    assert_eq!(database.restore_account(business, uid).await.unwrap(), None);
+
+    // Expired
+    database.delete_gacha_records(business, uid).await.unwrap();
+    assert_eq!(purge_expired(&database).await.unwrap(), 0);
+
+    sqlx::query(
+      "UPDATE `HG_GACHA_RECORDS_DELETED` SET `deleted_at` = DATETIME('now', '-31 days');",
+    )
+    .execute(&database.inner)
+    .await
+    .unwrap();
+    assert_eq!(purge_expired(&database).await.unwrap(), 3);
+
+    database.close().await;
+    temp_dir.close().unwrap();
+  }
+}
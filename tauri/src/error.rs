@@ -68,12 +68,52 @@ impl Serialize for dyn ErrorDetails + Send + 'static {
 // region: Compat
 
 pub mod compat {
+  use hg_game_biz::{UidError, UidInvalidReason};
   use hg_url_finder::dirty::DirtyGachaUrlError;
   use hg_url_finder::parse::ParsedGachaUrlError;
   use hg_url_scraper::requester::GachaUrlRequestError;
 
   use super::*;
 
+  impl ErrorDetails for UidError {
+    fn name(&self) -> &'static str {
+      stringify!(UidError)
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+      use serde_json::json;
+
+      let mut details = match self.reason {
+        UidInvalidReason::Zero => json!({
+          "kind": stringify!(Zero),
+        }),
+        UidInvalidReason::DigitCount { digits } => json!({
+          "kind": stringify!(DigitCount),
+          "digits": digits,
+        }),
+        UidInvalidReason::UnknownRegionDigit { digit } => json!({
+          "kind": stringify!(UnknownRegionDigit),
+          "digit": digit,
+        }),
+      };
+
+      details["value"] = self.value.into();
+      details["candidates"] = self
+        .candidates
+        .iter()
+        .map(|biz| {
+          json!({
+            "gameBiz": biz.codename(),
+            "region": biz.region(),
+            "server": biz.server().as_str(),
+          })
+        })
+        .collect();
+
+      Some(details)
+    }
+  }
+
   impl ErrorDetails for DirtyGachaUrlError {
     fn name(&self) -> &'static str {
       stringify!(DirtyGachaUrlError)
@@ -161,6 +201,9 @@ pub mod compat {
         Self::ReachedMaxAttempts => json!({
           "kind": stringify!(ReachedMaxAttempts),
         }),
+        Self::Cancelled => json!({
+          "kind": stringify!(Cancelled),
+        }),
       })
     }
   }